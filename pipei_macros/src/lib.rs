@@ -0,0 +1,300 @@
+//! Derive and attribute macros for [pipei](https://docs.rs/pipei).
+//!
+//! Use them through pipei's `macros` feature, which re-exports them; the
+//! generated code refers to items of the `pipei` crate.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Expr, Fields, FieldsNamed, FnArg, ItemFn, LitInt, LitStr, ReturnType, Type};
+
+/// Generates `tap_field_<name>` and `tap_field_<name>_mut` methods for each
+/// named field of a struct.
+///
+/// `value.tap_field_x(f)(args..)` is `value.tap_proj(|v| &v.x, f)(args..)`,
+/// and `tap_field_x_mut` projects to `&mut v.x`. Each method has the
+/// visibility of its field. Fields marked `#[tap_skip]` get no methods.
+///
+/// # Examples
+///
+/// ```rust
+/// use pipei::TapFields;
+///
+/// #[derive(TapFields)]
+/// struct Point {
+///     x: f64,
+///     y: f64,
+///     #[tap_skip]
+///     label: &'static str,
+/// }
+///
+/// fn check_finite(v: &f64) { assert!(v.is_finite()); }
+/// fn scale(v: &mut f64, k: f64) { *v *= k; }
+///
+/// let p = Point { x: 1.0, y: 2.0, label: "p" }
+///     .tap_field_x(check_finite)()
+///     .tap_field_y_mut(scale)(10.0);
+/// assert_eq!((p.x, p.y, p.label), (1.0, 20.0, "p"));
+/// ```
+#[proc_macro_derive(TapFields, attributes(tap_skip))]
+pub fn derive_tap_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    tap_fields(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn tap_fields(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = named_fields(&input, "TapFields")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let methods = fields.named.iter().filter(|field| !field.attrs.iter().any(|a| a.path().is_ident("tap_skip"))).map(|field| {
+        let (vis, ty) = (&field.vis, &field.ty);
+        let ident = field.ident.as_ref().expect("named field");
+        let field_name = ident.unraw().to_string();
+        let tap = format_ident!("tap_field_{}", field_name);
+        let tap_mut = format_ident!("tap_field_{}_mut", field_name);
+        let doc = format!("Like `tap_proj`, projecting `self` to its `{field_name}` field.");
+        let doc_mut = format!("Like `tap_proj`, projecting `self` to its `{field_name}` field mutably.");
+        quote! {
+            #[doc = #doc]
+            #[inline(always)]
+            #vis fn #tap<const ARITY: usize, R, F, Params>(self, f: F) -> F::Curry
+            where
+                F: ::pipei::CurryWith<ARITY, Params, ::pipei::Imm, ::pipei::Proj, Self, fn(&Self) -> &#ty, R>,
+            {
+                let proj: fn(&Self) -> &#ty = |v| &v.#ident;
+                ::pipei::TapWith::<ARITY, ::pipei::Imm>::tap_proj(self, proj, f)
+            }
+
+            #[doc = #doc_mut]
+            #[inline(always)]
+            #vis fn #tap_mut<const ARITY: usize, R, F, Params>(self, f: F) -> F::Curry
+            where
+                F: ::pipei::CurryWith<ARITY, Params, ::pipei::Mut, ::pipei::Proj, Self, fn(&mut Self) -> &mut #ty, R>,
+            {
+                let proj: fn(&mut Self) -> &mut #ty = |v| &mut v.#ident;
+                ::pipei::TapWith::<ARITY, ::pipei::Mut>::tap_proj(self, proj, f)
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+/// Implements `pipei::Validate<Self>` for `pipei::FieldSchema`, checking
+/// each field marked `#[validate(min = .., max = ..)]` against its bounds.
+///
+/// Either bound may be left out; the field must be `PartialOrd` with them.
+/// Fields are checked in declaration order, and the error names the first
+/// one that fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use pipei::{FieldSchema, PipeValidate, Validate};
+///
+/// #[derive(Validate)]
+/// struct Retry {
+///     #[validate(min = 1, max = 10)]
+///     attempts: u32,
+///     #[validate(max = 60)]
+///     timeout_secs: u64,
+/// }
+///
+/// fn total(r: Retry) -> u64 { u64::from(r.attempts) * r.timeout_secs }
+///
+/// assert_eq!(Retry { attempts: 3, timeout_secs: 5 }.pipe_with_schema(FieldSchema, total)(), Ok(15));
+/// let err = Retry { attempts: 0, timeout_secs: 5 }.pipe_with_schema(FieldSchema, total)().unwrap_err();
+/// assert_eq!(err.to_string(), "invalid `attempts`: below minimum");
+/// ```
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    validate(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+fn validate(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = named_fields(&input, "Validate")?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut checks = Vec::new();
+    for field in &fields.named {
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("validate")) {
+            let (mut min, mut max) = (None::<Expr>, None::<Expr>);
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("min") {
+                    min = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("max") {
+                    max = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("expected `min` or `max`"));
+                }
+                Ok(())
+            })?;
+            let range = match (min, max) {
+                (Some(min), Some(max)) => quote!((#min)..=(#max)),
+                (Some(min), None) => quote!((#min)..),
+                (None, Some(max)) => quote!(..=(#max)),
+                (None, None) => return Err(Error::new_spanned(attr, "expected `min`, `max`, or both")),
+            };
+            let ident = field.ident.as_ref().expect("named field");
+            let field_name = ident.unraw().to_string();
+            checks.push(quote! {
+                ::pipei::Validate::validate(&(#range), &value.#ident).map_err(|e| e.with_field(#field_name))?;
+            });
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::pipei::Validate<#name #ty_generics> for ::pipei::FieldSchema #where_clause {
+            fn validate(&self, value: &#name #ty_generics) -> ::core::result::Result<(), ::pipei::ValidationError> {
+                #(#checks)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
+
+/// Generates a unit struct that implements pipei's `Curry` for a function,
+/// so the function can be handed to pipei without wrapping it in a closure.
+///
+/// `#[pipe_derive(arity = N, borrow = "..")]` on `fn process(..)` keeps the
+/// function and adds `ProcessPipe`, whose `curry(value)` returns a closure
+/// over the remaining `N` arguments. `borrow` says how the function takes its
+/// first argument: `"own"` (the default) by value, `"ref"` as `&T`, or
+/// `"mut"` as `&mut T`; the curried value is then a `T` that the closure
+/// owns. `arity` must match the number of remaining arguments.
+///
+/// The generated `Curry` impl names its closure type with `impl Trait`, so
+/// the crate using the attribute must enable
+/// `#![feature(impl_trait_in_assoc_type)]`.
+///
+/// # Examples
+///
+/// ```rust
+/// #![feature(impl_trait_in_assoc_type)]
+/// use pipei::{pipe_derive, Curry};
+///
+/// struct Scale(i32);
+///
+/// #[pipe_derive(arity = 2, borrow = "ref")]
+/// fn affine(s: &Scale, x: i32, offset: i32) -> i32 { s.0 * x + offset }
+///
+/// let line = AffinePipe.curry(Scale(3));
+/// assert_eq!((line(1, 0), line(2, 1)), (3, 7));
+/// ```
+#[proc_macro_attribute]
+pub fn pipe_derive(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut options = PipeDeriveOptions::default();
+    let parser = syn::meta::parser(|meta| options.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(item as ItemFn);
+    pipe_derive_impl(&options, &item).map_or_else(Error::into_compile_error, |stage| quote!(#item #stage)).into()
+}
+
+#[derive(Default)]
+struct PipeDeriveOptions {
+    arity: Option<LitInt>,
+    borrow: Option<LitStr>,
+}
+
+impl PipeDeriveOptions {
+    fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("arity") {
+            self.arity = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("borrow") {
+            self.borrow = Some(meta.value()?.parse()?);
+        } else {
+            return Err(meta.error("expected `arity` or `borrow`"));
+        }
+        Ok(())
+    }
+}
+
+fn pipe_derive_impl(options: &PipeDeriveOptions, item: &ItemFn) -> syn::Result<proc_macro2::TokenStream> {
+    let sig = &item.sig;
+    if let Some(token) = &sig.asyncness {
+        return Err(Error::new_spanned(token, "`pipe_derive` does not support async functions"));
+    }
+
+    let mut inputs = sig.inputs.iter().map(|arg| match arg {
+        FnArg::Typed(arg) => Ok(&*arg.ty),
+        FnArg::Receiver(arg) => Err(Error::new_spanned(arg, "`pipe_derive` needs a free function")),
+    });
+    let first = inputs.next().ok_or_else(|| Error::new_spanned(sig, "`pipe_derive` needs at least one argument"))??;
+    let rest = inputs.collect::<syn::Result<Vec<_>>>()?;
+
+    let arity = match &options.arity {
+        Some(lit) if lit.base10_parse::<usize>()? != rest.len() => {
+            let message = format!("`arity = {lit}`, but `{}` takes {} arguments after the first", sig.ident, rest.len());
+            return Err(Error::new_spanned(lit, message));
+        }
+        _ => rest.len(),
+    };
+
+    let borrow = options.borrow.as_ref().map_or_else(|| String::from("own"), LitStr::value);
+    let (state, a0, call_trait, arg0) = match (borrow.as_str(), first) {
+        ("own", ty) => (quote!(::pipei::Own), ty, quote!(FnOnce), quote!(arg0)),
+        ("ref", Type::Reference(r)) if r.mutability.is_none() => (quote!(::pipei::Imm), &*r.elem, quote!(Fn), quote!(&arg0)),
+        ("mut", Type::Reference(r)) if r.mutability.is_some() => (quote!(::pipei::Mut), &*r.elem, quote!(FnMut), quote!(&mut arg0)),
+        ("ref" | "mut", ty) => {
+            let expected = if borrow == "ref" { "&T" } else { "&mut T" };
+            return Err(Error::new(ty.span(), format!("`borrow = \"{borrow}\"` needs a first argument of type `{expected}`")));
+        }
+        _ => return Err(Error::new_spanned(&options.borrow, "expected `borrow = \"own\"`, `\"ref\"`, or `\"mut\"`")),
+    };
+
+    let output = match &sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let (vis, name) = (&item.vis, &sig.ident);
+    let stage = format_ident!("{}Pipe", upper_camel(&name.unraw().to_string()));
+    let (impl_generics, _, where_clause) = sig.generics.split_for_impl();
+    let params = (0..arity).map(|i| format_ident!("arg{}", i + 1)).collect::<Vec<_>>();
+    let arity = proc_macro2::Literal::usize_unsuffixed(arity);
+    let doc = format!("Pipe stage for [`{name}`]: curries its first argument, returning a closure over the others.");
+    let mutability = (borrow == "mut").then(|| quote!(mut));
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, Default)]
+        #vis struct #stage;
+
+        impl #impl_generics ::pipei::Curry<#arity, (#(#rest,)*), #state, ::pipei::Own, ::pipei::PipeMark, #a0, #output> for #stage #where_clause {
+            type Curry = impl #call_trait(#(#rest),*) -> #output;
+            #[inline(always)]
+            fn curry(self, #mutability arg0: #a0) -> Self::Curry {
+                move |#(#params),*| #name(#arg0, #(#params),*)
+            }
+        }
+    })
+}
+
+fn upper_camel(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or_else(String::new, |c| c.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+/// Returns the named fields of a struct, or an error naming `derive` otherwise.
+fn named_fields<'a>(input: &'a DeriveInput, derive: &str) -> syn::Result<&'a FieldsNamed> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields),
+            _ => Err(Error::new_spanned(&input.ident, format!("`{derive}` needs a struct with named fields"))),
+        },
+        _ => Err(Error::new_spanned(&input.ident, format!("`{derive}` can only be derived for structs"))),
+    }
+}
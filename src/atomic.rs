@@ -0,0 +1,81 @@
+//! Read-modify-write pipes for the `core::sync::atomic` integer types.
+
+use core::sync::atomic::Ordering::{AcqRel, Acquire};
+
+/// Extension trait for piping the value of an atomic through a function.
+///
+/// Both methods run a compare-and-swap loop: `f` may be called more than once
+/// if another thread updates the atomic concurrently, so it should be free of
+/// side effects. Successful updates use [`AcqRel`] ordering and loads use
+/// [`Acquire`].
+pub trait PipeAtomic {
+    /// The type stored in the atomic.
+    type Value;
+
+    /// Atomically replaces the stored value with `f(current)`, retrying on
+    /// contention, and returns the value that was stored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeAtomic;
+    /// use core::sync::atomic::AtomicU32;
+    ///
+    /// let hits = AtomicU32::new(7);
+    /// assert_eq!(hits.pipe_atomic_update(|x| x * 2 + 1), 15);
+    /// ```
+    fn pipe_atomic_update<F: FnMut(Self::Value) -> Self::Value>(&self, f: F) -> Self::Value;
+
+    /// Atomically replaces the stored value with `f(current)` while `f`
+    /// returns `Some`, following the contract of `fetch_update`: returns
+    /// `Ok(previous)` on success, or `Err(current)` if `f` returned `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeAtomic;
+    /// use core::sync::atomic::AtomicU8;
+    ///
+    /// let level = AtomicU8::new(254);
+    /// assert_eq!(level.pipe_atomic_fetch_update(|x| x.checked_add(1)), Ok(254));
+    /// assert_eq!(level.pipe_atomic_fetch_update(|x| x.checked_add(1)), Err(255));
+    /// ```
+    fn pipe_atomic_fetch_update<F: FnMut(Self::Value) -> Option<Self::Value>>(
+        &self,
+        f: F,
+    ) -> Result<Self::Value, Self::Value>;
+}
+
+macro_rules! impl_pipe_atomic {
+    ($($width:literal => [$($Atomic:ident: $T:ty),*]),* $(,)?) => {$($(
+        #[cfg(target_has_atomic = $width)]
+        impl PipeAtomic for core::sync::atomic::$Atomic {
+            type Value = $T;
+
+            #[inline]
+            fn pipe_atomic_update<F: FnMut($T) -> $T>(&self, mut f: F) -> $T {
+                let mut current = self.load(Acquire);
+                loop {
+                    let new = f(current);
+                    match self.compare_exchange_weak(current, new, AcqRel, Acquire) {
+                        Ok(_) => return new,
+                        Err(actual) => current = actual,
+                    }
+                }
+            }
+
+            #[inline]
+            fn pipe_atomic_fetch_update<F: FnMut($T) -> Option<$T>>(&self, f: F) -> Result<$T, $T> {
+                self.fetch_update(AcqRel, Acquire, f)
+            }
+        }
+    )*)*};
+}
+
+impl_pipe_atomic! {
+    "8" => [AtomicBool: bool, AtomicI8: i8, AtomicU8: u8],
+    "16" => [AtomicI16: i16, AtomicU16: u16],
+    "32" => [AtomicI32: i32, AtomicU32: u32],
+    "64" => [AtomicI64: i64, AtomicU64: u64],
+    "ptr" => [AtomicIsize: isize, AtomicUsize: usize],
+}
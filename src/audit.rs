@@ -0,0 +1,100 @@
+//! Pipe stages that append a record of each call to an audit log.
+
+use core::hash::{Hash, Hasher};
+use std::collections::hash_map::DefaultHasher;
+use std::time::SystemTime;
+use std::vec::Vec;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// One recorded call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuditEntry {
+    /// When the call finished.
+    pub timestamp: SystemTime,
+    /// Name of the operation.
+    pub operation: &'static str,
+    /// Hash of the input value, taken before the call.
+    pub value_hash: u64,
+    /// Whether the call returned `Ok`.
+    pub success: bool,
+}
+
+/// An append-only list of [`AuditEntry`] records.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+}
+
+impl AuditLog {
+    /// Creates an empty log.
+    pub const fn new() -> Self {
+        AuditLog { entries: Vec::new() }
+    }
+
+    /// Appends `entry`.
+    pub fn record(&mut self, entry: AuditEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Returns the recorded entries, oldest first.
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+}
+
+/// Extension trait for auditing fallible pipe stages.
+pub trait PipeAudit<const ARITY: usize> {
+    /// Like `pipe` for a fallible `f`, but records an [`AuditEntry`] in `log`
+    /// with the operation name, a hash of `self` taken before the call (using
+    /// [`DefaultHasher`]), and whether `f` returned `Ok`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{AuditLog, PipeAudit};
+    /// fn withdraw(balance: u64, amount: u64) -> Result<u64, &'static str> {
+    ///     balance.checked_sub(amount).ok_or("insufficient funds")
+    /// }
+    ///
+    /// let mut log = AuditLog::new();
+    /// assert_eq!(100.pipe_with_audit_log(&mut log, "withdraw", withdraw)(30), Ok(70));
+    /// assert!(10.pipe_with_audit_log(&mut log, "withdraw", withdraw)(30).is_err());
+    /// let outcomes: Vec<_> = log.entries().iter().map(|e| e.success).collect();
+    /// assert_eq!(outcomes, [true, false]);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_audit_log<'l, R, F, Params>(self, log: &'l mut AuditLog, operation: &'static str, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Audited<'l>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Audited { log, operation })
+    }
+}
+impl<const ARITY: usize, T> PipeAudit<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: hashes the value, runs the call, and records the outcome.
+pub struct Audited<'l> {
+    log: &'l mut AuditLog,
+    operation: &'static str,
+}
+
+impl<A0: Hash, T, E> Around<A0, Result<T, E>> for Audited<'_> {
+    type Output = Result<T, E>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> Result<T, E>>(self, arg0: A0, call: C) -> Self::Output {
+        let mut hasher = DefaultHasher::new();
+        arg0.hash(&mut hasher);
+        let value_hash = hasher.finish();
+        let result = call(arg0);
+        self.log.record(AuditEntry {
+            timestamp: SystemTime::now(),
+            operation: self.operation,
+            value_hash,
+            success: result.is_ok(),
+        });
+        result
+    }
+}
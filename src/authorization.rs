@@ -0,0 +1,120 @@
+//! Pipe stages guarded by a capability check.
+
+use core::fmt;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Error returned when a [`Capability`] refuses an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AuthError {
+    reason: &'static str,
+}
+
+impl AuthError {
+    /// Creates an error with the given reason.
+    #[inline]
+    pub const fn new(reason: &'static str) -> Self {
+        AuthError { reason }
+    }
+
+    /// Returns why the operation was refused.
+    #[inline]
+    pub const fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not authorized: {}", self.reason)
+    }
+}
+
+impl core::error::Error for AuthError {}
+
+/// A set of permissions that decides whether a named operation may run.
+///
+/// The trait is object safe, so heterogeneous capabilities can be passed as
+/// `&dyn Capability`.
+pub trait Capability {
+    /// Returns `Ok(())` if `operation` is permitted.
+    fn permits(&self, operation: &str) -> Result<(), AuthError>;
+}
+
+impl<C: Capability + ?Sized> Capability for &C {
+    #[inline(always)]
+    fn permits(&self, operation: &str) -> Result<(), AuthError> {
+        (**self).permits(operation)
+    }
+}
+
+/// A [`Capability`] that permits every operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PermitAll;
+
+impl Capability for PermitAll {
+    #[inline(always)]
+    fn permits(&self, _operation: &str) -> Result<(), AuthError> {
+        Ok(())
+    }
+}
+
+/// A [`Capability`] that refuses every operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DenyAll;
+
+impl Capability for DenyAll {
+    #[inline(always)]
+    fn permits(&self, _operation: &str) -> Result<(), AuthError> {
+        Err(AuthError::new("all operations denied"))
+    }
+}
+
+/// Extension trait for piping values through authorization checks.
+pub trait PipeAuthorize<const ARITY: usize> {
+    /// Like `pipe`, but first asks `capability` whether `operation` is permitted.
+    /// Returns `Ok(f(self, args..))` if so, and `Err` without calling `f` otherwise.
+    /// `self` is consumed either way.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{AuthError, Capability, PipeAuthorize};
+    /// struct ReadOnly;
+    /// impl Capability for ReadOnly {
+    ///     fn permits(&self, op: &str) -> Result<(), AuthError> {
+    ///         if op == "read" { Ok(()) } else { Err(AuthError::new("read-only")) }
+    ///     }
+    /// }
+    /// fn redact(s: &str, keep: usize) -> String { format!("{}***", &s[..keep]) }
+    ///
+    /// assert_eq!("secret".pipe_with_authorization(&ReadOnly, "read", redact)(2).as_deref(), Ok("se***"));
+    /// assert!("secret".pipe_with_authorization(&ReadOnly, "write", redact)(2).is_err());
+    /// ```
+    #[inline(always)]
+    fn pipe_with_authorization<'o, C, R, F, Params>(self, capability: C, operation: &'o str, f: F) -> F::Curry
+    where
+        C: Capability,
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Authorized<'o, C>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Authorized { capability, operation })
+    }
+}
+impl<const ARITY: usize, T> PipeAuthorize<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call only if the capability permits the operation.
+pub struct Authorized<'o, C> {
+    capability: C,
+    operation: &'o str,
+}
+
+impl<C: Capability, A0, R> Around<A0, R> for Authorized<'_, C> {
+    type Output = Result<R, AuthError>;
+    #[inline(always)]
+    fn around<K: FnOnce(A0) -> R>(self, arg0: A0, call: K) -> Self::Output {
+        self.capability.permits(self.operation)?;
+        Ok(call(arg0))
+    }
+}
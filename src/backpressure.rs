@@ -0,0 +1,173 @@
+//! Pipe stages that publish their input to a bounded channel first.
+
+use std::sync::mpsc::{SendError, SyncSender};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Extension trait for applying backpressure from a bounded channel.
+pub trait PipeBackpressure<const ARITY: usize> {
+    /// Like `pipe`, but first sends a clone of `self` on `tx`, blocking while the
+    /// channel is full, so the pipeline cannot outpace its consumer. Returns
+    /// `Ok(f(self, args..))`, or `Err` without calling `f` if the receiver is gone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBackpressure;
+    /// use std::sync::mpsc::sync_channel;
+    ///
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let (tx, rx) = sync_channel(1);
+    /// assert_eq!(1.pipe_with_sync_backpressure(&tx, add)(2), Ok(3));
+    /// assert_eq!(rx.recv(), Ok(1));
+    ///
+    /// drop(rx);
+    /// assert!(1.pipe_with_sync_backpressure(&tx, add)(2).is_err());
+    /// ```
+    #[inline(always)]
+    fn pipe_with_sync_backpressure<'t, R, F, Params>(self, tx: &'t SyncSender<Self>, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Backpressure<'t, Self>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Backpressure(tx))
+    }
+
+    /// Async version of
+    /// [`pipe_with_sync_backpressure`](PipeBackpressure::pipe_with_sync_backpressure)
+    /// for a tokio channel: the closure over the remaining arguments returns a
+    /// future that sends a clone of `self` on `tx`, waiting while the channel
+    /// is full, and then resolves to `Ok(f(self, args..))`. It resolves to `Err`
+    /// without calling `f` if the receiver is gone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBackpressure;
+    /// use tokio::sync::mpsc::channel;
+    ///
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// let (tx, mut rx) = channel(1);
+    /// assert_eq!(1.pipe_with_backpressure(&tx, add)(2).await, Ok(3));
+    /// assert_eq!(rx.recv().await, Some(1));
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[inline(always)]
+    fn pipe_with_backpressure<R, F, Params>(self, tx: &tokio::sync::mpsc::Sender<Self>, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AsyncBackpressureMark, Self, tokio::sync::mpsc::Sender<Self>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, tx.clone())
+    }
+
+    /// Like [`pipe_with_backpressure`](PipeBackpressure::pipe_with_backpressure),
+    /// but gives up waiting for room in the channel after `timeout`, resolving
+    /// to `Err` without calling `f`. Needs a tokio runtime with the time driver.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBackpressure;
+    /// use std::time::Duration;
+    /// use tokio::sync::mpsc::{channel, error::SendTimeoutError};
+    ///
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+    /// let (tx, _rx) = channel(1);
+    /// let timeout = Duration::from_millis(10);
+    /// assert_eq!(1.pipe_with_backpressure_bounded(&tx, timeout, add)(2).await, Ok(3));
+    /// let full = 2.pipe_with_backpressure_bounded(&tx, timeout, add)(2).await;
+    /// assert!(matches!(full, Err(SendTimeoutError::Timeout(2))));
+    /// # });
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[inline(always)]
+    fn pipe_with_backpressure_bounded<R, F, Params>(
+        self,
+        tx: &tokio::sync::mpsc::Sender<Self>,
+        timeout: Duration,
+        f: F,
+    ) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AsyncBackpressureMark, Self, (tokio::sync::mpsc::Sender<Self>, Duration), R>,
+        Self: Sized,
+    {
+        f.curry_with(self, (tx.clone(), timeout))
+    }
+}
+impl<const ARITY: usize, T> PipeBackpressure<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: sends a clone of the value before the call, blocking while the channel is full.
+pub struct Backpressure<'t, T>(&'t SyncSender<T>);
+
+impl<A0: Clone, R> Around<A0, R> for Backpressure<'_, A0> {
+    type Output = Result<R, SendError<A0>>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        self.0.send(arg0.clone())?;
+        Ok(call(arg0))
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[doc(hidden)]
+/// Marker type: `pipe_with_backpressure` semantics (await room in a tokio channel first).
+pub struct AsyncBackpressureMark;
+
+#[cfg(feature = "tokio")]
+macro_rules! impl_backpressure_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{AsyncBackpressureMark, CurryWith, Duration, Own};
+            #[cfg(feature = $feat)]
+            use core::future::Future;
+            #[cfg(feature = $feat)]
+            use tokio::sync::mpsc::error::{SendError, SendTimeoutError};
+            #[cfg(feature = $feat)]
+            use tokio::sync::mpsc::Sender;
+
+            // --- Await room ---
+            #[cfg(feature = $feat)]
+            impl<F, A0: Clone, $($Params,)* R> CurryWith<$N, $TupleType, Own, AsyncBackpressureMark, A0, Sender<A0>, R> for F
+            where F: FnOnce(A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> impl Future<Output = Result<R, SendError<A0>>>;
+                #[inline(always)] fn curry_with(self, arg0: A0, tx: Sender<A0>) -> Self::Curry {
+                    move |$($Params),*| async move {
+                        tx.send(arg0.clone()).await?;
+                        Ok(self(arg0, $($Params),*))
+                    }
+                }
+            }
+
+            // --- Await room, with a timeout ---
+            #[cfg(feature = $feat)]
+            impl<F, A0: Clone, $($Params,)* R> CurryWith<$N, $TupleType, Own, AsyncBackpressureMark, A0, (Sender<A0>, Duration), R> for F
+            where F: FnOnce(A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> impl Future<Output = Result<R, SendTimeoutError<A0>>>;
+                #[inline(always)] fn curry_with(self, arg0: A0, (tx, timeout): (Sender<A0>, Duration)) -> Self::Curry {
+                    move |$($Params),*| async move {
+                        tx.send_timeout(arg0.clone(), timeout).await?;
+                        Ok(self(arg0, $($Params),*))
+                    }
+                }
+            }
+        };
+    };
+}
+
+#[cfg(feature = "tokio")]
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_backpressure_arity);
+}
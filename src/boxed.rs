@@ -0,0 +1,128 @@
+//! Pipes that return boxed closures, erasing the curried closure's type.
+
+use crate::{Curry, Imm, Own};
+
+/// Extension trait for curried closures that can be stored as trait objects.
+///
+/// The boxed closures are `Fn`, so they can be called any number of times;
+/// the curried value is cloned for each call. Because `Box<dyn Fn>` defaults to
+/// `'static`, the value and the function must not borrow.
+pub trait PipeBoxed<const ARITY: usize> {
+    /// Like `pipe`, but returns `Box<dyn Fn(args..) -> R>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBoxed;
+    /// fn in_range(x: i32, lo: i32, hi: i32) -> bool { lo <= x && x <= hi }
+    ///
+    /// let check: Box<dyn Fn(i32, i32) -> bool> = 5.pipe_to_boxed_fn(in_range);
+    /// assert!(check(0, 10));
+    /// assert!(!check(6, 10));
+    /// ```
+    #[inline(always)]
+    fn pipe_to_boxed_fn<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, BoxedMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Like [`pipe_to_boxed_fn`](PipeBoxed::pipe_to_boxed_fn), but returns
+    /// `Box<dyn Fn(args..) -> R + Send + Sync>`, which can be shared between threads.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBoxed;
+    /// fn add(x: u64, y: u64) -> u64 { x + y }
+    ///
+    /// let add_ten = 10.pipe_to_boxed_fn_send(add);
+    /// assert_eq!(std::thread::spawn(move || add_ten(5)).join().unwrap(), 15);
+    /// ```
+    #[inline(always)]
+    fn pipe_to_boxed_fn_send<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, BoxedSendMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Like `tap`, but returns `Box<dyn Fn(args..) -> Self>`, which runs the side
+    /// effect on a reference to the value and returns a clone of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBoxed;
+    /// fn check_len(v: &Vec<u8>, max: usize) { assert!(v.len() <= max) }
+    ///
+    /// let checked = vec![1, 2].tap_to_boxed_fn(check_len);
+    /// assert_eq!(checked(4), [1, 2]);
+    /// ```
+    #[inline(always)]
+    fn tap_to_boxed_fn<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, BoxedMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeBoxed<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_to_boxed_fn` / `tap_to_boxed_fn` semantics (return a boxed `Fn`).
+pub struct BoxedMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_to_boxed_fn_send` semantics (return a boxed `Fn + Send + Sync`).
+pub struct BoxedSendMark;
+
+macro_rules! impl_boxed_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{BoxedMark, BoxedSendMark, Curry, Imm, Own};
+            #[cfg(feature = $feat)]
+            use alloc::boxed::Box;
+
+            // --- Boxed Pipe ---
+            #[cfg(feature = $feat)]
+            impl<F: 'static, A0: Clone + 'static, $($Params,)* R> Curry<$N, $TupleType, Own, Own, BoxedMark, A0, R> for F
+            where F: Fn(A0, $($Params),*) -> R {
+                type Curry = Box<dyn Fn($($Params),*) -> R>;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    Box::new(move |$($Params),*| self(arg0.clone(), $($Params),*))
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F: Send + Sync + 'static, A0: Clone + Send + Sync + 'static, $($Params,)* R> Curry<$N, $TupleType, Own, Own, BoxedSendMark, A0, R> for F
+            where F: Fn(A0, $($Params),*) -> R {
+                type Curry = Box<dyn Fn($($Params),*) -> R + Send + Sync>;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    Box::new(move |$($Params),*| self(arg0.clone(), $($Params),*))
+                }
+            }
+
+            // --- Boxed Tap ---
+            #[cfg(feature = $feat)]
+            impl<F: 'static, A0: Clone + 'static, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, BoxedMark, A0, R> for F
+            where F: Fn(&A0, $($Params),*) -> R {
+                type Curry = Box<dyn Fn($($Params),*) -> A0>;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    Box::new(move |$($Params),*| { self(&arg0, $($Params),*); arg0.clone() })
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_boxed_arity);
+}
@@ -0,0 +1,99 @@
+//! Pipe stages that draw from a computational budget.
+
+use core::fmt;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// A budget of abstract cost units shared by the stages of a pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Budget {
+    total: usize,
+    remaining: usize,
+}
+
+impl Budget {
+    /// Creates a budget of `total` units.
+    #[inline]
+    pub const fn new(total: usize) -> Self {
+        Budget { total, remaining: total }
+    }
+
+    /// Returns the units left to spend.
+    #[inline]
+    pub const fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns the units the budget was created with.
+    #[inline]
+    pub const fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Refills the budget to its total.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.remaining = self.total;
+    }
+}
+
+/// Error returned when a stage costs more than the budget has left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BudgetExceeded {
+    /// The cost of the stage.
+    pub cost: usize,
+    /// The units that were left.
+    pub remaining: usize,
+}
+
+impl fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "stage costs {} units but only {} remain", self.cost, self.remaining)
+    }
+}
+
+impl core::error::Error for BudgetExceeded {}
+
+/// Extension trait for budgeted pipe stages.
+pub trait PipeBudget<const ARITY: usize> {
+    /// Like `pipe`, but first spends `cost` units of `budget`, returning
+    /// `Ok(f(self, args..))`, or `Err(BudgetExceeded)` without calling `f` or
+    /// spending anything if fewer than `cost` units remain.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Budget, BudgetExceeded, PipeBudget};
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let mut budget = Budget::new(5);
+    /// assert_eq!(1.pipe_with_budget(&mut budget, 3, add)(2), Ok(3));
+    /// assert_eq!(1.pipe_with_budget(&mut budget, 3, add)(2), Err(BudgetExceeded { cost: 3, remaining: 2 }));
+    /// ```
+    #[inline(always)]
+    fn pipe_with_budget<'b, R, F, Params>(self, budget: &'b mut Budget, cost: usize, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Spend<'b>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Spend { budget, cost })
+    }
+}
+impl<const ARITY: usize, T> PipeBudget<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: spends from a budget before the call, or skips it if the budget is short.
+pub struct Spend<'b> {
+    budget: &'b mut Budget,
+    cost: usize,
+}
+
+impl<A0, R> Around<A0, R> for Spend<'_> {
+    type Output = Result<R, BudgetExceeded>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        let remaining = self.budget.remaining;
+        self.budget.remaining = remaining.checked_sub(self.cost).ok_or(BudgetExceeded { cost: self.cost, remaining })?;
+        Ok(call(arg0))
+    }
+}
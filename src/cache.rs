@@ -0,0 +1,123 @@
+//! Pipe stages memoized in a caller-owned map.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{CurryWith, Own};
+
+/// Extension trait for memoizing pipe stages.
+///
+/// The cache is borrowed rather than owned, so one map can serve many
+/// pipeline invocations.
+pub trait PipeCache<const ARITY: usize> {
+    /// Curries `self` into `f`, returning a closure over the remaining arguments
+    /// that computes `key_fn(&self, &args..)` and returns a clone of the entry
+    /// cached under that key. On a miss, `f(self, args..)` is called and its
+    /// result cached.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCache;
+    /// use std::collections::HashMap;
+    ///
+    /// fn pow(base: u64, exp: u32) -> u64 { base.pow(exp) }
+    ///
+    /// let mut cache = HashMap::new();
+    /// assert_eq!(2.pipe_with_cache(&mut cache, |b: &u64, e: &u32| (*b, *e), pow)(10), 1024);
+    /// assert_eq!(cache[&(2, 10)], 1024);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_cache<'c, K, R, S, KF, F, Params>(self, cache: &'c mut HashMap<K, R, S>, key_fn: KF, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, CacheMark, Self, (&'c mut HashMap<K, R, S>, KF), R>,
+        Self: Sized,
+    {
+        f.curry_with(self, (cache, key_fn))
+    }
+
+    /// Like [`pipe_with_cache`](PipeCache::pipe_with_cache), but for a cache shared
+    /// between threads, e.g. through `&Arc<Mutex<HashMap<K, R>>>`. The lock is not
+    /// held while `f` runs, so concurrent misses on one key may each call `f`;
+    /// the last result is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCache;
+    /// use std::collections::HashMap;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// fn len(s: &str) -> usize { s.len() }
+    ///
+    /// let cache = Arc::new(Mutex::new(HashMap::new()));
+    /// let key = |s: &&str| s.to_string();
+    /// assert_eq!("abc".pipe_with_shared_cache(&cache, key, len)(), 3);
+    /// assert_eq!(cache.lock().unwrap()["abc"], 3);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_shared_cache<'c, K, R, S, KF, F, Params>(self, cache: &'c Mutex<HashMap<K, R, S>>, key_fn: KF, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, CacheMark, Self, (&'c Mutex<HashMap<K, R, S>>, KF), R>,
+        Self: Sized,
+    {
+        f.curry_with(self, (cache, key_fn))
+    }
+}
+impl<const ARITY: usize, T> PipeCache<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_with_cache` semantics (look up, or compute and store).
+pub struct CacheMark;
+
+macro_rules! impl_cache_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CacheMark, CurryWith, HashMap, Mutex, Own};
+            #[cfg(feature = $feat)]
+            use core::hash::{BuildHasher, Hash};
+            #[cfg(feature = $feat)]
+            use std::collections::hash_map::Entry;
+            #[cfg(feature = $feat)]
+            use std::sync::PoisonError;
+
+            // --- Cache ---
+            #[cfg(feature = $feat)]
+            impl<'c, F, KF, A0, K: Hash + Eq, S: BuildHasher, $($Params,)* R: Clone> CurryWith<$N, $TupleType, Own, CacheMark, A0, (&'c mut HashMap<K, R, S>, KF), R> for F
+            where F: FnOnce(A0, $($Params),*) -> R, KF: FnOnce(&A0, $(&$Params),*) -> K {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry_with(self, arg0: A0, (cache, key_fn): (&'c mut HashMap<K, R, S>, KF)) -> Self::Curry {
+                    move |$($Params),*| match cache.entry(key_fn(&arg0, $(&$Params),*)) {
+                        Entry::Occupied(e) => e.get().clone(),
+                        Entry::Vacant(e) => e.insert(self(arg0, $($Params),*)).clone(),
+                    }
+                }
+            }
+
+            // --- Shared Cache ---
+            #[cfg(feature = $feat)]
+            impl<'c, F, KF, A0, K: Hash + Eq, S: BuildHasher, $($Params,)* R: Clone> CurryWith<$N, $TupleType, Own, CacheMark, A0, (&'c Mutex<HashMap<K, R, S>>, KF), R> for F
+            where F: FnOnce(A0, $($Params),*) -> R, KF: FnOnce(&A0, $(&$Params),*) -> K {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry_with(self, arg0: A0, (cache, key_fn): (&'c Mutex<HashMap<K, R, S>>, KF)) -> Self::Curry {
+                    move |$($Params),*| {
+                        let key = key_fn(&arg0, $(&$Params),*);
+                        if let Some(hit) = cache.lock().unwrap_or_else(PoisonError::into_inner).get(&key) {
+                            return hit.clone();
+                        }
+                        let result = self(arg0, $($Params),*);
+                        cache.lock().unwrap_or_else(PoisonError::into_inner).insert(key, result.clone());
+                        result
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_cache_arity);
+}
@@ -0,0 +1,181 @@
+//! Pipe stages guarded by a circuit breaker.
+
+use core::fmt;
+use core::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Settings for a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures after which the breaker opens.
+    pub failure_threshold: u32,
+    /// How long the breaker stays open before letting a trial call through.
+    pub reset_timeout: Duration,
+}
+
+/// State of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CircuitState {
+    /// Calls go through; failures are counted.
+    Closed,
+    /// Calls are rejected until the reset timeout elapses.
+    Open,
+    /// The reset timeout elapsed; the next call decides whether to close or reopen.
+    HalfOpen,
+}
+
+impl CircuitState {
+    const fn from_u8(v: u8) -> Self {
+        match v {
+            0 => CircuitState::Closed,
+            1 => CircuitState::Open,
+            _ => CircuitState::HalfOpen,
+        }
+    }
+}
+
+/// Error returned when a call is rejected because the breaker is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CircuitOpen;
+
+impl fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("circuit breaker is open")
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// A thread-safe circuit breaker.
+///
+/// The breaker starts `Closed`. After `failure_threshold` consecutive
+/// failures it opens and rejects calls; once `reset_timeout` has elapsed
+/// since the last failure it becomes `HalfOpen` and lets calls through.
+/// A success then closes it, and a failure reopens it.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failures: AtomicU32,
+    state: AtomicU8,
+    last_failure: Mutex<Instant>,
+    config: CircuitBreakerConfig,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker.
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        CircuitBreaker {
+            failures: AtomicU32::new(0),
+            state: AtomicU8::new(CircuitState::Closed as u8),
+            last_failure: Mutex::new(Instant::now()),
+            config,
+        }
+    }
+
+    /// Returns the breaker's settings.
+    #[inline]
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Returns the current state, moving from `Open` to `HalfOpen` if the reset
+    /// timeout has elapsed.
+    pub fn state(&self) -> CircuitState {
+        let state = CircuitState::from_u8(self.state.load(Ordering::Acquire));
+        if state == CircuitState::Open && self.last_failure().elapsed() >= self.config.reset_timeout {
+            // Losing the race means another thread already moved the state on.
+            let _ = self.state.compare_exchange(
+                CircuitState::Open as u8,
+                CircuitState::HalfOpen as u8,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            );
+            return CircuitState::from_u8(self.state.load(Ordering::Acquire));
+        }
+        state
+    }
+
+    /// Returns the number of consecutive failures recorded.
+    #[inline]
+    pub fn failure_count(&self) -> u32 {
+        self.failures.load(Ordering::Acquire)
+    }
+
+    /// Records a successful call, closing the breaker.
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Release);
+        self.state.store(CircuitState::Closed as u8, Ordering::Release);
+    }
+
+    /// Records a failed call, opening the breaker if it was half-open or the
+    /// failure threshold is reached.
+    pub fn record_failure(&self) {
+        let failures = self.failures.fetch_add(1, Ordering::AcqRel).saturating_add(1);
+        let half_open = self.state.load(Ordering::Acquire) == CircuitState::HalfOpen as u8;
+        if half_open || failures >= self.config.failure_threshold {
+            *self.last_failure.lock().unwrap_or_else(PoisonError::into_inner) = Instant::now();
+            self.state.store(CircuitState::Open as u8, Ordering::Release);
+        }
+    }
+
+    /// Closes the breaker and clears the failure count.
+    #[inline]
+    pub fn reset(&self) {
+        self.record_success();
+    }
+
+    fn last_failure(&self) -> Instant {
+        *self.last_failure.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Extension trait for piping values through a circuit breaker.
+pub trait PipeCircuitBreaker<const ARITY: usize> {
+    /// Like `pipe` for a fallible `f`, but rejects the call with `Err(CircuitOpen)`
+    /// while `breaker` is open. Otherwise calls `f(self, args..)`, records whether it
+    /// returned `Ok` or `Err`, and returns its result as `Ok(result)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{CircuitBreaker, CircuitBreakerConfig, CircuitOpen, PipeCircuitBreaker};
+    /// use std::time::Duration;
+    ///
+    /// fn parse(s: &str, radix: u32) -> Result<u32, std::num::ParseIntError> { u32::from_str_radix(s, radix) }
+    ///
+    /// let cb = CircuitBreaker::new(CircuitBreakerConfig { failure_threshold: 1, reset_timeout: Duration::from_secs(60) });
+    /// assert!("zz".pipe_with_circuit_breaker(&cb, parse)(10).unwrap().is_err());
+    /// assert_eq!("10".pipe_with_circuit_breaker(&cb, parse)(10), Err(CircuitOpen));
+    /// ```
+    #[inline(always)]
+    fn pipe_with_circuit_breaker<'b, R, F, Params>(self, breaker: &'b CircuitBreaker, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Breaker<'b>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Breaker(breaker))
+    }
+}
+impl<const ARITY: usize, T> PipeCircuitBreaker<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: rejects the call while the breaker is open and records its outcome.
+pub struct Breaker<'b>(&'b CircuitBreaker);
+
+impl<A0, T, E> Around<A0, Result<T, E>> for Breaker<'_> {
+    type Output = Result<Result<T, E>, CircuitOpen>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> Result<T, E>>(self, arg0: A0, call: C) -> Self::Output {
+        if self.0.state() == CircuitState::Open {
+            return Err(CircuitOpen);
+        }
+        let result = call(arg0);
+        match result {
+            Ok(_) => self.0.record_success(),
+            Err(_) => self.0.record_failure(),
+        }
+        Ok(result)
+    }
+}
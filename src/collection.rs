@@ -0,0 +1,442 @@
+//! Pipes over collections: the extra arguments are captured once and
+//! shared with every per-element call, either by reference or by cloning.
+
+use crate::{Curry, CurryWith, Own};
+#[cfg(feature = "alloc")]
+use core::cmp::Ordering;
+
+/// Extension trait for running multi-argument callbacks over a collection.
+pub trait PipeCollection<const ARITY: usize> {
+    /// Sorts `self` with a comparator that receives the remaining arguments
+    /// by reference, returning the sorted collection. The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn by_distance(a: &i32, b: &i32, origin: &i32) -> core::cmp::Ordering {
+    ///     (a - origin).abs().cmp(&(b - origin).abs())
+    /// }
+    ///
+    /// let sorted = vec![1, 9, 4, 6].pipe_sort_by_with(by_distance)(5);
+    /// assert_eq!(sorted, [4, 6, 1, 9]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn pipe_sort_by_with<F, Params>(self, cmp: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, SortByMark, Self, Ordering>,
+        Self: Sized,
+    {
+        cmp.curry(self)
+    }
+
+    /// Sorts `self` by the key `key_fn` extracts from each element, with the
+    /// remaining arguments passed to `key_fn` by reference. The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn nth_char(s: &&str, n: &usize) -> Option<char> { s.chars().nth(*n) }
+    ///
+    /// let sorted = vec!["abc", "bca", "cab"].pipe_sort_by_key_with(nth_char)(1);
+    /// assert_eq!(sorted, ["cab", "abc", "bca"]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn pipe_sort_by_key_with<K, F, Params>(self, key_fn: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, SortByKeyMark, Self, K>,
+        Self: Sized,
+    {
+        key_fn.curry(self)
+    }
+
+    /// Splits the elements of `self` into those for which `pred` returns
+    /// `true` and those for which it returns `false`, preserving their order.
+    /// The remaining arguments are passed to `pred` by reference.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn above(x: &i32, limit: &i32) -> bool { x > limit }
+    ///
+    /// let (high, low) = [3, 8, 1, 9].pipe_partition_with(above)(5);
+    /// assert_eq!((high, low), (vec![8, 9], vec![3, 1]));
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn pipe_partition_with<F, Params>(self, pred: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PartitionMark, Self, bool>,
+        Self: Sized,
+    {
+        pred.curry(self)
+    }
+
+    /// Splits the elements of `self` into two collections of possibly
+    /// different types, by whether `f` maps each one to `Left` or `Right`,
+    /// preserving their order. The remaining arguments are passed to `f` by
+    /// reference.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// use either::Either;
+    ///
+    /// fn parse(s: &str, radix: &u32) -> Either<u32, String> {
+    ///     u32::from_str_radix(s, *radix).map_or_else(|_| Either::Right(s.to_uppercase()), Either::Left)
+    /// }
+    ///
+    /// let (nums, words) = ["ff", "zz", "10"].pipe_partition_map_with(parse)(16);
+    /// assert_eq!((nums, words), (vec![255, 16], vec!["ZZ".to_string()]));
+    /// ```
+    #[cfg(all(feature = "alloc", feature = "either"))]
+    #[inline(always)]
+    fn pipe_partition_map_with<L, R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PartitionMapMark, Self, either::Either<L, R>>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Calls `f` with `self` and the remaining arguments, then returns an
+    /// iterator over the items of the iterable `f` produces.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// let mut words = "a quick fox".pipe_flat_map(str::split_whitespace)();
+    /// assert_eq!(words.next(), Some("a"));
+    /// assert_eq!(words.count(), 2);
+    /// ```
+    #[inline(always)]
+    fn pipe_flat_map<I, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, FlatMapMark, Self, I>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Like [`pipe_flat_map`](PipeCollection::pipe_flat_map), but collects the
+    /// produced items into `C`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn repeat(x: char, n: usize) -> core::iter::RepeatN<char> { core::iter::repeat_n(x, n) }
+    ///
+    /// let s = 'z'.pipe_flat_map_collect::<String, _, _>(repeat)(3);
+    /// assert_eq!(s, "zzz");
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn pipe_flat_map_collect<C, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, FlatMapCollectMark, Self, C>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Maps `f(item, args..)` over the items of `self` and extends `collection`
+    /// with the results, so several inputs can be accumulated into one collection.
+    /// The remaining arguments are cloned for each item.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn scale(x: &i32, k: i32) -> i32 { x * k }
+    ///
+    /// let mut all = Vec::new();
+    /// [1, 2].iter().pipe_map_collect_into(&mut all, scale)(10);
+    /// [3].iter().pipe_map_collect_into(&mut all, scale)(100);
+    /// assert_eq!(all, [10, 20, 300]);
+    /// ```
+    #[inline(always)]
+    fn pipe_map_collect_into<'c, C: ?Sized, R, F, Params>(self, collection: &'c mut C, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, CollectIntoMark, Self, &'c mut C, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, collection)
+    }
+
+    /// Calls `f` on every overlapping window of `size` elements of the slice,
+    /// collecting the results. The remaining arguments are cloned for each call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn weighted_sum(w: &[i32], scale: i32) -> i32 { w.iter().sum::<i32>() * scale }
+    ///
+    /// let sums = [1, 2, 3, 4].as_slice().pipe_window(2, weighted_sum)(10);
+    /// assert_eq!(sums, [30, 50, 70]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn pipe_window<R, F, Params>(self, size: usize, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, WindowMark, Self, usize, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, size)
+    }
+
+    /// Lazy version of [`pipe_window`](PipeCollection::pipe_window): returns an
+    /// iterator that calls `f` on each window as it is consumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn is_rising(w: &[i32]) -> bool { w[0] < w[1] }
+    ///
+    /// let rising = [1, 3, 2, 5].as_slice().pipe_window_iter(2, is_rising)().filter(|&b| b).count();
+    /// assert_eq!(rising, 2);
+    /// ```
+    #[inline(always)]
+    fn pipe_window_iter<R, F, Params>(self, size: usize, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, WindowIterMark, Self, usize, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, size)
+    }
+
+    /// Calls `f` on every non-overlapping chunk of `size` elements of the
+    /// slice, collecting the results. The last chunk may be shorter than `size`.
+    /// The remaining arguments are cloned for each call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeCollection;
+    /// fn first_or(c: &[i32], default: i32) -> i32 { c.first().copied().unwrap_or(default) }
+    ///
+    /// let heads = [1, 2, 3, 4, 5].as_slice().pipe_chunks_with(2, first_or)(0);
+    /// assert_eq!(heads, [1, 3, 5]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn pipe_chunks_with<R, F, Params>(self, size: usize, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, ChunksMark, Self, usize, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, size)
+    }
+}
+impl<const ARITY: usize, T> PipeCollection<ARITY> for T {}
+
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+/// Marker type: `pipe_sort_by_with` semantics (sort with a comparator).
+pub struct SortByMark;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+/// Marker type: `pipe_sort_by_key_with` semantics (sort by an extracted key).
+pub struct SortByKeyMark;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+/// Marker type: `pipe_partition_with` semantics (split by a predicate).
+pub struct PartitionMark;
+#[cfg(all(feature = "alloc", feature = "either"))]
+#[doc(hidden)]
+/// Marker type: `pipe_partition_map_with` semantics (split by `Either` side).
+pub struct PartitionMapMark;
+#[doc(hidden)]
+/// Marker type: `pipe_flat_map` semantics (iterate over the function's output).
+pub struct FlatMapMark;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+/// Marker type: `pipe_flat_map_collect` semantics (collect the function's output).
+pub struct FlatMapCollectMark;
+#[doc(hidden)]
+/// Marker type: `pipe_map_collect_into` semantics (extend an existing collection).
+pub struct CollectIntoMark;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+/// Marker type: `pipe_window` semantics (collect over sliding windows).
+pub struct WindowMark;
+#[doc(hidden)]
+/// Marker type: `pipe_window_iter` semantics (lazily map over sliding windows).
+pub struct WindowIterMark;
+#[cfg(feature = "alloc")]
+#[doc(hidden)]
+/// Marker type: `pipe_chunks_with` semantics (collect over non-overlapping chunks).
+pub struct ChunksMark;
+
+any_arity! {
+    allow_dead_code;
+
+    /// Iterator returned by [`PipeCollection::pipe_window_iter`]: calls the
+    /// function on each sliding window, cloning the captured arguments per call.
+    pub struct WindowMap<'a, T, F, Args> {
+        windows: core::slice::Windows<'a, T>,
+        f: F,
+        args: Args,
+    }
+}
+
+macro_rules! impl_collection_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CollectIntoMark, Curry, CurryWith, FlatMapMark, Own, WindowIterMark, WindowMap};
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            use super::{ChunksMark, FlatMapCollectMark, Ordering, PartitionMark, SortByKeyMark, SortByMark, WindowMark};
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            use alloc::vec::Vec;
+            #[cfg(all(feature = $feat, feature = "alloc", feature = "either"))]
+            use super::PartitionMapMark;
+            #[cfg(all(feature = $feat, feature = "alloc", feature = "either"))]
+            use either::Either;
+
+            // --- Sort ---
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            impl<F, T, $($Params),*> Curry<$N, $TupleType, Own, Own, SortByMark, Vec<T>, Ordering> for F
+            where F: Fn(&T, &T, $(&$Params),*) -> Ordering {
+                type Curry = impl FnOnce($($Params),*) -> Vec<T>;
+                #[inline(always)] fn curry(self, mut arg0: Vec<T>) -> Self::Curry {
+                    move |$($Params),*| { arg0.sort_by(|a, b| self(a, b, $(&$Params),*)); arg0 }
+                }
+            }
+
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            impl<F, T, K: Ord, $($Params),*> Curry<$N, $TupleType, Own, Own, SortByKeyMark, Vec<T>, K> for F
+            where F: Fn(&T, $(&$Params),*) -> K {
+                type Curry = impl FnOnce($($Params),*) -> Vec<T>;
+                #[inline(always)] fn curry(self, mut arg0: Vec<T>) -> Self::Curry {
+                    move |$($Params),*| { arg0.sort_by_key(|x| self(x, $(&$Params),*)); arg0 }
+                }
+            }
+
+            // --- Partition ---
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            impl<F, I: IntoIterator, $($Params),*> Curry<$N, $TupleType, Own, Own, PartitionMark, I, bool> for F
+            where F: Fn(&I::Item, $(&$Params),*) -> bool {
+                type Curry = impl FnOnce($($Params),*) -> (Vec<I::Item>, Vec<I::Item>);
+                #[inline(always)] fn curry(self, arg0: I) -> Self::Curry {
+                    move |$($Params),*| arg0.into_iter().partition(|x| self(x, $(&$Params),*))
+                }
+            }
+
+            #[cfg(all(feature = $feat, feature = "alloc", feature = "either"))]
+            impl<F, I: IntoIterator, L, R, $($Params),*> Curry<$N, $TupleType, Own, Own, PartitionMapMark, I, Either<L, R>> for F
+            where F: Fn(I::Item, $(&$Params),*) -> Either<L, R> {
+                type Curry = impl FnOnce($($Params),*) -> (Vec<L>, Vec<R>);
+                #[inline(always)] fn curry(self, arg0: I) -> Self::Curry {
+                    move |$($Params),*| {
+                        let (mut left, mut right) = (Vec::new(), Vec::new());
+                        for x in arg0 {
+                            match self(x, $(&$Params),*) {
+                                Either::Left(l) => left.push(l),
+                                Either::Right(r) => right.push(r),
+                            }
+                        }
+                        (left, right)
+                    }
+                }
+            }
+
+            // --- Flat Map ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, I: IntoIterator, $($Params),*> Curry<$N, $TupleType, Own, Own, FlatMapMark, A0, I> for F
+            where F: FnOnce(A0, $($Params),*) -> I {
+                type Curry = impl FnOnce($($Params),*) -> I::IntoIter;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    |$($Params),*| self(arg0, $($Params),*).into_iter()
+                }
+            }
+
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            impl<F, A0, I: IntoIterator, C: FromIterator<I::Item>, $($Params),*> Curry<$N, $TupleType, Own, Own, FlatMapCollectMark, A0, C> for F
+            where F: FnOnce(A0, $($Params),*) -> I {
+                type Curry = impl FnOnce($($Params),*) -> C;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    |$($Params),*| self(arg0, $($Params),*).into_iter().collect()
+                }
+            }
+
+            // --- Collect Into ---
+            #[cfg(feature = $feat)]
+            impl<'c, F, I: IntoIterator, C: Extend<R> + ?Sized, $($Params: Clone,)* R> CurryWith<$N, $TupleType, Own, CollectIntoMark, I, &'c mut C, R> for F
+            where F: FnMut(I::Item, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*);
+                #[inline(always)] fn curry_with(mut self, arg0: I, collection: &'c mut C) -> Self::Curry {
+                    move |$($Params),*| collection.extend(arg0.into_iter().map(|x| self(x, $($Params.clone()),*)))
+                }
+            }
+
+            // --- Windows & Chunks ---
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            impl<'a, F, T, $($Params: Clone,)* R> CurryWith<$N, $TupleType, Own, WindowMark, &'a [T], usize, R> for F
+            where F: FnMut(&'a [T], $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Vec<R>;
+                #[inline(always)] fn curry_with(mut self, arg0: &'a [T], size: usize) -> Self::Curry {
+                    move |$($Params),*| arg0.windows(size).map(|w| self(w, $($Params.clone()),*)).collect()
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<'a, F, T, $($Params: Clone,)* R> CurryWith<$N, $TupleType, Own, WindowIterMark, &'a [T], usize, R> for F
+            where F: FnMut(&'a [T], $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> WindowMap<'a, T, F, $TupleType>;
+                #[inline(always)] fn curry_with(self, arg0: &'a [T], size: usize) -> Self::Curry {
+                    move |$($Params),*| WindowMap { windows: arg0.windows(size), f: self, args: ($($Params,)*) }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<'a, F, T, $($Params: Clone,)* R> Iterator for WindowMap<'a, T, F, $TupleType>
+            where F: FnMut(&'a [T], $($Params),*) -> R {
+                type Item = R;
+                #[inline(always)] fn next(&mut self) -> Option<R> {
+                    let w = self.windows.next()?;
+                    let ($($Params,)*) = &self.args;
+                    Some((self.f)(w, $($Params.clone()),*))
+                }
+                #[inline(always)] fn size_hint(&self) -> (usize, Option<usize>) {
+                    self.windows.size_hint()
+                }
+            }
+
+            #[cfg(all(feature = $feat, feature = "alloc"))]
+            impl<'a, F, T, $($Params: Clone,)* R> CurryWith<$N, $TupleType, Own, ChunksMark, &'a [T], usize, R> for F
+            where F: FnMut(&'a [T], $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Vec<R>;
+                #[inline(always)] fn curry_with(mut self, arg0: &'a [T], size: usize) -> Self::Curry {
+                    move |$($Params),*| arg0.chunks(size).map(|c| self(c, $($Params.clone()),*)).collect()
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_collection_arity);
+}
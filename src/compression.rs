@@ -0,0 +1,110 @@
+//! Pipe stages that compress or decompress bytes with gzip.
+
+use std::io::{self, Read, Write};
+use std::vec::Vec;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::{Curry, Own};
+
+/// Extension trait for gzip-compressing byte buffers in a pipeline.
+pub trait PipeGzip: AsRef<[u8]> {
+    /// Compresses `self` with gzip and pipes the compressed bytes into `f`,
+    /// returning `Ok(f(compressed, args..))`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the encoder's I/O error without calling `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeGzip;
+    /// fn store(blob: Vec<u8>, bucket: &mut Vec<Vec<u8>>) -> usize { bucket.push(blob); bucket.len() }
+    ///
+    /// let mut bucket = Vec::new();
+    /// assert_eq!(b"payload".pipe_with_gzip(store)(&mut bucket).unwrap(), 1);
+    /// assert_eq!(bucket[0].tap_decompress_gzip().unwrap(), b"payload");
+    /// ```
+    #[inline(always)]
+    fn pipe_with_gzip<'a, const ARITY: usize, R, F, Params>(&'a self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, GzipMark, &'a [u8], R>,
+    {
+        f.curry(self.as_ref())
+    }
+
+    /// Compresses `self` with gzip at the default level.
+    ///
+    /// # Errors
+    ///
+    /// Returns the encoder's I/O error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeGzip;
+    /// let text = "to be or not to be, ".repeat(50);
+    /// let compressed = text.pipe_compress_gzip().unwrap();
+    /// assert!(compressed.len() < text.len());
+    /// ```
+    #[inline]
+    fn pipe_compress_gzip(&self) -> io::Result<Vec<u8>> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(self.as_ref())?;
+        encoder.finish()
+    }
+
+    /// Decompresses gzip data in `self`, returning the original bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `self` is not valid gzip data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeGzip;
+    /// let compressed = b"hello".pipe_compress_gzip().unwrap();
+    /// assert_eq!(compressed.tap_decompress_gzip().unwrap(), b"hello");
+    /// assert!(b"not gzip".tap_decompress_gzip().is_err());
+    /// ```
+    #[inline]
+    fn tap_decompress_gzip(&self) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        GzDecoder::new(self.as_ref()).read_to_end(&mut out)?;
+        Ok(out)
+    }
+}
+impl<T: AsRef<[u8]> + ?Sized> PipeGzip for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_with_gzip` semantics (compress, then pipe).
+pub struct GzipMark;
+
+macro_rules! impl_gzip_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{io, Curry, GzipMark, Own, PipeGzip, Vec};
+
+            // --- Gzip ---
+            #[cfg(feature = $feat)]
+            impl<'a, F, $($Params,)* R> Curry<$N, $TupleType, Own, Own, GzipMark, &'a [u8], R> for F
+            where F: FnOnce(Vec<u8>, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> io::Result<R>;
+                #[inline(always)] fn curry(self, arg0: &'a [u8]) -> Self::Curry {
+                    move |$($Params),*| Ok(self(arg0.pipe_compress_gzip()?, $($Params),*))
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_gzip_arity);
+}
@@ -0,0 +1,78 @@
+//! Table-driven transforms: pick the function to pipe a value into by index.
+
+use core::fmt;
+
+/// Error returned when a selector picks an index past the end of the transforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IndexOutOfBounds {
+    /// The index the selector returned.
+    pub index: usize,
+    /// The number of transforms available.
+    pub len: usize,
+}
+
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "transform index {} out of bounds for {} transforms", self.index, self.len)
+    }
+}
+
+impl core::error::Error for IndexOutOfBounds {}
+
+/// Extension trait for piping a value into a transform chosen from a slice.
+///
+/// The transforms can be function pointers, which needs no allocation, or
+/// any other `Fn(T) -> T`, such as boxed closures.
+pub trait PipeConditional: Sized {
+    /// Pipes `self` into `transforms[selector(&self)]`, or returns it unchanged
+    /// if the index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeConditional;
+    /// let by_sign = |x: &i32| match x.signum() { 0 => 0, -1 => 1, _ => 2 };
+    /// let transforms: [fn(i32) -> i32; 3] = [|x| x, |x| -x, |x| x * 10];
+    ///
+    /// assert_eq!((-4).pipe_conditional(by_sign, &transforms), 4);
+    /// assert_eq!(4.pipe_conditional(by_sign, &transforms), 40);
+    /// assert_eq!(4.pipe_conditional(|_: &i32| 9, &transforms), 4);
+    /// ```
+    #[inline]
+    fn pipe_conditional<S, F>(self, selector: S, transforms: &[F]) -> Self
+    where
+        S: FnOnce(&Self) -> usize,
+        F: Fn(Self) -> Self,
+    {
+        match transforms.get(selector(&self)) {
+            Some(transform) => transform(self),
+            None => self,
+        }
+    }
+
+    /// Like [`pipe_conditional`](PipeConditional::pipe_conditional), but
+    /// returns `Err(IndexOutOfBounds)` if the index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{IndexOutOfBounds, PipeConditional};
+    /// let transforms: Vec<Box<dyn Fn(String) -> String>> = vec![Box::new(|s| s.to_uppercase())];
+    ///
+    /// assert_eq!("a".to_string().pipe_conditional_result(|_| 0, &transforms).as_deref(), Ok("A"));
+    /// assert_eq!("a".to_string().pipe_conditional_result(|_| 1, &transforms), Err(IndexOutOfBounds { index: 1, len: 1 }));
+    /// ```
+    #[inline]
+    fn pipe_conditional_result<S, F>(self, selector: S, transforms: &[F]) -> Result<Self, IndexOutOfBounds>
+    where
+        S: FnOnce(&Self) -> usize,
+        F: Fn(Self) -> Self,
+    {
+        let index = selector(&self);
+        match transforms.get(index) {
+            Some(transform) => Ok(transform(self)),
+            None => Err(IndexOutOfBounds { index, len: transforms.len() }),
+        }
+    }
+}
+impl<T> PipeConditional for T {}
@@ -0,0 +1,101 @@
+//! A value that carries a configuration through its pipe stages.
+
+use crate::{Curry, Own};
+
+/// A value `T` paired with a configuration `C` that every stage can read.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::PipeConfig;
+/// struct Config { scale: i32, offset: i32 }
+///
+/// fn scale(x: i32, cfg: &Config) -> i32 { x * cfg.scale }
+/// fn shift(x: i32, cfg: &Config, extra: i32) -> i32 { x + cfg.offset + extra }
+///
+/// let out = PipeConfig::new(5, Config { scale: 3, offset: 1 })
+///     .pipe_configured(scale)()
+///     .pipe_configured(shift)(10)
+///     .into_value();
+/// assert_eq!(out, 26);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PipeConfig<T, C> {
+    value: T,
+    config: C,
+}
+
+impl<T, C> PipeConfig<T, C> {
+    /// Pairs `value` with `config`.
+    #[inline]
+    pub const fn new(value: T, config: C) -> Self {
+        PipeConfig { value, config }
+    }
+
+    /// Returns the current value.
+    #[inline]
+    pub const fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the configuration.
+    #[inline]
+    pub const fn config(&self) -> &C {
+        &self.config
+    }
+
+    /// Returns the current value, dropping the configuration.
+    #[inline]
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Returns the current value and the configuration.
+    #[inline]
+    pub fn into_parts(self) -> (T, C) {
+        (self.value, self.config)
+    }
+
+    /// Returns a closure over the remaining arguments that calls
+    /// `f(value, &config, args..)` and pairs its result with the same
+    /// configuration.
+    #[inline(always)]
+    pub fn pipe_configured<const ARITY: usize, U, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, ConfigMark, Self, U>,
+    {
+        f.curry(self)
+    }
+}
+
+#[doc(hidden)]
+/// Marker type: `pipe_configured` semantics (pass the configuration after the value).
+pub struct ConfigMark;
+
+macro_rules! impl_config_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{ConfigMark, Curry, Own, PipeConfig};
+
+            // --- Configured ---
+            #[cfg(feature = $feat)]
+            impl<F, T, C, $($Params,)* U> Curry<$N, $TupleType, Own, Own, ConfigMark, PipeConfig<T, C>, U> for F
+            where F: FnOnce(T, &C, $($Params),*) -> U {
+                type Curry = impl FnOnce($($Params),*) -> PipeConfig<U, C>;
+                #[inline(always)] fn curry(self, arg0: PipeConfig<T, C>) -> Self::Curry {
+                    move |$($Params),*| {
+                        let value = self(arg0.value, &arg0.config, $($Params),*);
+                        PipeConfig { value, config: arg0.config }
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_config_arity);
+}
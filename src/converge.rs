@@ -0,0 +1,68 @@
+//! Pipe stages that run two branches on copies of a value and merge the results.
+
+use crate::{CurryWith, Own};
+
+/// Extension trait for convergent pipe stages.
+pub trait PipeConverge<const ARITY: usize> {
+    /// Returns a closure over the remaining arguments that calls
+    /// `f1(self.clone(), args..)` and `f2(self, args..)` and combines the two
+    /// results with `merge(r1, r2)`.
+    ///
+    /// The branches run in that order, but for a commutative `merge`, such as
+    /// a last-writer-wins or CRDT join, the result does not depend on it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeConverge;
+    /// use std::collections::BTreeSet;
+    ///
+    /// fn add_a(mut s: BTreeSet<char>, tag: char) -> BTreeSet<char> { s.insert('a'); s.insert(tag); s }
+    /// fn add_b(mut s: BTreeSet<char>, tag: char) -> BTreeSet<char> { s.insert('b'); s.insert(tag); s }
+    /// fn union(mut x: BTreeSet<char>, y: BTreeSet<char>) -> BTreeSet<char> { x.extend(y); x }
+    ///
+    /// let merged = BTreeSet::from(['z']).pipe_eventually(union, add_a, add_b)('t');
+    /// assert_eq!(merged, BTreeSet::from(['a', 'b', 't', 'z']));
+    /// ```
+    #[inline(always)]
+    fn pipe_eventually<M, G, R, F, Params>(self, merge: M, f1: F, f2: G) -> F::Curry
+    where
+        M: FnOnce(R, R) -> R,
+        F: CurryWith<ARITY, Params, Own, ConvergeMark, Self, (M, G), R>,
+        Self: Sized,
+    {
+        f1.curry_with(self, (merge, f2))
+    }
+}
+impl<const ARITY: usize, T> PipeConverge<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_eventually` semantics (two branches on clones, then merge).
+pub struct ConvergeMark;
+
+macro_rules! impl_converge_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{ConvergeMark, CurryWith, Own};
+
+            #[cfg(feature = $feat)]
+            impl<F, G, M, A0: Clone, $($Params: Clone,)* R> CurryWith<$N, $TupleType, Own, ConvergeMark, A0, (M, G), R> for F
+            where F: FnOnce(A0, $($Params),*) -> R, G: FnOnce(A0, $($Params),*) -> R, M: FnOnce(R, R) -> R {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry_with(self, arg0: A0, (merge, f2): (M, G)) -> Self::Curry {
+                    |$($Params),*| {
+                        let r1 = self(arg0.clone(), $($Params.clone()),*);
+                        merge(r1, f2(arg0, $($Params),*))
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_converge_arity);
+}
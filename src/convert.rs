@@ -0,0 +1,81 @@
+//! Pipes that convert the value or the result with `Into`.
+
+use crate::{Curry, Own, PipeMark};
+
+/// Extension trait for pipes that convert with `Into` on the way.
+pub trait PipeConvert<const ARITY: usize> {
+    /// Like `pipe`, but first converts `self` into `U`, so `f` can take any
+    /// type `self` converts into. The conversion runs once, before currying,
+    /// and the returned closure holds the converted value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeConvert;
+    /// fn mean(total: u64, count: u64) -> u64 { total / count }
+    /// fn shout(s: Box<str>, suffix: &str) -> String { s.to_uppercase() + suffix }
+    ///
+    /// let total: u32 = 90;
+    /// assert_eq!(total.into_pipe::<u64, _, _, _>(mean)(4), 22);
+    /// assert_eq!(String::from("hi").into_pipe::<Box<str>, _, _, _>(shout)("!"), "HI!");
+    /// ```
+    #[inline(always)]
+    fn into_pipe<U, R, F, Params>(self, f: F) -> F::Curry
+    where
+        Self: Into<U>,
+        F: Curry<ARITY, Params, Own, Own, PipeMark, U, R>,
+    {
+        f.curry(self.into())
+    }
+
+    /// Like `pipe`, but converts the result of `f(self, args..)` into `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeConvert;
+    /// fn area(w: u32, h: u32) -> u32 { w * h }
+    ///
+    /// assert_eq!(3.pipe_into::<u64, _, _, _>(area)(4), 12u64);
+    /// assert_eq!("a".pipe_into::<String, _, _, _>(|s: &str, n: usize| s.repeat(n))(2), "aa");
+    /// ```
+    #[inline(always)]
+    fn pipe_into<U, R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PipeIntoMark, Self, (R, U)>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeConvert<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_into` semantics. The result slot carries `(R, U)`, the
+/// result of `f` and the type it is converted into.
+pub struct PipeIntoMark;
+
+macro_rules! impl_convert_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, Own, PipeIntoMark};
+
+            // --- Pipe Into ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R, U> Curry<$N, $TupleType, Own, Own, PipeIntoMark, A0, (R, U)> for F
+            where F: FnOnce(A0, $($Params),*) -> R, R: Into<U> {
+                type Curry = impl FnOnce($($Params),*) -> U;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    |$($Params),*| self(arg0, $($Params),*).into()
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_convert_arity);
+}
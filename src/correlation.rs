@@ -0,0 +1,111 @@
+//! Thread-local correlation ids: tag every stage run on behalf of one request
+//! with the same id, e.g. to include it in each log line.
+
+use core::cell::RefCell;
+use core::fmt;
+use core::hash::{BuildHasher, Hasher};
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::collections::hash_map::RandomState;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::scoped::with_scoped;
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// An id shared by all the stages that handle one request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CorrelationId(pub u128);
+
+static GENERATED: AtomicU64 = AtomicU64::new(0);
+
+impl CorrelationId {
+    /// Generates a new id in the layout of a version 4 (random) UUID.
+    ///
+    /// The random bits are derived from the standard library's randomly
+    /// seeded hasher, the current time, and a process-wide counter. They are
+    /// unique in practice, but not suitable where unpredictability matters.
+    pub fn generate() -> Self {
+        let count = GENERATED.fetch_add(1, Ordering::Relaxed);
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        let state = RandomState::new();
+        let half = |salt: u64| {
+            let mut hasher = state.build_hasher();
+            hasher.write_u64(salt);
+            hasher.write_u64(count);
+            hasher.write_u128(nanos);
+            hasher.finish() as u128
+        };
+        let bits = (half(0) << 64) | half(1);
+        let version_4 = (bits & !(0xf << 76)) | (0x4 << 76);
+        CorrelationId((version_4 & !(0x3 << 62)) | (0x2 << 62))
+    }
+}
+
+/// Formats the id as a hyphenated UUID, e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`.
+impl fmt::Display for CorrelationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let id = self.0;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            id >> 96,
+            (id >> 80) & 0xffff,
+            (id >> 64) & 0xffff,
+            (id >> 48) & 0xffff,
+            id & 0xffff_ffff_ffff
+        )
+    }
+}
+
+std::thread_local! {
+    static CURRENT_ID: RefCell<Option<CorrelationId>> = const { RefCell::new(None) };
+}
+
+/// Returns the correlation id of the innermost stage started by
+/// [`pipe_with_correlation_id`](PipeCorrelation::pipe_with_correlation_id)
+/// running on this thread, if any.
+pub fn current_id() -> Option<CorrelationId> {
+    CURRENT_ID.with_borrow(|id| *id)
+}
+
+/// Extension trait for running pipe stages under a correlation id.
+pub trait PipeCorrelation<const ARITY: usize> {
+    /// Like `pipe`, but makes `id` the current correlation id while `f` runs.
+    /// Stages nested inside `f` see `id` unless they set their own; the outer
+    /// id comes back once `f` returns or unwinds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::correlation::{self, CorrelationId, PipeCorrelation};
+    /// fn log_line(msg: &str, level: &str) -> String {
+    ///     let id = correlation::current_id().unwrap();
+    ///     format!("[{id}] {level}: {msg}")
+    /// }
+    ///
+    /// let id = CorrelationId(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+    /// let line = "started".pipe_with_correlation_id(id, log_line)("INFO");
+    /// assert_eq!(line, "[67e55044-10b1-426f-9247-bb680e5fe0c8] INFO: started");
+    /// assert_eq!(correlation::current_id(), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_correlation_id<R, F, Params>(self, id: CorrelationId, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, WithCorrelation, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, WithCorrelation(id))
+    }
+}
+impl<const ARITY: usize, T> PipeCorrelation<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call with the correlation id set.
+pub struct WithCorrelation(CorrelationId);
+
+impl<A0, R> Around<A0, R> for WithCorrelation {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        with_scoped(&CURRENT_ID, self.0, || call(arg0))
+    }
+}
@@ -0,0 +1,216 @@
+//! Database-agnostic transactions: run a pipe stage inside `begin` / `commit`,
+//! rolling back when it fails.
+
+use core::cell::RefCell;
+use core::fmt;
+use std::rc::Rc;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{Curry, Own};
+
+/// Error raised while beginning, running, or committing a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TxError {
+    reason: &'static str,
+}
+
+impl TxError {
+    /// Creates an error with the given reason.
+    #[inline]
+    pub const fn new(reason: &'static str) -> Self {
+        TxError { reason }
+    }
+
+    /// Returns why the transaction failed.
+    #[inline]
+    pub const fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl fmt::Display for TxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "transaction failed: {}", self.reason)
+    }
+}
+
+impl std::error::Error for TxError {}
+
+/// An open transaction, finished by exactly one of `commit` or `rollback`.
+pub trait TxGuard {
+    /// Makes the transaction's changes permanent.
+    fn commit(self) -> Result<(), TxError>;
+
+    /// Discards the transaction's changes.
+    fn rollback(self);
+}
+
+/// A connection that can open transactions.
+pub trait Transaction {
+    /// The transaction handle returned by [`begin`](Transaction::begin).
+    type Guard: TxGuard;
+
+    /// Opens a transaction.
+    fn begin(&mut self) -> Result<Self::Guard, TxError>;
+}
+
+/// Extension trait for running pipe stages inside a transaction.
+pub trait PipeTransaction<const ARITY: usize> {
+    /// Curries a connection `&mut C` into `f`, returning a closure over the
+    /// remaining arguments that begins a transaction, calls
+    /// `f(conn, &mut tx, args..)`, and commits if `f` returns `Ok` or rolls
+    /// back if it returns `Err`. Errors from `begin` and `commit` are returned
+    /// as well.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::db::{MockConnection, MockTx, PipeTransaction, TxError};
+    /// fn insert(_: &mut MockConnection, tx: &mut MockTx, row: &str) -> Result<usize, TxError> {
+    ///     tx.execute(row);
+    ///     Ok(1)
+    /// }
+    ///
+    /// let mut conn = MockConnection::new();
+    /// assert_eq!((&mut conn).pipe_with_transaction(insert)("alice"), Ok(1));
+    /// assert_eq!(conn.committed(), ["alice"]);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_transaction<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, TxMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeTransaction<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_with_transaction` semantics (commit on `Ok`, roll back on `Err`).
+pub struct TxMark;
+
+#[derive(Debug, Default)]
+struct MockState {
+    committed: Vec<String>,
+    events: Vec<&'static str>,
+}
+
+/// An in-memory [`Transaction`] implementation for tests.
+///
+/// Statements executed in a [`MockTx`] become visible in
+/// [`committed`](MockConnection::committed) only after a successful commit.
+/// Every `begin`, `commit`, and `rollback` is recorded in
+/// [`events`](MockConnection::events).
+#[derive(Debug, Default)]
+pub struct MockConnection {
+    state: Rc<RefCell<MockState>>,
+    fail_begin: bool,
+    fail_commit: bool,
+}
+
+impl MockConnection {
+    /// Creates a connection whose transactions begin and commit successfully.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a connection whose `begin` always fails.
+    pub fn failing_begin() -> Self {
+        MockConnection { fail_begin: true, ..Self::default() }
+    }
+
+    /// Creates a connection whose `commit` always fails.
+    pub fn failing_commit() -> Self {
+        MockConnection { fail_commit: true, ..Self::default() }
+    }
+
+    /// Returns the statements committed so far.
+    pub fn committed(&self) -> Vec<String> {
+        self.state.borrow().committed.clone()
+    }
+
+    /// Returns the transaction events recorded so far.
+    pub fn events(&self) -> Vec<&'static str> {
+        self.state.borrow().events.clone()
+    }
+}
+
+impl Transaction for MockConnection {
+    type Guard = MockTx;
+
+    fn begin(&mut self) -> Result<MockTx, TxError> {
+        if self.fail_begin {
+            return Err(TxError::new("begin failed"));
+        }
+        self.state.borrow_mut().events.push("begin");
+        Ok(MockTx { state: Rc::clone(&self.state), staged: Vec::new(), fail_commit: self.fail_commit })
+    }
+}
+
+/// The transaction handle of a [`MockConnection`].
+#[derive(Debug)]
+pub struct MockTx {
+    state: Rc<RefCell<MockState>>,
+    staged: Vec<String>,
+    fail_commit: bool,
+}
+
+impl MockTx {
+    /// Stages `statement` until the transaction commits.
+    pub fn execute(&mut self, statement: impl Into<String>) {
+        self.staged.push(statement.into());
+    }
+}
+
+impl TxGuard for MockTx {
+    fn commit(self) -> Result<(), TxError> {
+        if self.fail_commit {
+            self.rollback();
+            return Err(TxError::new("commit failed"));
+        }
+        let mut state = self.state.borrow_mut();
+        state.committed.extend(self.staged);
+        state.events.push("commit");
+        Ok(())
+    }
+
+    fn rollback(self) {
+        self.state.borrow_mut().events.push("rollback");
+    }
+}
+
+macro_rules! impl_db_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, Own, Transaction, TxError, TxGuard, TxMark};
+
+            // --- Transaction ---
+            #[cfg(feature = $feat)]
+            impl<'c, F, C: Transaction + ?Sized, $($Params,)* R> Curry<$N, $TupleType, Own, Own, TxMark, &'c mut C, R> for F
+            where F: FnOnce(&mut C, &mut C::Guard, $($Params),*) -> Result<R, TxError> {
+                type Curry = impl FnOnce($($Params),*) -> Result<R, TxError>;
+                #[inline(always)] fn curry(self, arg0: &'c mut C) -> Self::Curry {
+                    move |$($Params),*| {
+                        let mut tx = arg0.begin()?;
+                        match self(arg0, &mut tx, $($Params),*) {
+                            Ok(r) => tx.commit().map(|()| r),
+                            Err(e) => {
+                                tx.rollback();
+                                Err(e)
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_db_arity);
+}
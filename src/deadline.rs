@@ -0,0 +1,69 @@
+//! Pipe stages checked against an absolute deadline.
+
+use core::fmt;
+use std::time::Instant;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Error returned when a stage starts or finishes after its deadline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeadlineExceeded;
+
+impl fmt::Display for DeadlineExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline exceeded")
+    }
+}
+
+impl std::error::Error for DeadlineExceeded {}
+
+/// Extension trait for piping values under a deadline.
+pub trait PipeDeadline<const ARITY: usize> {
+    /// Like `pipe`, but returns `Err(DeadlineExceeded)` without calling `f` if
+    /// `deadline` has already passed, and discards `f`'s result in favor of
+    /// `Err(DeadlineExceeded)` if it passes while `f` runs.
+    ///
+    /// Because the deadline is absolute, one `Instant` can be threaded through
+    /// every stage of a request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{DeadlineExceeded, PipeDeadline};
+    /// use std::time::{Duration, Instant};
+    ///
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let deadline = Instant::now() + Duration::from_secs(5);
+    /// let x = 1.pipe_with_deadline(deadline, add)(2).and_then(|x| x.pipe_with_deadline(deadline, add)(3));
+    /// assert_eq!(x, Ok(6));
+    ///
+    /// let expired = Instant::now();
+    /// assert_eq!(1.pipe_with_deadline(expired, add)(2), Err(DeadlineExceeded));
+    /// ```
+    #[inline(always)]
+    fn pipe_with_deadline<R, F, Params>(self, deadline: Instant, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Deadline, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Deadline(deadline))
+    }
+}
+impl<const ARITY: usize, T> PipeDeadline<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: checks the deadline before and after the call.
+pub struct Deadline(Instant);
+
+impl<A0, R> Around<A0, R> for Deadline {
+    type Output = Result<R, DeadlineExceeded>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        if Instant::now() >= self.0 {
+            return Err(DeadlineExceeded);
+        }
+        let result = call(arg0);
+        if Instant::now() > self.0 { Err(DeadlineExceeded) } else { Ok(result) }
+    }
+}
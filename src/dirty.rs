@@ -0,0 +1,163 @@
+//! A value that knows whether it differs from its last saved state.
+
+use crate::{Curry, Imm, Own};
+
+/// A value together with a copy of it as of the last [`mark_clean`](Dirty::mark_clean).
+///
+/// The value is dirty while it differs from that copy, so changing it and
+/// changing it back leaves it clean.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::Dirty;
+/// fn persist(_config: &Vec<&str>, writes: &mut u32) { *writes += 1; }
+///
+/// let mut writes = 0;
+/// let config = Dirty::new(vec!["a"]);
+/// let mut config = config.tap_check_dirty(persist)(&mut writes);
+/// assert_eq!(writes, 0);
+///
+/// config.get_mut().push("b");
+/// let config = config.tap_check_dirty(persist)(&mut writes);
+/// assert_eq!(writes, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Dirty<T> {
+    current: T,
+    original: T,
+}
+
+impl<T: Clone> Dirty<T> {
+    /// Wraps `value`, clean.
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Dirty { original: value.clone(), current: value }
+    }
+
+    /// Records the current value as the saved state, making it clean.
+    #[inline]
+    pub fn mark_clean(&mut self) {
+        self.original.clone_from(&self.current);
+    }
+}
+
+impl<T> Dirty<T> {
+    /// Returns the current value.
+    #[inline]
+    pub const fn get(&self) -> &T {
+        &self.current
+    }
+
+    /// Returns the current value for modification.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.current
+    }
+
+    /// Replaces the current value.
+    #[inline]
+    pub fn set(&mut self, value: T) {
+        self.current = value;
+    }
+
+    /// Returns the value as of the last [`mark_clean`](Dirty::mark_clean).
+    #[inline]
+    pub const fn original(&self) -> &T {
+        &self.original
+    }
+
+    /// Returns the current value, discarding the saved state.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.current
+    }
+}
+
+impl<T: PartialEq> Dirty<T> {
+    /// Returns `true` if the current value differs from the saved state.
+    #[inline]
+    pub fn is_dirty(&self) -> bool {
+        self.current != self.original
+    }
+
+    /// Returns a closure over the remaining arguments that calls
+    /// `on_dirty(&value, args..)` if the value is dirty, and returns `self`.
+    #[inline(always)]
+    pub fn tap_check_dirty<const ARITY: usize, R, F, Params>(self, on_dirty: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, CheckDirtyMark, Self, R>,
+    {
+        on_dirty.curry(self)
+    }
+
+    /// Returns a closure over the remaining arguments that returns
+    /// `Some(f(value, args..))` if the value is dirty, and `None` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::Dirty;
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let mut count = Dirty::new(1);
+    /// assert_eq!(count.clone().pipe_if_dirty(add)(10), None);
+    /// count.set(2);
+    /// assert_eq!(count.pipe_if_dirty(add)(10), Some(12));
+    /// ```
+    #[inline(always)]
+    pub fn pipe_if_dirty<const ARITY: usize, R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, IfDirtyMark, Self, R>,
+    {
+        f.curry(self)
+    }
+}
+
+#[doc(hidden)]
+/// Marker type: `tap_check_dirty` semantics (tap the value only if dirty).
+pub struct CheckDirtyMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_if_dirty` semantics (pipe the value only if dirty).
+pub struct IfDirtyMark;
+
+macro_rules! impl_dirty_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CheckDirtyMark, Curry, Dirty, IfDirtyMark, Imm, Own};
+
+            // --- Check ---
+            #[cfg(feature = $feat)]
+            impl<F, T: PartialEq, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, CheckDirtyMark, Dirty<T>, R> for F
+            where F: FnOnce(&T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Dirty<T>;
+                #[inline(always)] fn curry(self, arg0: Dirty<T>) -> Self::Curry {
+                    move |$($Params),*| {
+                        if arg0.is_dirty() {
+                            self(&arg0.current, $($Params),*);
+                        }
+                        arg0
+                    }
+                }
+            }
+
+            // --- Pipe ---
+            #[cfg(feature = $feat)]
+            impl<F, T: PartialEq, $($Params,)* R> Curry<$N, $TupleType, Own, Own, IfDirtyMark, Dirty<T>, R> for F
+            where F: FnOnce(T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Option<R>;
+                #[inline(always)] fn curry(self, arg0: Dirty<T>) -> Self::Curry {
+                    move |$($Params),*| arg0.is_dirty().then(|| self(arg0.current, $($Params),*))
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_dirty_arity);
+}
@@ -0,0 +1,131 @@
+//! Table-driven dispatch: look up the function to pipe a value into by key.
+
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use std::boxed::Box;
+use std::collections::HashMap;
+use std::string::String;
+
+/// Extension trait for piping a value into a function selected from a table.
+pub trait PipeDispatch: Sized {
+    /// Looks up `key` in `table` and pipes `self` into the function found,
+    /// or returns `None` if the key is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeDispatch;
+    /// use std::collections::HashMap;
+    ///
+    /// let table = HashMap::from([("neg", (|x: i32| -x) as fn(i32) -> i32), ("sq", |x| x * x)]);
+    /// assert_eq!(3.pipe_select_from_map(&table, "sq"), Some(9));
+    /// assert_eq!(3.pipe_select_from_map(&table, "abs"), None);
+    /// ```
+    #[inline]
+    fn pipe_select_from_map<K, Q, R, S>(self, table: &HashMap<K, fn(Self) -> R, S>, key: &Q) -> Option<R>
+    where
+        K: Borrow<Q> + Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+        S: BuildHasher,
+    {
+        table.get(key).map(|f| f(self))
+    }
+
+    /// Like [`pipe_select_from_map`](PipeDispatch::pipe_select_from_map), for
+    /// functions that take one extra argument.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeDispatch;
+    /// use std::collections::HashMap;
+    ///
+    /// let table = HashMap::from([("add", (|x, y| x + y) as fn(i32, i32) -> i32), ("mul", |x, y| x * y)]);
+    /// assert_eq!(3.pipe_select_from_map_with(&table, "mul", 4), Some(12));
+    /// ```
+    #[inline]
+    fn pipe_select_from_map_with<K, Q, A, R, S>(
+        self,
+        table: &HashMap<K, fn(Self, A) -> R, S>,
+        key: &Q,
+        extra: A,
+    ) -> Option<R>
+    where
+        K: Borrow<Q> + Hash + Eq,
+        Q: Hash + Eq + ?Sized,
+        S: BuildHasher,
+    {
+        table.get(key).map(|f| f(self, extra))
+    }
+
+    /// Pipes `self` into the handler registered under `key` in `table`, or
+    /// returns `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipeDispatch, PipeDispatchTable};
+    /// let offset = 100;
+    /// let table = PipeDispatchTable::new()
+    ///     .route("shift", move |x: i32| x + offset)
+    ///     .route("neg", |x: i32| -x);
+    ///
+    /// assert_eq!(1.pipe_dispatch(&table, "shift"), Some(101));
+    /// assert_eq!(1.pipe_dispatch(&table, "nope"), None);
+    /// ```
+    #[inline]
+    fn pipe_dispatch<R>(self, table: &PipeDispatchTable<Self, R>, key: &str) -> Option<R> {
+        table.dispatch(key, self)
+    }
+}
+impl<T> PipeDispatch for T {}
+
+/// A routing table of named handlers, which, unlike a table of function
+/// pointers, may be closures that capture their environment.
+pub struct PipeDispatchTable<T, R> {
+    routes: HashMap<String, Box<dyn Fn(T) -> R>>,
+}
+
+impl<T, R> PipeDispatchTable<T, R> {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self { routes: HashMap::new() }
+    }
+
+    /// Registers `handler` under `key`, replacing any previous handler, and returns the table.
+    pub fn route(mut self, key: impl Into<String>, handler: impl Fn(T) -> R + 'static) -> Self {
+        self.insert(key, handler);
+        self
+    }
+
+    /// Registers `handler` under `key`, replacing any previous handler.
+    pub fn insert(&mut self, key: impl Into<String>, handler: impl Fn(T) -> R + 'static) {
+        self.routes.insert(key.into(), Box::new(handler));
+    }
+
+    /// Calls the handler registered under `key` with `value`, or returns `None`.
+    pub fn dispatch(&self, key: &str, value: T) -> Option<R> {
+        self.routes.get(key).map(|f| f(value))
+    }
+
+    /// Returns `true` if a handler is registered under `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.routes.contains_key(key)
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Returns `true` if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+}
+
+impl<T, R> Default for PipeDispatchTable<T, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
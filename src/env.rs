@@ -0,0 +1,155 @@
+//! Pipelines that start from an environment variable.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+use std::env::{self, VarError};
+use std::string::String;
+
+use crate::{Curry, Imm, Own};
+
+/// Error returned when an environment variable cannot be read as a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvError<E> {
+    /// The variable is not set.
+    Missing(String),
+    /// The variable is set, but not to valid Unicode.
+    NotUnicode(String),
+    /// The variable is set, but `FromStr` rejected its value.
+    ParseError(String, E),
+}
+
+impl<E> EnvError<E> {
+    /// Returns the name of the variable that was read.
+    pub fn var_name(&self) -> &str {
+        match self {
+            EnvError::Missing(name) | EnvError::NotUnicode(name) | EnvError::ParseError(name, _) => name,
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for EnvError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvError::Missing(name) => write!(f, "environment variable `{name}` is not set"),
+            EnvError::NotUnicode(name) => write!(f, "environment variable `{name}` is not valid unicode"),
+            EnvError::ParseError(name, e) => write!(f, "environment variable `{name}` could not be parsed: {e}"),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for EnvError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EnvError::ParseError(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Extension trait for parsing environment variables into pipeline values.
+///
+/// The variable is read when the returned closure is called, not when it is built.
+pub trait PipeFromEnv<const ARITY: usize>: FromStr + Sized {
+    /// Returns a closure over the remaining arguments that reads `var_name`,
+    /// parses it as `Self`, and pipes it into `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{EnvError, PipeFromEnv};
+    /// fn bind_addr(port: u16, host: &str) -> String { format!("{host}:{port}") }
+    ///
+    /// std::env::set_var("PIPEI_DOC_PORT", "8080");
+    /// assert_eq!(u16::pipe_from_env("PIPEI_DOC_PORT", bind_addr)("localhost").unwrap(), "localhost:8080");
+    ///
+    /// let err = u16::pipe_from_env("PIPEI_DOC_UNSET", bind_addr)("localhost").unwrap_err();
+    /// assert_eq!(err, EnvError::Missing("PIPEI_DOC_UNSET".into()));
+    /// ```
+    #[inline(always)]
+    fn pipe_from_env<'v, R, F, Params>(var_name: &'v str, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, EnvMark, EnvVar<'v, Self>, R>,
+    {
+        f.curry(EnvVar(var_name, PhantomData))
+    }
+
+    /// Like [`pipe_from_env`](PipeFromEnv::pipe_from_env), but passes the parsed
+    /// value to `handler` by reference and returns it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeFromEnv;
+    /// std::env::set_var("PIPEI_DOC_VERBOSE", "true");
+    ///
+    /// let verbose = bool::tap_env("PIPEI_DOC_VERBOSE", |v: &bool| assert!(*v))();
+    /// assert_eq!(verbose, Ok(true));
+    /// ```
+    #[inline(always)]
+    fn tap_env<'v, R, F, Params>(var_name: &'v str, handler: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, EnvMark, EnvVar<'v, Self>, R>,
+    {
+        handler.curry(EnvVar(var_name, PhantomData))
+    }
+}
+impl<const ARITY: usize, T: FromStr> PipeFromEnv<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_from_env` semantics (read and parse the variable first).
+pub struct EnvMark;
+
+any_arity! {
+    allow_dead_code;
+
+    #[doc(hidden)]
+    /// Internal: the name of an environment variable to be parsed as `T`.
+    pub struct EnvVar<'v, T>(&'v str, PhantomData<fn() -> T>);
+
+    impl<T: FromStr> EnvVar<'_, T> {
+        fn read(self) -> Result<T, EnvError<T::Err>> {
+            let name = self.0;
+            match env::var(name) {
+                Ok(raw) => raw.parse().map_err(|e| EnvError::ParseError(name.into(), e)),
+                Err(VarError::NotPresent) => Err(EnvError::Missing(name.into())),
+                Err(VarError::NotUnicode(_)) => Err(EnvError::NotUnicode(name.into())),
+            }
+        }
+    }
+}
+
+macro_rules! impl_env_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, EnvError, EnvMark, EnvVar, FromStr, Imm, Own};
+
+            // --- Pipe ---
+            #[cfg(feature = $feat)]
+            impl<'v, F, T: FromStr, $($Params,)* R> Curry<$N, $TupleType, Own, Own, EnvMark, EnvVar<'v, T>, R> for F
+            where F: FnOnce(T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Result<R, EnvError<T::Err>>;
+                #[inline(always)] fn curry(self, arg0: EnvVar<'v, T>) -> Self::Curry {
+                    |$($Params),*| arg0.read().map(|value| self(value, $($Params),*))
+                }
+            }
+
+            // --- Tap ---
+            #[cfg(feature = $feat)]
+            impl<'v, F, T: FromStr, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, EnvMark, EnvVar<'v, T>, R> for F
+            where F: FnOnce(&T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, EnvError<T::Err>>;
+                #[inline(always)] fn curry(self, arg0: EnvVar<'v, T>) -> Self::Curry {
+                    |$($Params),*| arg0.read().map(|value| { self(&value, $($Params),*); value })
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_env_arity);
+}
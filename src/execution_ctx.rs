@@ -0,0 +1,133 @@
+//! Thread-local execution context: the request, user, timing and free-form
+//! tags of a pipeline, in one place, without passing them through every function.
+
+use core::cell::RefCell;
+use std::collections::HashMap;
+use std::string::String;
+use std::time::{Duration, Instant};
+
+use crate::scoped::with_scoped_then;
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// The cross-cutting state a stage runs under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionContext {
+    /// Identifies the request, for correlating logs and traces.
+    pub correlation_id: u128,
+    /// The authenticated user, if any.
+    pub user_id: Option<u64>,
+    /// When the work started.
+    pub start_time: Instant,
+    /// Free-form annotations, which stages may add to while they run.
+    pub tags: HashMap<String, String>,
+}
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<ExecutionContext>> = const { RefCell::new(None) };
+}
+
+impl ExecutionContext {
+    /// Creates a context with no user and no tags, started now.
+    pub fn new(correlation_id: u128) -> Self {
+        ExecutionContext { correlation_id, user_id: None, start_time: Instant::now(), tags: HashMap::new() }
+    }
+
+    /// Returns the time elapsed since `start_time`.
+    pub fn elapsed(&self) -> Duration {
+        self.start_time.elapsed()
+    }
+
+    /// Returns a copy of the context of the innermost stage started by
+    /// [`pipe_with_execution_context`](PipeExecutionContext::pipe_with_execution_context)
+    /// on this thread, or `None` if there is none.
+    ///
+    /// Changes to the copy do not reach the active context; use
+    /// [`with_current_mut`](ExecutionContext::with_current_mut) for that.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within `with_current_mut`.
+    pub fn current() -> Option<ExecutionContext> {
+        Self::with_current(ExecutionContext::clone)
+    }
+
+    /// Calls `f` with the context of the innermost stage started by
+    /// [`pipe_with_execution_context`](PipeExecutionContext::pipe_with_execution_context)
+    /// on this thread, or returns `None` if there is none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within [`with_current_mut`](ExecutionContext::with_current_mut),
+    /// or if `f` starts a stage with its own execution context, since the
+    /// active context stays borrowed while `f` runs.
+    pub fn with_current<R>(f: impl FnOnce(&ExecutionContext) -> R) -> Option<R> {
+        CURRENT.with_borrow(|ctx| ctx.as_ref().map(f))
+    }
+
+    /// Like [`with_current`](ExecutionContext::with_current), but `f` may modify
+    /// the context, e.g. to add tags.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from within `with_current` or `with_current_mut`, or if
+    /// `f` starts a stage with its own execution context.
+    pub fn with_current_mut<R>(f: impl FnOnce(&mut ExecutionContext) -> R) -> Option<R> {
+        CURRENT.with_borrow_mut(|ctx| ctx.as_mut().map(f))
+    }
+}
+
+/// Extension trait for running pipe stages under an execution context.
+pub trait PipeExecutionContext<const ARITY: usize> {
+    /// Like `pipe`, but makes `ctx` the current execution context while `f` runs.
+    ///
+    /// The context is borrowed mutably: changes made to it while `f` runs, such
+    /// as added tags, are kept in `ctx` afterwards, also if `f` panics. The
+    /// outer context, if any, becomes current again when `f` is done.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::execution_ctx::{ExecutionContext, PipeExecutionContext};
+    /// fn charge(cents: u64, currency: &str) -> String {
+    ///     ExecutionContext::with_current_mut(|ctx| {
+    ///         ctx.tags.insert("currency".into(), currency.into());
+    ///         format!("user {:?} charged {cents} {currency}", ctx.user_id)
+    ///     })
+    ///     .unwrap()
+    /// }
+    ///
+    /// let mut ctx = ExecutionContext { user_id: Some(7), ..ExecutionContext::new(1) };
+    /// assert_eq!(250.pipe_with_execution_context(&mut ctx, charge)("EUR"), "user Some(7) charged 250 EUR");
+    /// assert_eq!(ctx.tags["currency"], "EUR");
+    /// assert_eq!(ExecutionContext::with_current(|_| ()), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_execution_context<'c, R, F, Params>(self, ctx: &'c mut ExecutionContext, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, WithExecution<'c>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, WithExecution(ctx))
+    }
+}
+impl<const ARITY: usize, T> PipeExecutionContext<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call with the execution context set.
+pub struct WithExecution<'c>(&'c mut ExecutionContext);
+
+impl<A0, R> Around<A0, R> for WithExecution<'_> {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        let owner = self.0;
+        let placeholder = ExecutionContext {
+            correlation_id: owner.correlation_id,
+            user_id: owner.user_id,
+            start_time: owner.start_time,
+            tags: HashMap::new(),
+        };
+        let active = core::mem::replace(owner, placeholder);
+        with_scoped_then(&CURRENT, active, || call(arg0), |ctx| *owner = ctx)
+    }
+}
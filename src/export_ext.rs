@@ -0,0 +1,94 @@
+//! Taps that hand a value to a metrics backend.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::fmt::Debug;
+
+/// A value that can be exported as a metric: exporters may downcast it with
+/// [`as_any`](Metric::as_any) to read known types, or format it with `Debug`.
+pub trait Metric: Any + Debug {
+    /// Returns `self` as `&dyn Any`, for downcasting to a concrete type.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any + Debug> Metric for T {
+    #[inline(always)]
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A backend that receives named metric values, e.g. a client for an
+/// external metrics system.
+///
+/// The trait is object safe, so exporters can be passed as `&mut dyn MetricsExporter`.
+pub trait MetricsExporter {
+    /// Records `value` under `name`.
+    fn export(&mut self, name: &str, value: &dyn Metric);
+}
+
+/// An exporter that prints each metric to stderr as `"{name}: {value:?}"`.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugMetricsExporter;
+
+#[cfg(feature = "std")]
+impl MetricsExporter for DebugMetricsExporter {
+    fn export(&mut self, name: &str, value: &dyn Metric) {
+        std::eprintln!("{name}: {value:?}");
+    }
+}
+
+/// An exporter that stores each metric as a `(name, format!("{value:?}"))` pair.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VecMetricsExporter {
+    exports: Vec<(String, String)>,
+}
+
+impl VecMetricsExporter {
+    /// Creates an exporter with no exports.
+    pub const fn new() -> Self {
+        VecMetricsExporter { exports: Vec::new() }
+    }
+
+    /// Returns the exports recorded so far, oldest first.
+    pub fn exports(&self) -> &[(String, String)] {
+        &self.exports
+    }
+}
+
+impl MetricsExporter for VecMetricsExporter {
+    fn export(&mut self, name: &str, value: &dyn Metric) {
+        self.exports.push((name.into(), alloc::format!("{value:?}")));
+    }
+}
+
+/// Extension trait for exporting a value as a metric mid-pipeline.
+pub trait TapExport: Sized {
+    /// Calls `exporter.export(name, &self)`, then returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, TapExport, VecMetricsExporter};
+    /// fn scale(x: u64, k: u64) -> u64 { x * k }
+    ///
+    /// let mut exporter = VecMetricsExporter::new();
+    /// let bytes = 4
+    ///     .pipe(scale)(1024)
+    ///     .tap_with_export(&mut exporter, "buffer_bytes");
+    ///
+    /// assert_eq!(bytes, 4096);
+    /// assert_eq!(exporter.exports(), [("buffer_bytes".to_string(), "4096".to_string())]);
+    /// ```
+    #[inline(always)]
+    fn tap_with_export<E: MetricsExporter + ?Sized>(self, exporter: &mut E, name: &str) -> Self
+    where
+        Self: Metric,
+    {
+        exporter.export(name, &self);
+        self
+    }
+}
+impl<T> TapExport for T {}
@@ -0,0 +1,118 @@
+//! Pipes that drive `Result`-returning functions.
+
+use crate::{Curry, Own};
+
+/// Extension trait for repeating a fallible stage.
+pub trait PipeWhileOk<const ARITY: usize> {
+    /// Curries `self` into `f`, returning a closure over the remaining arguments
+    /// that calls `f` repeatedly, feeding each `Ok` value into the next call,
+    /// until it returns `Err`.
+    ///
+    /// If the first call fails its error is returned; otherwise the last `Ok`
+    /// value is returned once `f` fails. The value is cloned before each call so
+    /// that it survives the failing one, and the remaining arguments are cloned
+    /// for each call. The loop does not end until `f` fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeWhileOk;
+    /// fn step(x: u32, limit: u32) -> Result<u32, &'static str> {
+    ///     if x * 2 <= limit { Ok(x * 2) } else { Err("limit") }
+    /// }
+    ///
+    /// assert_eq!(1.pipe_while_ok(step)(100), Ok(64));
+    /// assert_eq!(101.pipe_while_ok(step)(100), Err("limit"));
+    /// ```
+    #[inline(always)]
+    fn pipe_while_ok<E, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, WhileOkMark, Self, E>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeWhileOk<ARITY> for T {}
+
+/// Extension trait for piping into a single fallible stage.
+pub trait PipeTry<const ARITY: usize> {
+    /// Like `pipe`, but only accepts an `f` returning `Result<T, E>`, so the
+    /// closure's result can be propagated with `?` and a mismatched `f` is
+    /// reported at the `try_pipe` call rather than at the `?`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeTry;
+    /// use std::num::ParseIntError;
+    ///
+    /// fn parse(s: &str, radix: u32) -> Result<u32, ParseIntError> { u32::from_str_radix(s, radix) }
+    ///
+    /// fn sum(a: &str, b: &str) -> Result<u32, ParseIntError> {
+    ///     Ok(a.try_pipe(parse)(16)? + b.try_pipe(parse)(16)?)
+    /// }
+    ///
+    /// assert_eq!(sum("a", "f"), Ok(25));
+    /// assert!(sum("a", "g").is_err());
+    /// ```
+    #[inline(always)]
+    fn try_pipe<T, E, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, TryPipeMark, Self, (T, E)>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeTry<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_while_ok` semantics (repeat while `Ok`).
+pub struct WhileOkMark;
+
+#[doc(hidden)]
+/// Marker type: `try_pipe` semantics. The result slot carries `(T, E)`, the
+/// success and error types of `f`.
+pub struct TryPipeMark;
+
+macro_rules! impl_fallible_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, Own, TryPipeMark, WhileOkMark};
+
+            // --- While Ok ---
+            #[cfg(feature = $feat)]
+            impl<F, A0: Clone, $($Params: Clone,)* E> Curry<$N, $TupleType, Own, Own, WhileOkMark, A0, E> for F
+            where F: FnMut(A0, $($Params),*) -> Result<A0, E> {
+                type Curry = impl FnOnce($($Params),*) -> Result<A0, E>;
+                #[inline(always)] fn curry(mut self, arg0: A0) -> Self::Curry {
+                    move |$($Params),*| {
+                        let mut last = self(arg0, $($Params.clone()),*)?;
+                        while let Ok(next) = self(last.clone(), $($Params.clone()),*) {
+                            last = next;
+                        }
+                        Ok(last)
+                    }
+                }
+            }
+
+            // --- Try ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* T, E> Curry<$N, $TupleType, Own, Own, TryPipeMark, A0, (T, E)> for F
+            where F: FnOnce(A0, $($Params),*) -> Result<T, E> {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, E>;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    |$($Params),*| self(arg0, $($Params),*)
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_fallible_arity);
+}
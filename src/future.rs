@@ -0,0 +1,123 @@
+//! Pipes and taps through async functions.
+
+use crate::{Curry, Own};
+
+/// Extension trait for piping into an async function.
+pub trait PipeAsync<const ARITY: usize> {
+    /// Like `pipe`, but for an `f` returning a future: the closure over the
+    /// remaining arguments returns the future of `f(self, args..)`, which
+    /// resolves to its result. The future is `Send` if `f`'s future is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeAsync;
+    /// # use core::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+    /// # fn block_on<F: Future>(fut: F) -> F::Output {
+    /// #     let mut fut = pin!(fut);
+    /// #     loop { if let Poll::Ready(r) = fut.as_mut().poll(&mut Context::from_waker(Waker::noop())) { return r } }
+    /// # }
+    /// async fn fetch(id: u32, host: &str) -> String { format!("{host}/{id}") }
+    ///
+    /// let url = block_on(async { 7.pipe_async(fetch)("example.com").await });
+    /// assert_eq!(url, "example.com/7");
+    /// ```
+    #[inline(always)]
+    fn pipe_async<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, AsyncPipeMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeAsync<ARITY> for T {}
+
+/// Extension trait for running an async side effect.
+pub trait TapAsync<const ARITY: usize, State> {
+    /// Like `tap`, but for an async `f`: the closure over the remaining
+    /// arguments returns a future that awaits `f(&self, args..)`, or
+    /// `f(&mut self, args..)`, and resolves to `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapAsync;
+    /// # use core::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+    /// # fn block_on<F: Future>(fut: F) -> F::Output {
+    /// #     let mut fut = pin!(fut);
+    /// #     loop { if let Poll::Ready(r) = fut.as_mut().poll(&mut Context::from_waker(Waker::noop())) { return r } }
+    /// # }
+    /// async fn audit(x: &u32, log: &mut Vec<u32>) { log.push(*x) }
+    /// async fn bump(x: &mut u32, by: u32) { *x += by }
+    ///
+    /// let mut log = Vec::new();
+    /// let x = block_on(async { 1.tap_async(audit)(&mut log).await.tap_async(bump)(10).await });
+    /// assert_eq!((x, log), (11, vec![1]));
+    /// ```
+    #[inline(always)]
+    fn tap_async<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, State, Own, AsyncTapMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, State, T> TapAsync<ARITY, State> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_async` semantics (return the function's future).
+pub struct AsyncPipeMark;
+
+#[doc(hidden)]
+/// Marker type: `tap_async` semantics (await the function, then return the value).
+pub struct AsyncTapMark;
+
+macro_rules! impl_future_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{AsyncPipeMark, AsyncTapMark, Curry, Own};
+            #[cfg(feature = $feat)]
+            use crate::{Imm, Mut};
+            #[cfg(feature = $feat)]
+            use core::future::Future;
+
+            // --- Pipe ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* Fut, R> Curry<$N, $TupleType, Own, Own, AsyncPipeMark, A0, R> for F
+            where F: FnOnce(A0, $($Params),*) -> Fut, Fut: Future<Output = R> {
+                type Curry = impl FnOnce($($Params),*) -> Fut;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    |$($Params),*| self(arg0, $($Params),*)
+                }
+            }
+
+            // --- Tap ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, AsyncTapMark, A0, R> for F
+            where F: AsyncFnOnce(&A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> impl Future<Output = A0>;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    |$($Params),*| async move { self(&arg0, $($Params),*).await; arg0 }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R> Curry<$N, $TupleType, Mut, Own, AsyncTapMark, A0, R> for F
+            where F: AsyncFnOnce(&mut A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> impl Future<Output = A0>;
+                #[inline(always)] fn curry(self, mut arg0: A0) -> Self::Curry {
+                    |$($Params),*| async move { self(&mut arg0, $($Params),*).await; arg0 }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_future_arity);
+}
@@ -0,0 +1,167 @@
+//! A wrapper that records the names of the stages a value passes through.
+
+use alloc::vec::Vec;
+
+use crate::{CurryWith, Imm, Own};
+
+/// A value together with the names of the stages applied to it.
+///
+/// With `debug_assertions` on, each [`pipe_named`](PipelineGraph::pipe_named) and
+/// [`tap_named`](PipelineGraph::tap_named) stage appends its name to a trace. With
+/// them off, `PipelineGraph<T>` is a plain newtype over `T`: stage names are
+/// discarded and nothing is allocated.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::PipelineGraph;
+/// fn add(x: i32, y: i32) -> i32 { x + y }
+///
+/// let (value, names) = PipelineGraph::new("start", 1)
+///     .pipe_named("add", add)(2)
+///     .tap_named("check", |x: &i32| assert_eq!(*x, 3))()
+///     .into_traced();
+///
+/// assert_eq!(value, 3);
+/// if cfg!(debug_assertions) {
+///     assert_eq!(names, ["start", "add", "check"]);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineGraph<T> {
+    value: T,
+    #[cfg(debug_assertions)]
+    names: Vec<&'static str>,
+}
+
+impl<T> PipelineGraph<T> {
+    /// Starts a trace whose first stage is `name`.
+    #[inline]
+    pub fn new(name: &'static str, value: T) -> Self {
+        #[cfg(not(debug_assertions))]
+        let _ = name;
+        PipelineGraph {
+            value,
+            #[cfg(debug_assertions)]
+            names: alloc::vec![name],
+        }
+    }
+
+    /// Returns a reference to the current value.
+    #[inline]
+    pub const fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the stage names recorded so far; always empty without `debug_assertions`.
+    #[inline]
+    pub fn names(&self) -> &[&'static str] {
+        #[cfg(debug_assertions)]
+        return &self.names;
+        #[cfg(not(debug_assertions))]
+        return &[];
+    }
+
+    /// Extracts the current value, discarding the trace.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Extracts the current value and the recorded stage names.
+    #[inline]
+    pub fn into_traced(self) -> (T, Vec<&'static str>) {
+        #[cfg(debug_assertions)]
+        return (self.value, self.names);
+        #[cfg(not(debug_assertions))]
+        return (self.value, Vec::new());
+    }
+
+    /// Records `name` and curries the current value into `f`, returning a closure
+    /// over the remaining arguments that yields `f`'s result with the trace so far.
+    #[inline(always)]
+    pub fn pipe_named<const ARITY: usize, R, F, Params>(self, name: &'static str, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, NamedMark, Self, &'static str, R>,
+    {
+        f.curry_with(self, name)
+    }
+
+    /// Records `name` and curries a reference to the current value into `f` for a
+    /// side effect, returning a closure over the remaining arguments that yields
+    /// the unchanged graph.
+    #[inline(always)]
+    pub fn tap_named<const ARITY: usize, R, F, Params>(self, name: &'static str, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Imm, NamedMark, Self, &'static str, R>,
+    {
+        f.curry_with(self, name)
+    }
+}
+
+any_arity! {
+    impl<T> PipelineGraph<T> {
+        #[inline(always)]
+        fn record(&mut self, name: &'static str) {
+            #[cfg(debug_assertions)]
+            self.names.push(name);
+            #[cfg(not(debug_assertions))]
+            let _ = name;
+        }
+
+        #[inline(always)]
+        fn map<R>(self, f: impl FnOnce(T) -> R) -> PipelineGraph<R> {
+            PipelineGraph {
+                value: f(self.value),
+                #[cfg(debug_assertions)]
+                names: self.names,
+            }
+        }
+    }
+}
+
+#[doc(hidden)]
+/// Marker type: `pipe_named` / `tap_named` semantics (record the stage name).
+pub struct NamedMark;
+
+macro_rules! impl_graph_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryWith, Imm, NamedMark, Own, PipelineGraph};
+
+            // --- Pipe Named ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R> CurryWith<$N, $TupleType, Own, NamedMark, PipelineGraph<A0>, &'static str, R> for F
+            where F: FnOnce(A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> PipelineGraph<R>;
+                #[inline(always)] fn curry_with(self, mut arg0: PipelineGraph<A0>, name: &'static str) -> Self::Curry {
+                    move |$($Params),*| {
+                        arg0.record(name);
+                        arg0.map(|v| self(v, $($Params),*))
+                    }
+                }
+            }
+
+            // --- Tap Named ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R> CurryWith<$N, $TupleType, Imm, NamedMark, PipelineGraph<A0>, &'static str, R> for F
+            where F: FnOnce(&A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> PipelineGraph<A0>;
+                #[inline(always)] fn curry_with(self, mut arg0: PipelineGraph<A0>, name: &'static str) -> Self::Curry {
+                    move |$($Params),*| {
+                        arg0.record(name);
+                        self(&arg0.value, $($Params),*);
+                        arg0
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_graph_arity);
+}
@@ -0,0 +1,139 @@
+//! Scope guards: run a cleanup function when a value goes out of scope.
+
+use core::fmt;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Extension trait for attaching cleanup to a value.
+pub trait PipeOnDrop: Sized {
+    /// Wraps `self` in a [`DropGuard`] that passes it to `f` when the guard
+    /// is dropped. The guard dereferences to the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeOnDrop;
+    /// let mut log = Vec::new();
+    /// {
+    ///     let mut buf = Vec::new().pipe_on_drop(|b: Vec<i32>| log.push(b.len()));
+    ///     buf.push(1);
+    ///     buf.push(2);
+    /// }
+    /// assert_eq!(log, [2]);
+    /// ```
+    #[inline(always)]
+    fn pipe_on_drop<F: FnOnce(Self)>(self, f: F) -> DropGuard<Self, F> {
+        DropGuard { value: ManuallyDrop::new(self), f: ManuallyDrop::new(f) }
+    }
+}
+impl<T> PipeOnDrop for T {}
+
+/// Extension trait for pipe stages that own a resource needing cleanup on failure.
+pub trait PipeResourceGuard<const ARITY: usize> {
+    /// Like `pipe`, but holds `resource` while `f` runs. If `f` panics,
+    /// `cleanup(resource)` runs as the panic unwinds; otherwise the resource
+    /// is returned alongside `f`'s result, as `(resource, result)`.
+    ///
+    /// The guard is only armed once the returned closure is called: if the
+    /// closure is dropped without being called, `resource` is dropped and
+    /// `cleanup` does not run.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeResourceGuard;
+    /// use std::cell::Cell;
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    /// fn parse(s: &str, radix: u32) -> u32 { u32::from_str_radix(s, radix).unwrap() }
+    ///
+    /// let released = Cell::new(false);
+    /// let release = |_lock: &str| released.set(true);
+    ///
+    /// let (lock, n) = "ff".pipe_with_resource_guard("lock", release, parse)(16);
+    /// assert_eq!((lock, n, released.get()), ("lock", 255, false));
+    ///
+    /// let failed = catch_unwind(AssertUnwindSafe(|| "zz".pipe_with_resource_guard("lock", release, parse)(16)));
+    /// assert!(failed.is_err() && released.get());
+    /// ```
+    #[inline(always)]
+    fn pipe_with_resource_guard<Res, C, R, F, Params>(self, resource: Res, cleanup: C, f: F) -> F::Curry
+    where
+        C: FnOnce(Res),
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, ResourceGuard<Res, C>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, ResourceGuard(resource, cleanup))
+    }
+}
+impl<const ARITY: usize, T> PipeResourceGuard<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: cleans up the resource if the call panics, and returns it otherwise.
+pub struct ResourceGuard<Res, C: FnOnce(Res)>(Res, C);
+
+impl<A0, R, Res, C: FnOnce(Res)> Around<A0, R> for ResourceGuard<Res, C> {
+    type Output = (Res, R);
+    #[inline(always)]
+    fn around<Call: FnOnce(A0) -> R>(self, arg0: A0, call: Call) -> Self::Output {
+        let guard = self.0.pipe_on_drop(self.1);
+        let result = call(arg0);
+        (guard.defuse(), result)
+    }
+}
+
+/// Guard returned by [`PipeOnDrop::pipe_on_drop`]: calls `f(value)` when dropped,
+/// unless [`defused`](DropGuard::defuse).
+///
+/// If `f` panics while another panic is already unwinding (for example, when
+/// the guard is dropped during unwinding), the process aborts, as with any
+/// panicking `Drop` implementation.
+pub struct DropGuard<T, F: FnOnce(T)> {
+    value: ManuallyDrop<T>,
+    f: ManuallyDrop<F>,
+}
+
+impl<T, F: FnOnce(T)> DropGuard<T, F> {
+    /// Returns the wrapped value without calling the cleanup function.
+    #[inline]
+    pub fn defuse(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so each field is taken or dropped exactly once.
+        unsafe {
+            ManuallyDrop::drop(&mut this.f);
+            ManuallyDrop::take(&mut this.value)
+        }
+    }
+}
+
+impl<T, F: FnOnce(T)> Drop for DropGuard<T, F> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the fields are only taken here and in `defuse`, which skips this destructor.
+        let (value, f) = unsafe { (ManuallyDrop::take(&mut self.value), ManuallyDrop::take(&mut self.f)) };
+        f(value);
+    }
+}
+
+impl<T, F: FnOnce(T)> Deref for DropGuard<T, F> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, F: FnOnce(T)> DerefMut for DropGuard<T, F> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T: fmt::Debug, F: FnOnce(T)> fmt::Debug for DropGuard<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DropGuard").field(&*self.value).finish()
+    }
+}
@@ -0,0 +1,97 @@
+//! Pipe stages that track, and are gated by, the health of the service behind them.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// The health of a service, as seen by the stages that call it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub enum HealthStatus {
+    /// Calls succeed.
+    #[default]
+    Healthy,
+    /// Calls still go through, but the service is impaired; holds why.
+    Degraded(String),
+    /// Calls are rejected; holds the error that made the service unhealthy.
+    Unhealthy(String),
+}
+
+impl HealthStatus {
+    /// Returns `true` if the status is [`Healthy`](HealthStatus::Healthy).
+    #[inline]
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, HealthStatus::Healthy)
+    }
+
+    /// Returns `true` unless the status is [`Unhealthy`](HealthStatus::Unhealthy).
+    #[inline]
+    pub fn is_available(&self) -> bool {
+        !matches!(self, HealthStatus::Unhealthy(_))
+    }
+}
+
+/// Error returned when a call is rejected because the service is unhealthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ServiceUnhealthy;
+
+impl fmt::Display for ServiceUnhealthy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("service is unhealthy")
+    }
+}
+
+impl core::error::Error for ServiceUnhealthy {}
+
+/// Extension trait for piping values through a health-tracked stage.
+pub trait PipeHealthCheck<const ARITY: usize> {
+    /// Like `pipe` for a fallible `f`, but rejects the call with
+    /// `Err(ServiceUnhealthy)` while `health` is `Unhealthy`. Otherwise calls
+    /// `f(self, args..)`, sets `health` to `Healthy` if it returned `Ok` and to
+    /// `Unhealthy(err.to_string())` if it returned `Err`, and returns its result
+    /// as `Ok(result)`.
+    ///
+    /// `Degraded` services are still called. Once unhealthy, `health` stays so
+    /// until the caller resets it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{HealthStatus, PipeHealthCheck, ServiceUnhealthy};
+    /// fn parse(s: &str, radix: u32) -> Result<u32, std::num::ParseIntError> { u32::from_str_radix(s, radix) }
+    ///
+    /// let mut health = HealthStatus::Healthy;
+    /// assert!("zz".pipe_with_health_check(&mut health, parse)(10).unwrap().is_err());
+    /// assert_eq!(health, HealthStatus::Unhealthy("invalid digit found in string".into()));
+    /// assert_eq!("10".pipe_with_health_check(&mut health, parse)(10), Err(ServiceUnhealthy));
+    /// ```
+    #[inline(always)]
+    fn pipe_with_health_check<'h, R, F, Params>(self, health: &'h mut HealthStatus, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Checked<'h>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Checked(health))
+    }
+}
+impl<const ARITY: usize, T> PipeHealthCheck<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: rejects the call while the service is unhealthy and records its outcome.
+pub struct Checked<'h>(&'h mut HealthStatus);
+
+impl<A0, T, E: fmt::Display> Around<A0, Result<T, E>> for Checked<'_> {
+    type Output = Result<Result<T, E>, ServiceUnhealthy>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> Result<T, E>>(self, arg0: A0, call: C) -> Self::Output {
+        if !self.0.is_available() {
+            return Err(ServiceUnhealthy);
+        }
+        let result = call(arg0);
+        *self.0 = match &result {
+            Ok(_) => HealthStatus::Healthy,
+            Err(err) => HealthStatus::Unhealthy(err.to_string()),
+        };
+        Ok(result)
+    }
+}
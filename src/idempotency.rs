@@ -0,0 +1,132 @@
+//! Pipe stages that run at most once per idempotency key.
+
+use core::hash::BuildHasher;
+use std::collections::HashMap;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{CurryWith, Own};
+
+/// Outcome of looking up an idempotency key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum IdempotencyResult {
+    /// The key has not been seen before.
+    Fresh,
+    /// The key was seen before; holds the serialized result stored for it.
+    Duplicate(Vec<u8>),
+}
+
+/// A store of serialized results, keyed by idempotency key.
+///
+/// Implemented for `HashMap<String, Vec<u8>>`; implement it for a database
+/// table or a distributed cache to deduplicate across processes.
+pub trait IdempotencyStore {
+    /// Returns the result stored under `key`, if any.
+    fn check(&self, key: &str) -> IdempotencyResult;
+
+    /// Stores `result` under `key`.
+    fn store(&mut self, key: &str, result: &[u8]);
+
+    /// Stores `result` under `key` unless the key was seen before, in which
+    /// case the earlier result is returned and kept.
+    fn check_and_store(&mut self, key: &str, result: &[u8]) -> IdempotencyResult {
+        let seen = self.check(key);
+        if seen == IdempotencyResult::Fresh {
+            self.store(key, result);
+        }
+        seen
+    }
+}
+
+impl<S: BuildHasher> IdempotencyStore for HashMap<String, Vec<u8>, S> {
+    #[inline]
+    fn check(&self, key: &str) -> IdempotencyResult {
+        match self.get(key) {
+            Some(result) => IdempotencyResult::Duplicate(result.clone()),
+            None => IdempotencyResult::Fresh,
+        }
+    }
+
+    #[inline]
+    fn store(&mut self, key: &str, result: &[u8]) {
+        self.insert(key.into(), result.into());
+    }
+}
+
+/// Extension trait for deduplicating pipe stages by key.
+pub trait PipeIdempotency<const ARITY: usize> {
+    /// Curries `self` into `f`, returning a closure over the remaining arguments
+    /// that computes `key_fn(&self, &args..)` and checks it in `store`. For a
+    /// duplicate key, the stored result is deserialized and returned without
+    /// calling `f`; otherwise `f(self, args..)` runs and its result is stored
+    /// as JSON before being returned.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the result cannot be serialized, or a stored result cannot
+    /// be deserialized as `R`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeIdempotency;
+    /// use std::collections::HashMap;
+    ///
+    /// fn charge(balance: u64, amount: u64) -> u64 { balance - amount }
+    ///
+    /// let mut store: HashMap<String, Vec<u8>> = HashMap::new();
+    /// let key = |_: &u64, amount: &u64| format!("charge-{amount}");
+    /// assert_eq!(100.pipe_with_idempotency(&mut store, key, charge)(30).unwrap(), 70);
+    /// // A retried request with the same key replays the first result.
+    /// assert_eq!(70.pipe_with_idempotency(&mut store, key, charge)(30).unwrap(), 70);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_idempotency<'s, St, KF, R, F, Params>(self, store: &'s mut St, key_fn: KF, f: F) -> F::Curry
+    where
+        St: IdempotencyStore + ?Sized,
+        F: CurryWith<ARITY, Params, Own, IdempotencyMark, Self, (&'s mut St, KF), R>,
+        Self: Sized,
+    {
+        f.curry_with(self, (store, key_fn))
+    }
+}
+impl<const ARITY: usize, T> PipeIdempotency<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_with_idempotency` semantics (replay, or compute and store).
+pub struct IdempotencyMark;
+
+macro_rules! impl_idempotency_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryWith, IdempotencyMark, IdempotencyResult, IdempotencyStore, Own};
+            #[cfg(feature = $feat)]
+            use serde::{de::DeserializeOwned, Serialize};
+
+            // --- Idempotency ---
+            #[cfg(feature = $feat)]
+            impl<'s, F, St: IdempotencyStore + ?Sized, KF, K: AsRef<str>, A0, $($Params,)* R: Serialize + DeserializeOwned> CurryWith<$N, $TupleType, Own, IdempotencyMark, A0, (&'s mut St, KF), R> for F
+            where F: FnOnce(A0, $($Params),*) -> R, KF: FnOnce(&A0, $(&$Params),*) -> K {
+                type Curry = impl FnOnce($($Params),*) -> Result<R, serde_json::Error>;
+                #[inline(always)] fn curry_with(self, arg0: A0, (store, key_fn): (&'s mut St, KF)) -> Self::Curry {
+                    move |$($Params),*| {
+                        let key = key_fn(&arg0, $(&$Params),*);
+                        if let IdempotencyResult::Duplicate(stored) = store.check(key.as_ref()) {
+                            return serde_json::from_slice(&stored);
+                        }
+                        let result = self(arg0, $($Params),*);
+                        store.store(key.as_ref(), &serde_json::to_vec(&result)?);
+                        Ok(result)
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_idempotency_arity);
+}
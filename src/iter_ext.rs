@@ -0,0 +1,145 @@
+//! Taps over iterators: lazy adaptors that observe each element as it passes,
+//! and terminal stages that collect an iterator.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::{CurryWith, Imm};
+
+/// Extension trait for running side effects on the elements of an iterator.
+pub trait TapIter<const ARITY: usize> {
+    /// Returns a lazy adaptor that, for each element, calls
+    /// `report_fn(index, total)` with the 0-based index and then
+    /// `f(&element, args..)`, before yielding the element. The remaining
+    /// arguments are cloned for each call. Pass `total = 0` when the length
+    /// is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapIter;
+    /// fn check_below(x: &u32, limit: u32) { assert!(*x < limit); }
+    ///
+    /// let mut progress = Vec::new();
+    /// let sum: u32 = [3, 1, 4]
+    ///     .into_iter()
+    ///     .tap_with_progress(3, |i, n| progress.push((i, n)), check_below)(10)
+    ///     .sum();
+    ///
+    /// assert_eq!(sum, 8);
+    /// assert_eq!(progress, [(0, 3), (1, 3), (2, 3)]);
+    /// ```
+    #[inline(always)]
+    fn tap_with_progress<G, R, F, Params>(self, total: usize, report_fn: G, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Imm, ProgressMark, Self, (usize, G), R>,
+        Self: Sized,
+    {
+        f.curry_with(self, (total, report_fn))
+    }
+}
+impl<const ARITY: usize, T> TapIter<ARITY> for T {}
+
+/// Extension trait for collecting any `IntoIterator` as a pipeline terminal.
+pub trait PipeFromIter: IntoIterator + Sized {
+    /// Builds a `C` from the elements of `self`; `iter.collect::<C>()` in pipe form.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeFromIter;
+    /// use std::collections::HashSet;
+    ///
+    /// let unique: HashSet<char> = "hello".chars().pipe_from_iter();
+    /// assert_eq!(unique.len(), 4);
+    /// assert_eq!([1, 2].pipe_from_iter::<Vec<_>>(), [1, 2]);
+    /// ```
+    #[inline(always)]
+    fn pipe_from_iter<C: FromIterator<Self::Item>>(self) -> C {
+        self.into_iter().collect()
+    }
+
+    /// Like [`pipe_from_iter`](PipeFromIter::pipe_from_iter), but sorts the
+    /// elements before building `C`. The sort is stable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeFromIter;
+    /// use std::collections::VecDeque;
+    ///
+    /// assert_eq!([3, 1, 2].pipe_from_iter_sorted::<Vec<_>>(), [1, 2, 3]);
+    /// assert_eq!("bca".chars().pipe_from_iter_sorted::<VecDeque<_>>(), ['a', 'b', 'c']);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn pipe_from_iter_sorted<C: FromIterator<Self::Item>>(self) -> C
+    where
+        Self::Item: Ord,
+    {
+        let mut items: Vec<Self::Item> = self.into_iter().collect();
+        items.sort();
+        items.into_iter().collect()
+    }
+}
+impl<I: IntoIterator> PipeFromIter for I {}
+
+#[doc(hidden)]
+/// Marker type: `tap_with_progress` semantics (report and tap each element).
+pub struct ProgressMark;
+
+any_arity! {
+    allow_dead_code;
+
+    /// Iterator returned by [`TapIter::tap_with_progress`].
+    pub struct ProgressTap<I, F, G, Args> {
+        iter: I,
+        index: usize,
+        total: usize,
+        report: G,
+        f: F,
+        args: Args,
+    }
+}
+
+macro_rules! impl_iter_ext_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryWith, Imm, ProgressMark, ProgressTap};
+
+            // --- Progress ---
+            #[cfg(feature = $feat)]
+            impl<I: Iterator, F, G: FnMut(usize, usize), $($Params: Clone,)* R> CurryWith<$N, $TupleType, Imm, ProgressMark, I, (usize, G), R> for F
+            where F: FnMut(&I::Item, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> ProgressTap<I, F, G, $TupleType>;
+                #[inline(always)] fn curry_with(self, arg0: I, (total, report): (usize, G)) -> Self::Curry {
+                    move |$($Params),*| ProgressTap { iter: arg0, index: 0, total, report, f: self, args: ($($Params,)*) }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<I: Iterator, F, G: FnMut(usize, usize), $($Params: Clone,)* R> Iterator for ProgressTap<I, F, G, $TupleType>
+            where F: FnMut(&I::Item, $($Params),*) -> R {
+                type Item = I::Item;
+                #[inline(always)] fn next(&mut self) -> Option<I::Item> {
+                    let item = self.iter.next()?;
+                    (self.report)(self.index, self.total);
+                    self.index += 1;
+                    let ($($Params,)*) = &self.args;
+                    (self.f)(&item, $($Params.clone()),*);
+                    Some(item)
+                }
+                #[inline(always)] fn size_hint(&self) -> (usize, Option<usize>) {
+                    self.iter.size_hint()
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_iter_ext_arity);
+}
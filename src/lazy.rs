@@ -0,0 +1,170 @@
+//! A lazily computed pipeline value.
+
+use alloc::boxed::Box;
+use core::cell::{Cell, OnceCell};
+use core::fmt;
+use core::ops::Deref;
+
+use crate::{Curry, Imm, Own};
+
+/// A value computed on first use.
+///
+/// The computation runs at most once, the first time the value is forced by
+/// [`force`](Lazy::force), [`pipe_force`](Lazy::pipe_force), or a dereference.
+/// If it panics, the `Lazy` is poisoned and every later force panics too.
+pub struct Lazy<T> {
+    value: OnceCell<T>,
+    init: Cell<Option<Box<dyn FnOnce() -> T>>>,
+}
+
+impl<T> Lazy<T> {
+    /// Creates a value that will be computed by `init` on first use.
+    #[inline]
+    pub fn new(init: impl FnOnce() -> T + 'static) -> Self {
+        Lazy { value: OnceCell::new(), init: Cell::new(Some(Box::new(init))) }
+    }
+
+    /// Creates a value that has already been computed.
+    #[inline]
+    pub fn forced(value: T) -> Self {
+        Lazy { value: OnceCell::from(value), init: Cell::new(None) }
+    }
+
+    /// Computes the value if that has not happened yet, and returns it.
+    pub fn force(&self) -> &T {
+        self.value.get_or_init(|| match self.init.take() {
+            Some(init) => init(),
+            None => panic!("Lazy instance has previously been poisoned"),
+        })
+    }
+
+    /// Returns the value if it has been computed, without computing it.
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        self.value.get()
+    }
+
+    /// Returns `true` if the value has been computed.
+    #[inline]
+    pub fn is_forced(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    /// Computes the value if needed, and returns it, consuming the `Lazy`.
+    pub fn into_inner(self) -> T {
+        self.force();
+        self.value.into_inner().unwrap()
+    }
+
+    /// Curries a reference to the value, computed if needed, into `f`,
+    /// returning a closure over the remaining arguments.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::Lazy;
+    /// fn add(x: &u64, y: u64) -> u64 { x + y }
+    ///
+    /// let config = Lazy::new(|| 40);
+    /// assert!(!config.is_forced());
+    /// assert_eq!(config.pipe_force(add)(2), 42);
+    /// assert_eq!(config.pipe_force(add)(3), 43);
+    /// ```
+    #[inline(always)]
+    pub fn pipe_force<'l, const ARITY: usize, R, F, Params>(&'l self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, ForceMark, &'l Self, R>,
+    {
+        f.curry(self)
+    }
+
+    /// Returns a closure over the remaining arguments that calls
+    /// `f(&value, args..)` if the value has already been computed, without
+    /// computing it otherwise, and returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::Lazy;
+    /// let mut seen = Vec::new();
+    /// let lazy = Lazy::new(|| 7);
+    ///
+    /// lazy.tap_inspect_lazy(|x: &i32, seen: &mut Vec<i32>| seen.push(*x))(&mut seen);
+    /// lazy.force();
+    /// lazy.tap_inspect_lazy(|x: &i32, seen: &mut Vec<i32>| seen.push(*x))(&mut seen);
+    /// assert_eq!(seen, [7]);
+    /// ```
+    #[inline(always)]
+    pub fn tap_inspect_lazy<'l, const ARITY: usize, R, F, Params>(&'l self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, InspectLazyMark, &'l Self, R>,
+    {
+        f.curry(self)
+    }
+}
+
+impl<T> Deref for Lazy<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.force()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Lazy<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get() {
+            Some(value) => f.debug_tuple("Lazy").field(value).finish(),
+            None => f.write_str("Lazy(<unforced>)"),
+        }
+    }
+}
+
+#[doc(hidden)]
+/// Marker type: `pipe_force` semantics (compute the value if needed, then pipe it).
+pub struct ForceMark;
+
+#[doc(hidden)]
+/// Marker type: `tap_inspect_lazy` semantics (tap the value only if already computed).
+pub struct InspectLazyMark;
+
+macro_rules! impl_lazy_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, ForceMark, Imm, InspectLazyMark, Lazy, Own};
+
+            // --- Force ---
+            #[cfg(feature = $feat)]
+            impl<'l, F, T, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, ForceMark, &'l Lazy<T>, R> for F
+            where F: FnOnce(&T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry(self, arg0: &'l Lazy<T>) -> Self::Curry {
+                    move |$($Params),*| self(arg0.force(), $($Params),*)
+                }
+            }
+
+            // --- Inspect ---
+            #[cfg(feature = $feat)]
+            impl<'l, F, T, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, InspectLazyMark, &'l Lazy<T>, R> for F
+            where F: FnOnce(&T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> &'l Lazy<T>;
+                #[inline(always)] fn curry(self, arg0: &'l Lazy<T>) -> Self::Curry {
+                    move |$($Params),*| {
+                        if let Some(value) = arg0.get() {
+                            self(value, $($Params),*);
+                        }
+                        arg0
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_lazy_arity);
+}
@@ -1,5 +1,7 @@
 #![no_std]
 #![feature(impl_trait_in_assoc_type)]
+#![feature(unsize)]
+#![cfg_attr(feature = "async", feature(impl_trait_in_fn_trait_return))]
 
 //! # pipei
 //!
@@ -14,6 +16,96 @@
 //! * **[`Tap::tap`]:** Passes `self` to a function for inspection or mutation, then returns the original (now possibly modified) value.
 //! * **[`TapWith::tap_proj`]:** Like `tap`, but first applies a projection to extract a sub-reference.
 //! * **[`TapWith::tap_cond`]:** Like `tap_proj`, but the projection returns `Option`; the side effect only runs on `Some`.
+//! * **`TapFields`** (`macros`): Derives a `tap_proj` shorthand for each field of a struct, e.g. `point.tap_field_x(check)()`.
+//! * **`pipe_derive`** (`macros`): Turns a function into a named pipe stage that implements `Curry` directly.
+//! * **[`PipeOwnership`]:** `pipe_move`, `pipe_borrow`, and `pipe_borrow_mut` are `pipe` with the way `f` takes the value spelled out.
+//! * **[`PipeRef::pipe_ref`]:** Calls a function on `&self` and returns its result, leaving `self` usable.
+//! * **[`PipeConvert::into_pipe`]:** Like `pipe`, but first converts `self` with `Into`.
+//! * **[`PipeConvert::pipe_into`]:** Like `pipe`, but converts the result with `Into`.
+//! * **[`PipeWith::pipe_zip_with`]:** Like `pipe`, but curries a second value alongside `self`.
+//! * **[`PipeWith::pipe_branch`]:** Pipes `self` into one of two functions, chosen by a predicate on `&self`; `pipe_branch_either` (`either`) lets the two return different types.
+//! * **[`PipeWith::pipe_if`]:** Pipes `self` into a function only if a predicate on `&self` holds, returning it unchanged otherwise.
+//! * **[`PipeWith::pipe_with_feature_toggle`]:** Like `pipe_branch`, chosen by a runtime flag; [`pipe_with_cfg_feature!`] chooses at compile time.
+//! * **[`PipeConverge::pipe_eventually`]:** Pipes clones of `self` into two functions and merges their results.
+//! * **[`PipeBind::pipe_bind`]:** Feeds the success value of an `Option` or `Result` ([`PipeMonad`]) into a function.
+//! * **[`PipeBimap::pipe_bimap`]:** Maps the `Ok` and `Err` variants of a `Result`, or the sides of an `Either` (`either`), with two functions sharing the same arguments.
+//! * **[`PipeRecover::pipe_recover`]:** Feeds the error of a `Result` into a recovery function that may itself fail.
+//! * **[`PipeValidate::pipe_with_schema`]:** Checks `self` against a [`Validate`] schema before piping it; `#[derive(Validate)]` (`macros`) builds a [`FieldSchema`] from field bounds.
+//! * **[`PipeAuthorize::pipe_with_authorization`]:** Pipes `self` only if a [`Capability`] permits the operation.
+//! * **[`PipeBudget::pipe_with_budget`]:** Pipes `self` only if a [`Budget`] can pay the stage's cost, and spends it.
+//! * **[`PipeVersionGate::pipe_with_version_gate`]:** Pipes `self` only if the current version is at least a minimum version.
+//! * **[`PipeAbiVersion::pipe_with_abi_version`]:** Pipes `self` into the newest implementation compatible with an [`AbiVersion`].
+//! * **[`PipeTry::try_pipe`]:** Like `pipe`, for a function returning `Result`, so the result can be propagated with `?`.
+//! * **[`PipeWhileOk::pipe_while_ok`]:** Feeds each `Ok` result of a function back into it until it fails.
+//! * **[`PipeTypeName::pipe_inspect_type_name`]:** Like `pipe`, but also passes the name of `self`'s type as the first argument.
+//! * **[`PipeAssertImplements::pipe_assert_type_implements`]:** Fails to compile unless `self`'s type implements a given trait.
+//! * **[`PipeConditional`]:** Pipes `self` into one of a slice of transforms, picked by index from `&self`.
+//! * **[`PipeSeq`]:** Threads `self` through an array or run-time list of functions, in order, optionally stopping at the first `Err`.
+//! * **[`StateMachine`]:** Carries a value through event-driven state transitions written as multi-argument functions.
+//! * **[`TypeStatePipe`]:** Tags a value with a state type, so that e.g. processing can only follow validation.
+//! * **[`PipeConfig`]:** Carries a configuration alongside a value and passes it to every stage.
+//! * **[`Dirty`]:** Tracks whether a value changed since it was last saved, and taps or pipes it only if so.
+//! * **[`Versioned`]:** Wraps a value and counts the pipe and tap stages applied to it.
+//! * **`Observable`** (`alloc`): Pipes a value and notifies registered observers of the result; `SharedObservable` (`std`) is the thread-safe variant.
+//! * **`PipeBoxed`** (`alloc`): Like `pipe` and `tap`, but returns a `Box<dyn Fn>` that can be stored alongside other closures.
+//! * **`TapRefCount`** (`alloc`): Logs or asserts the strong count of an `Rc` or `Arc` mid-pipeline; `TapArcRefCount` logs it for `Arc` only.
+//! * **`PipelineGraph`** (`alloc`): Records the names of the stages a value passes through in debug builds.
+//! * **`Lazy`** (`alloc`): Defers computing a value until a stage first pipes or dereferences it.
+//! * **`PipeUndo`** (`alloc`): Saves the input of each stage in an `UndoStack`, so it can be restored later.
+//! * **`PipeHealthCheck`** (`alloc`): Runs a fallible pipe stage only while its service is not unhealthy, and updates a `HealthStatus` from the result.
+//! * **`TapSchema`** (`alloc`): Records the name of each stage and the type of the value there in an `ExportedSchema`, rendered as Markdown.
+//! * **`PipeLogged`** (`alloc`): Records each stage's name, input, and transform in a `TransformLog` that can be replayed.
+//! * **`PipeSaga`** (`alloc`): Runs a `Saga` of fallible steps on a stage's result, compensating in reverse when a step fails.
+//! * **`PipeMigrate`** (`alloc`): Migrates a value between versions with the steps registered in a `Migrator`.
+//! * **`TapExport`** (`alloc`): Hands a value to a `MetricsExporter` backend mid-pipeline.
+//! * **`PipeSerialize`** (`serde`): Serializes a value to JSON bytes or a string; `tap_serialize` (with `std`) writes it to an `io::Write` and passes it on.
+//! * **`PipeDeserialize`** (`serde`): Starts a pipeline from a value deserialized from JSON bytes or text, or from any deserializer with `DeserializerPipe`.
+//! * **`TapJsonPatch`** (`json-patch`): Appends the JSON Patch between an earlier version of a value and the current one to a log.
+//! * **`PipeIdempotency`** (`serde`, `std`): Runs a pipe stage once per idempotency key, replaying the result kept in an `IdempotencyStore` for repeated keys.
+//! * **`TapMetrics`** (`metrics`): Increments a counter or records a histogram observation through the `metrics` facade mid-pipeline.
+//! * **`PipeOtelSpan`** (`opentelemetry`): Runs a stage inside an OpenTelemetry span, recording whether it panicked.
+//! * **`PipeGzip`** (`flate2`): Compresses bytes with gzip before piping them, or compresses and decompresses them directly.
+//! * **`TapConditionalLog`** (`log`): Logs a message formatted from a value only if its level is enabled and a predicate on the value holds.
+//! * **`TapStructuredLog`** (`log`, `serde`, `std`): Logs a value with a map of fields as a JSON object through the `log` facade.
+//! * **[`TapIter`]:** Taps each element of an iterator lazily, reporting progress as it goes.
+//! * **[`PipeFromIter`]:** Collects any `IntoIterator` into a collection, optionally sorted (with `alloc`).
+//! * **[`PipeAtomic`]:** Pipes the value of an atomic through a function with a compare-and-swap loop.
+//! * **[`PipeOnDrop`]:** Wraps a value in a [`DropGuard`] that runs a cleanup function when dropped.
+//! * **[`PipeResourceGuard`]:** Holds a resource while a stage runs, cleaning it up if the stage panics.
+//! * **`PipeAsync`** (`async`): Like `pipe`, for an async function, returning its future; `TapAsync` awaits an async side effect and resolves to the original value.
+//! * **`PipeAsyncStream`** (`futures`): Maps an async function over the items of a stream, sharing the remaining arguments.
+//! * **`PipePanic`** (`std`): Runs a pipe stage behind a panic boundary, or aborts the process if it panics.
+//! * **`PipeDispatch`** (`std`): Pipes a value into a function looked up by key in a dispatch table.
+//! * **`PipeCircuitBreaker`** (`std`): Runs a fallible pipe stage behind a `CircuitBreaker`.
+//! * **`PipeRetry`** (`std`): Retries a fallible pipe stage according to a `RetryStrategy`.
+//! * **`PipeRateLimit`** (`std`): Runs a pipe stage only if a token-bucket `RateLimiter` allows it.
+//! * **`PipeDeadline`** (`std`): Fails a pipe stage that starts or finishes after an absolute deadline.
+//! * **`PipeCache`** (`std`): Memoizes a pipe stage in a caller-owned `HashMap`.
+//! * **`PipeAudit`** (`std`): Records each call of a fallible pipe stage in an `AuditLog`.
+//! * **`PipeProfile`** (`std`): Times a pipe stage and reports it to a `Profiler` backend.
+//! * **`TapMetricSpan`** (`std`): Times a tap and records it, with the spans nested inside it, as a `MetricSpan` tree.
+//! * **`trace::PipeTrace`** (`std`): Runs a stage inside a child span of a W3C trace context, tracked per thread.
+//! * **`request_ctx::PipeRequestContext`** (`std`): Runs a stage with a thread-local request context set.
+//! * **`correlation::PipeCorrelation`** (`std`): Runs a stage with a thread-local correlation id set.
+//! * **`execution_ctx::PipeExecutionContext`** (`std`): Runs a stage with a thread-local context of ids, timing, and tags set.
+//! * **`tenant::PipeTenant`** (`std`): Runs a stage with a thread-local tenant context, with permissions and quotas, set.
+//! * **`rbac::PipeCapability`** (`std`): Pipes a value only if a user's role-derived `Capabilities` hold the required ones.
+//! * **`db::PipeTransaction`** (`db`): Runs a stage inside a transaction, committing on `Ok` and rolling back on `Err`.
+//! * **`PipeFromEnv`** (`std`): Parses an environment variable and pipes it into a function.
+//! * **`PipeBackpressure`** (`std`): Publishes a value to a bounded channel, blocking while it is full, before piping it; with `tokio`, awaits room in a tokio channel instead.
+//! * **`PipePool`** (`std`): Runs a pipe stage on a fixed-size `ThreadPool`, returning a handle to its result.
+//! * **`PipeSampling`** (`rand`): Runs a pipe stage with a given probability, e.g. for A/B tests.
+//! * **`PipeTraverse`** (`rayon`): Maps a multi-argument function over the elements of a collection in parallel.
+//! * **[`type_aliases`]:** Names the closure types returned by `pipe` and `tap`, e.g. for struct fields.
+//! * **[`Also::also`]:** Runs a closure on `&self` or `&mut self` and returns `self`, without the trailing call of `tap(f)()`.
+//! * **[`Run::run`]:** Ends a pipeline by passing `self` by value to a closure, discarding the result; `run_returning` keeps it.
+//! * **[`TapWith::tap_if`]:** Like `tap`, but the side effect only runs if a predicate on `&self` holds.
+//! * **[`TapOption`]:** Taps the value inside `Some`, or runs a side effect on `None`.
+//! * **[`PipeOption`]:** Maps the value inside `Some`, or fills in `None`, with a multi-argument function.
+//! * **[`TapResult`]:** Taps the value inside `Ok`, or the error inside `Err`.
+//! * **[`PipeResult`]:** Maps the value inside `Ok`, or the error inside `Err`, with a multi-argument function.
+//! * **[`TapValue`]:** Taps that take no deferred arguments, such as writing `self` to a [`core::fmt::Write`] sink, comparing it with the previous value, or capturing a [`Snapshot`].
+//! * **[`PipeCollection`]:** Flat-maps a value, maps over slice windows, or maps into an existing collection, and (with `alloc`) sorts, partitions, or chunks a collection with a multi-argument callback.
 //!
 //! ```rust
 //! # use pipei::{Pipe, Tap};
@@ -28,6 +120,11 @@
 //! assert_eq!(result, Some(3));
 //! ```
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 /// Extension trait for transforming values.
 pub trait Pipe<const ARITY: usize, AState, RState> {
     /// Curries `self` as the first argument of `f`, returning a closure over
@@ -72,6 +169,232 @@ pub trait Pipe<const ARITY: usize, AState, RState> {
 }
 impl<const ARITY: usize, AState, RState, T> Pipe<ARITY, AState, RState> for T {}
 
+/// Extension trait for pipes whose name states how `f` receives the value.
+///
+/// Each method is `pipe` with the passing mode fixed rather than inferred
+/// from `f`'s signature, so the call site documents it; an `f` taking the
+/// value another way is rejected.
+pub trait PipeOwnership<const ARITY: usize> {
+    /// Like `pipe`, for an `f` that takes the value by value, consuming it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeOwnership;
+    /// fn append(mut v: Vec<i32>, x: i32) -> Vec<i32> { v.push(x); v }
+    ///
+    /// assert_eq!(vec![1].pipe_move(append)(2), [1, 2]);
+    /// ```
+    #[inline(always)]
+    fn pipe_move<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PipeMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Like `pipe`, for an `f` that takes the value by shared reference. The
+    /// returned closure owns the value and can be called repeatedly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeOwnership;
+    /// fn contains(v: &Vec<i32>, x: i32) -> bool { v.contains(&x) }
+    ///
+    /// let has = vec![1, 2].pipe_borrow(contains);
+    /// assert!(has(1) && !has(3));
+    /// ```
+    #[inline(always)]
+    fn pipe_borrow<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, PipeMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Like `pipe`, for an `f` that takes the value by exclusive reference.
+    /// The returned closure owns the value, and each call sees earlier
+    /// calls' changes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeOwnership;
+    /// fn push_len(v: &mut Vec<usize>) -> usize { v.push(v.len()); v.len() }
+    ///
+    /// let mut grow = Vec::new().pipe_borrow_mut(push_len);
+    /// assert_eq!((grow(), grow()), (1, 2));
+    /// ```
+    #[inline(always)]
+    fn pipe_borrow_mut<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Mut, Own, PipeMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeOwnership<ARITY> for T {}
+
+/// Extension trait for piping a borrowed value.
+pub trait PipeRef {
+    /// Calls `f(&self)` and returns its result directly, without a closure to
+    /// call. Unlike `pipe`, `self` is borrowed rather than moved, so it can be
+    /// piped again afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeRef;
+    /// fn double(v: &[i32]) -> Vec<i32> { v.iter().map(|x| x * 2).collect() }
+    /// fn sum(v: &[i32]) -> i32 { v.iter().sum() }
+    ///
+    /// let val = [1, 2];
+    /// let doubled = val[..].pipe_ref(double);
+    /// let total = val[..].pipe_ref(sum);
+    /// assert_eq!((doubled, total), (vec![2, 4], 3));
+    /// ```
+    #[inline(always)]
+    fn pipe_ref<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&Self) -> R,
+    {
+        f(self)
+    }
+}
+impl<T: ?Sized> PipeRef for T {}
+
+/// Extension trait for transforming values together with extra values captured up front.
+pub trait PipeWith<const ARITY: usize> {
+    /// Curries `self` and `other` as the first two arguments of `f`, returning
+    /// a closure over the remaining arguments. Where `pipe` fixes only the
+    /// receiver, `pipe_zip_with` fixes two leading values at once.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeWith;
+    /// fn lerp(a: f64, b: f64, t: f64) -> f64 { a + (b - a) * t }
+    ///
+    /// let between = 10.0.pipe_zip_with(20.0, lerp);
+    /// assert_eq!(between(0.25), 12.5);
+    /// ```
+    #[inline(always)]
+    fn pipe_zip_with<B, R, F, Params>(self, other: B, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, ZipMark, Self, B, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, other)
+    }
+
+    /// Returns a closure over the remaining arguments that calls `on_true(self, args..)`
+    /// if `pred(&self)` holds and `on_false(self, args..)` otherwise. `pred` only
+    /// borrows the value; exactly one arm consumes it. Both arms return `R`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeWith;
+    /// fn grant(user: &str, resource: &str) -> String { format!("{user} may read {resource}") }
+    /// fn deny(user: &str, resource: &str) -> String { format!("{user} may not read {resource}") }
+    ///
+    /// let route = |user: &'static str| user.pipe_branch(|u| u.starts_with("admin"), grant, deny)("logs");
+    /// assert_eq!(route("admin-eve"), "admin-eve may read logs");
+    /// assert_eq!(route("bob"), "bob may not read logs");
+    /// ```
+    #[inline(always)]
+    fn pipe_branch<Pr, G, R, F, Params>(self, pred: Pr, on_true: F, on_false: G) -> F::Curry
+    where
+        Pr: FnOnce(&Self) -> bool,
+        F: CurryWith<ARITY, Params, Own, BranchMark, Self, (Pr, G), R>,
+        Self: Sized,
+    {
+        on_true.curry_with(self, (pred, on_false))
+    }
+
+    /// Like [`pipe_branch`](PipeWith::pipe_branch), but the arms may return
+    /// different types: the result is `Left(on_true(self, args..))` or
+    /// `Right(on_false(self, args..))`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeWith;
+    /// use either::Either;
+    ///
+    /// fn parse(s: &str, radix: u32) -> u32 { u32::from_str_radix(s, radix).unwrap() }
+    /// fn reject(s: &str, _radix: u32) -> String { format!("not a number: {s}") }
+    ///
+    /// let is_hex = |s: &&str| s.chars().all(|c| c.is_ascii_hexdigit());
+    /// assert_eq!("ff".pipe_branch_either(is_hex, parse, reject)(16), Either::Left(255));
+    /// assert_eq!("zz".pipe_branch_either(is_hex, parse, reject)(16), Either::Right("not a number: zz".into()));
+    /// ```
+    #[cfg(feature = "either")]
+    #[inline(always)]
+    fn pipe_branch_either<Pr, G, L, R, F, Params>(self, pred: Pr, on_true: F, on_false: G) -> F::Curry
+    where
+        Pr: FnOnce(&Self) -> bool,
+        F: CurryWith<ARITY, Params, Own, BranchEitherMark, Self, (Pr, G), either::Either<L, R>>,
+        Self: Sized,
+    {
+        on_true.curry_with(self, (pred, on_false))
+    }
+
+    /// Returns a closure over the remaining arguments that calls `f(self, args..)`
+    /// if `pred(&self)` holds and returns `self` unchanged otherwise, so `f`
+    /// must return the type it takes. The arguments are dropped if `f` does
+    /// not run.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeWith;
+    /// fn truncate(s: String, len: usize) -> String { s.chars().take(len).collect() }
+    ///
+    /// let long = "pipeline".to_string().pipe_if(|s| s.len() > 4, truncate)(4);
+    /// let short = "pipe".to_string().pipe_if(|s| s.len() > 4, truncate)(2);
+    /// assert_eq!((long.as_str(), short.as_str()), ("pipe", "pipe"));
+    /// ```
+    #[inline(always)]
+    fn pipe_if<Pr, F, Params>(self, pred: Pr, f: F) -> F::Curry
+    where
+        Pr: FnOnce(&Self) -> bool,
+        F: CurryWith<ARITY, Params, Own, PipeIfMark, Self, Pr, Self>,
+        Self: Sized,
+    {
+        f.curry_with(self, pred)
+    }
+
+    /// Like [`pipe_branch`](PipeWith::pipe_branch), but selects the arm with a
+    /// runtime flag: `enabled_fn(self, args..)` if `flag` is set and
+    /// `disabled_fn(self, args..)` otherwise. For a flag known at compile time,
+    /// see [`pipe_with_cfg_feature!`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeWith;
+    /// fn fast_path(x: u64, k: u64) -> u64 { x << k }
+    /// fn slow_path(x: u64, k: u64) -> u64 { x * 2u64.pow(k as u32) }
+    ///
+    /// let use_fast = true;
+    /// assert_eq!(3.pipe_with_feature_toggle(use_fast, fast_path, slow_path)(4), 48);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_feature_toggle<G, R, F, Params>(self, flag: bool, enabled_fn: F, disabled_fn: G) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, BranchMark, Self, (bool, G), R>,
+        Self: Sized,
+    {
+        enabled_fn.curry_with(self, (flag, disabled_fn))
+    }
+}
+impl<const ARITY: usize, T> PipeWith<ARITY> for T {}
+
 /// Extension trait for running side effects, returning the original value.
 pub trait Tap<const ARITY: usize, State> {
     /// Passes `self` into `f` for inspection or mutation, then returns the
@@ -181,6 +504,63 @@ pub trait TapWith<const ARITY: usize, State> {
     {
         f.curry_with(self, proj)
     }
+
+    /// Runs a side effect on `self` only if `pred(&self)` holds, then returns
+    /// `self` in both cases. Unlike [`tap_cond`](TapWith::tap_cond), the
+    /// predicate only tests the value; `f` always receives the whole value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapWith;
+    /// fn warn(x: &u32, limit: u32, out: &mut Vec<String>) { out.push(format!("{x} exceeds {limit}")) }
+    ///
+    /// let mut out = Vec::new();
+    /// let a = 120.tap_if(|x| *x > 100, warn)(100, &mut out);
+    /// let b = 80.tap_if(|x| *x > 100, warn)(100, &mut out);
+    /// assert_eq!((a, b), (120, 80));
+    /// assert_eq!(out, ["120 exceeds 100"]);
+    ///
+    /// let v = vec![1, 2].tap_if(|v| v.len() < 3, |v: &mut Vec<i32>| v.push(3))();
+    /// assert_eq!(v, [1, 2, 3]);
+    /// ```
+    #[inline(always)]
+    fn tap_if<Pr, R, F, Params>(self, pred: Pr, f: F) -> F::Curry
+    where
+        Pr: FnOnce(&Self) -> bool,
+        F: CurryWith<ARITY, Params, State, TapIfMark, Self, Pr, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, pred)
+    }
+
+    /// Runs `f` with a shared reference to `self`, the exclusive `writer`,
+    /// and the remaining arguments, then returns `self`. The result of `f`
+    /// (typically a [`core::fmt::Result`]) is discarded, so a failing writer
+    /// never interrupts the pipeline.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapWith;
+    /// use core::fmt::Write;
+    ///
+    /// fn fixed(x: &f64, out: &mut String, digits: usize) -> core::fmt::Result {
+    ///     write!(out, "{x:.digits$}")
+    /// }
+    ///
+    /// let mut out = String::new();
+    /// let x = 3.14159.tap_side_channel_fmt(&mut out, fixed)(2);
+    /// assert_eq!((x, out.as_str()), (3.14159, "3.14"));
+    /// ```
+    #[inline(always)]
+    fn tap_side_channel_fmt<'w, W: ?Sized, R, F, Params>(self, writer: &'w mut W, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, State, WriteMark, Self, &'w mut W, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, writer)
+    }
 }
 impl<const ARITY: usize, State, T> TapWith<ARITY, State> for T {}
 
@@ -188,7 +568,7 @@ macro_rules! impl_arity {
     ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
         const _: () = {
             #[cfg(feature = $feat)]
-            use crate::{Imm, Curry, CurryWith, Mut, Own, PipeMark, TapMark, Proj, Cond};
+            use crate::{Around, AroundMark, BranchMark, Imm, Curry, CurryWith, Mut, Own, PipeIfMark, PipeMark, TapMark, TapIfMark, Proj, Cond, WriteMark, ZipMark};
 
             // --- Pipe ---
             #[cfg(feature = $feat)]
@@ -218,6 +598,68 @@ macro_rules! impl_arity {
                 }
             }
 
+            // --- Pipe Zip (CurryWith + ZipMark) ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, B, $($Params,)* R> CurryWith<$N, $TupleType, Own, ZipMark, A0, B, R> for F
+            where F: FnOnce(A0, B, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry_with(self, arg0: A0, other: B) -> Self::Curry {
+                    |$($Params),*| self(arg0, other, $($Params),*)
+                }
+            }
+
+            // --- Pipe Branch (CurryWith + BranchMark) ---
+            #[cfg(feature = $feat)]
+            impl<F, G, Pr, A0, $($Params,)* R> CurryWith<$N, $TupleType, Own, BranchMark, A0, (Pr, G), R> for F
+            where F: FnOnce(A0, $($Params),*) -> R, G: FnOnce(A0, $($Params),*) -> R, Pr: FnOnce(&A0) -> bool {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry_with(self, arg0: A0, (pred, on_false): (Pr, G)) -> Self::Curry {
+                    |$($Params),*| if pred(&arg0) { self(arg0, $($Params),*) } else { on_false(arg0, $($Params),*) }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, G, A0, $($Params,)* R> CurryWith<$N, $TupleType, Own, BranchMark, A0, (bool, G), R> for F
+            where F: FnOnce(A0, $($Params),*) -> R, G: FnOnce(A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry_with(self, arg0: A0, (flag, on_false): (bool, G)) -> Self::Curry {
+                    move |$($Params),*| if flag { self(arg0, $($Params),*) } else { on_false(arg0, $($Params),*) }
+                }
+            }
+
+            #[cfg(all(feature = $feat, feature = "either"))]
+            impl<F, G, Pr, A0, $($Params,)* L, R> CurryWith<$N, $TupleType, Own, crate::BranchEitherMark, A0, (Pr, G), either::Either<L, R>> for F
+            where F: FnOnce(A0, $($Params),*) -> L, G: FnOnce(A0, $($Params),*) -> R, Pr: FnOnce(&A0) -> bool {
+                type Curry = impl FnOnce($($Params),*) -> either::Either<L, R>;
+                #[inline(always)] fn curry_with(self, arg0: A0, (pred, on_false): (Pr, G)) -> Self::Curry {
+                    |$($Params),*| if pred(&arg0) {
+                        either::Either::Left(self(arg0, $($Params),*))
+                    } else {
+                        either::Either::Right(on_false(arg0, $($Params),*))
+                    }
+                }
+            }
+
+            // --- Pipe If (CurryWith + PipeIfMark) ---
+            #[cfg(feature = $feat)]
+            impl<F, Pr, A0, $($Params),*> CurryWith<$N, $TupleType, Own, PipeIfMark, A0, Pr, A0> for F
+            where F: FnOnce(A0, $($Params),*) -> A0, Pr: FnOnce(&A0) -> bool {
+                type Curry = impl FnOnce($($Params),*) -> A0;
+                #[inline(always)] fn curry_with(self, arg0: A0, pred: Pr) -> Self::Curry {
+                    |$($Params),*| if pred(&arg0) { self(arg0, $($Params),*) } else { arg0 }
+                }
+            }
+
+            // --- Pipe Around (CurryWith + AroundMark) ---
+            #[cfg(feature = $feat)]
+            impl<F, W, A0, $($Params,)* R> CurryWith<$N, $TupleType, Own, AroundMark, A0, W, R> for F
+            where F: FnOnce(A0, $($Params),*) -> R, W: Around<A0, R> {
+                type Curry = impl FnOnce($($Params),*) -> W::Output;
+                #[inline(always)] fn curry_with(self, arg0: A0, wrap: W) -> Self::Curry {
+                    |$($Params),*| wrap.around(arg0, |a0| self(a0, $($Params),*))
+                }
+            }
+
             // --- Tap ---
             #[cfg(feature = $feat)]
             impl<F, A0, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, TapMark, A0, R> for F
@@ -298,42 +740,415 @@ macro_rules! impl_arity {
                     }
                 }
             }
+
+            // --- Tap If (CurryWith + TapIfMark) ---
+            #[cfg(feature = $feat)]
+            impl<F, Pr, A0, $($Params,)* R> CurryWith<$N, $TupleType, Imm, TapIfMark, A0, Pr, R> for F
+            where F: FnOnce(&A0, $($Params),*) -> R, Pr: FnOnce(&A0) -> bool {
+                type Curry = impl FnOnce($($Params),*) -> A0;
+                #[inline(always)] fn curry_with(self, arg0: A0, pred: Pr) -> Self::Curry {
+                    |$($Params),*| {
+                        if pred(&arg0) { self(&arg0, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, Pr, A0, $($Params,)* R> CurryWith<$N, $TupleType, Mut, TapIfMark, A0, Pr, R> for F
+            where F: FnOnce(&mut A0, $($Params),*) -> R, Pr: FnOnce(&A0) -> bool {
+                type Curry = impl FnOnce($($Params),*) -> A0;
+                #[inline(always)] fn curry_with(self, mut arg0: A0, pred: Pr) -> Self::Curry {
+                    |$($Params),*| {
+                        if pred(&arg0) { self(&mut arg0, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            // --- Tap Write (CurryWith + WriteMark) ---
+            #[cfg(feature = $feat)]
+            impl<'w, F, A0, W: ?Sized, $($Params,)* R> CurryWith<$N, $TupleType, Imm, WriteMark, A0, &'w mut W, R> for F
+            where F: FnOnce(&A0, &mut W, $($Params),*) -> R
+            {
+                type Curry = impl FnOnce($($Params),*) -> A0;
+                #[inline(always)] fn curry_with(self, arg0: A0, writer: &'w mut W) -> Self::Curry {
+                    |$($Params),*| {
+                        self(&arg0, writer, $($Params),*);
+                        arg0
+                    }
+                }
+            }
         };
     };
 }
 
 macro_rules! generate_pipeline {
-    ( (0, $feat0:literal), $($rest:tt)* ) => {
-        impl_arity!(0, $feat0, [], ());
-        generate_pipeline!(@recurse [] ; $($rest)* );
+    ( $m:ident; (0, $feat0:literal), $($rest:tt)* ) => {
+        $m!(0, $feat0, [], ());
+        generate_pipeline!(@recurse $m [] ; $($rest)* );
+    };
+
+    (@recurse $m:ident $acc:tt ; ) => {};
+
+    (@recurse $m:ident [ $($Acc:ident),* ] ; ($N:literal, $feat:literal, $Next:ident) $(, ($Ns:literal, $feats:literal, $Nexts:ident))* $(,)? ) => {
+        $m!($N, $feat, [ $($Acc,)* $Next ], ( $($Acc,)* $Next, ) );
+        generate_pipeline!(@recurse $m [ $($Acc,)* $Next ] ; $( ($Ns, $feats, $Nexts) ),* );
     };
+}
 
-    (@recurse $acc:tt ; ) => {};
+/// Invokes `$m! { $($pre;)* (0, "0"), (1, "1", P1), .., (50, "50", P50) }` with the full arity list.
+macro_rules! arity_list {
+    ($m:ident $(, $pre:tt)*) => {
+        $m! { $($pre;)*
+            (0, "0"),
+            (1, "1", P1), (2, "2", P2), (3, "3", P3), (4, "4", P4), (5, "5", P5),
+            (6, "6", P6), (7, "7", P7), (8, "8", P8), (9, "9", P9), (10, "10", P10),
+            (11, "11", P11), (12, "12", P12), (13, "13", P13), (14, "14", P14), (15, "15", P15),
+            (16, "16", P16), (17, "17", P17), (18, "18", P18), (19, "19", P19), (20, "20", P20),
+            (21, "21", P21), (22, "22", P22), (23, "23", P23), (24, "24", P24), (25, "25", P25),
+            (26, "26", P26), (27, "27", P27), (28, "28", P28), (29, "29", P29), (30, "30", P30),
+            (31, "31", P31), (32, "32", P32), (33, "33", P33), (34, "34", P34), (35, "35", P35),
+            (36, "36", P36), (37, "37", P37), (38, "38", P38), (39, "39", P39), (40, "40", P40),
+            (41, "41", P41), (42, "42", P42), (43, "43", P43), (44, "44", P44), (45, "45", P45),
+            (46, "46", P46), (47, "47", P47), (48, "48", P48), (49, "49", P49), (50, "50", P50),
+        }
+    };
+}
 
-    (@recurse [ $($Acc:ident),* ] ; ($N:literal, $feat:literal, $Next:ident) $(, ($Ns:literal, $feats:literal, $Nexts:ident))* $(,)? ) => {
-        impl_arity!($N, $feat, [ $($Acc,)* $Next ], ( $($Acc,)* $Next, ) );
-        generate_pipeline!(@recurse [ $($Acc,)* $Next ] ; $( ($Ns, $feats, $Nexts) ),* );
+/// Invokes `$m!(N, "N", [P1, .., PN], (P1, .., PN,))` once for every arity from 0 to 50.
+macro_rules! for_each_arity {
+    ($m:ident) => {
+        arity_list!(generate_pipeline, $m);
     };
 }
 
+/// Gates each item on at least one arity feature being enabled, for helpers
+/// only the generated impls use. With a leading `allow_dead_code;`, the items
+/// are kept but `dead_code` is allowed when no arity is enabled.
+macro_rules! any_arity {
+    (@apply $pred:tt; $($item:item)*) => { $( #[cfg $pred] $item )* };
+    (@allow_dead_code $pred:tt; $($item:item)*) => { $( #[cfg_attr(not $pred, allow(dead_code))] $item )* };
+    ([$mode:ident]; { $($item:item)* }; (0, $feat0:literal) $(, ($N:literal, $feat:literal, $Next:ident))* $(,)?) => {
+        any_arity!(@$mode (any(feature = $feat0 $(, feature = $feat)*)); $($item)*);
+    };
+    (allow_dead_code; $($item:item)*) => { arity_list!(any_arity, [allow_dead_code], { $($item)* }); };
+    ($($item:item)*) => { arity_list!(any_arity, [apply], { $($item)* }); };
+}
+
+/// Evaluates to the highest arity whose feature is enabled, or 0 if none is.
+macro_rules! max_arity {
+    ( (0, $feat0:literal) $(, ($N:literal, $feat:literal, $Next:ident))* $(,)? ) => {{
+        #[allow(unused_mut)]
+        let mut max = 0;
+        $( if cfg!(feature = $feat) { max = $N; } )*
+        max
+    }};
+}
+
+/// The highest arity (number of arguments after the pipeline value) enabled by the crate features.
+///
+/// Arity features can be enabled individually, so lower arities are not guaranteed to be available;
+/// this is only the upper bound. It is 0 if no arity feature is enabled.
+///
+/// ```rust
+/// // Fail the build early if the enabled features do not cover the arities a crate relies on.
+/// const _: () = assert!(pipei::MAX_ARITY >= 3, "enable pipei's `3` feature");
+/// ```
+pub const MAX_ARITY: usize = arity_list!(max_arity);
+
+/// Returns [`MAX_ARITY`].
+#[inline(always)]
+pub const fn max_supported_arity() -> usize {
+    MAX_ARITY
+}
+
 mod generated {
     #![allow(non_snake_case)]
 
-    generate_pipeline! {
-        (0, "0"),
-        (1, "1", P1), (2, "2", P2), (3, "3", P3), (4, "4", P4), (5, "5", P5),
-        (6, "6", P6), (7, "7", P7), (8, "8", P8), (9, "9", P9), (10, "10", P10),
-        (11, "11", P11), (12, "12", P12), (13, "13", P13), (14, "14", P14), (15, "15", P15),
-        (16, "16", P16), (17, "17", P17), (18, "18", P18), (19, "19", P19), (20, "20", P20),
-        (21, "21", P21), (22, "22", P22), (23, "23", P23), (24, "24", P24), (25, "25", P25),
-        (26, "26", P26), (27, "27", P27), (28, "28", P28), (29, "29", P29), (30, "30", P30),
-        (31, "31", P31), (32, "32", P32), (33, "33", P33), (34, "34", P34), (35, "35", P35),
-        (36, "36", P36), (37, "37", P37), (38, "38", P38), (39, "39", P39), (40, "40", P40),
-        (41, "41", P41), (42, "42", P42), (43, "43", P43), (44, "44", P44), (45, "45", P45),
-        (46, "46", P46), (47, "47", P47), (48, "48", P48), (49, "49", P49), (50, "50", P50),
-    }
+    for_each_arity!(impl_arity);
 }
 
+mod toggle;
+
+pub mod type_aliases;
+
+mod collection;
+pub use collection::{PipeCollection, WindowMap};
+
+mod tap_value;
+pub use tap_value::{Snapshot, TapValue};
+
+mod atomic;
+pub use atomic::PipeAtomic;
+
+mod guard;
+pub use guard::{DropGuard, PipeOnDrop, PipeResourceGuard};
+
+mod iter_ext;
+pub use iter_ext::{PipeFromIter, ProgressTap, TapIter};
+
+mod monad;
+pub use monad::{PipeBimap, PipeBind, PipeMonad, PipeRecover};
+
+mod convert;
+pub use convert::PipeConvert;
+
+mod scope;
+pub use scope::{Also, Run};
+
+mod variant;
+pub use variant::{PipeOption, PipeResult, TapOption, TapResult};
+
+mod versioned;
+pub use versioned::Versioned;
+
+mod seq;
+pub use seq::PipeSeq;
+
+mod fallible;
+pub use fallible::{PipeTry, PipeWhileOk};
+
+mod validate;
+pub use validate::{FieldSchema, PipeValidate, Validate, ValidationError};
+
+mod authorization;
+pub use authorization::{AuthError, Capability, DenyAll, PermitAll, PipeAuthorize};
+
+mod budget;
+pub use budget::{Budget, BudgetExceeded, PipeBudget};
+
+mod version_gate;
+pub use version_gate::{AbiImpl, AbiVersion, PipeAbiVersion, PipeVersionGate, Version, VersionTooOld};
+
+mod type_name;
+pub use type_name::{PipeAssertImplements, PipeTypeName, TraitOf};
+
+mod conditional;
+pub use conditional::{IndexOutOfBounds, PipeConditional};
+
+mod state_machine;
+pub use state_machine::StateMachine;
+
+mod type_state;
+pub use type_state::{TypeStatePipe, Unvalidated, Validated};
+
+mod config;
+pub use config::PipeConfig;
+
+mod converge;
+pub use converge::PipeConverge;
+
+mod dirty;
+pub use dirty::Dirty;
+
+#[cfg(feature = "alloc")]
+mod observer;
+#[cfg(feature = "alloc")]
+pub use observer::Observable;
+#[cfg(feature = "std")]
+pub use observer::SharedObservable;
+
+#[cfg(feature = "alloc")]
+mod boxed;
+#[cfg(feature = "alloc")]
+pub use boxed::PipeBoxed;
+
+#[cfg(feature = "alloc")]
+mod ref_count;
+#[cfg(feature = "alloc")]
+pub use ref_count::TapRefCount;
+#[cfg(all(feature = "alloc", target_has_atomic = "ptr"))]
+pub use ref_count::TapArcRefCount;
+
+#[cfg(feature = "alloc")]
+mod graph;
+#[cfg(feature = "alloc")]
+pub use graph::PipelineGraph;
+
+#[cfg(feature = "alloc")]
+mod lazy;
+#[cfg(feature = "alloc")]
+pub use lazy::Lazy;
+
+#[cfg(feature = "alloc")]
+mod undo;
+#[cfg(feature = "alloc")]
+pub use undo::{PipeUndo, UndoStack};
+
+#[cfg(feature = "alloc")]
+mod health;
+#[cfg(feature = "alloc")]
+pub use health::{HealthStatus, PipeHealthCheck, ServiceUnhealthy};
+
+#[cfg(feature = "alloc")]
+mod schema;
+#[cfg(feature = "alloc")]
+pub use schema::{ExportedSchema, TapSchema};
+
+#[cfg(feature = "alloc")]
+mod transform_log;
+#[cfg(feature = "alloc")]
+pub use transform_log::{PipeLogged, TransformLog};
+
+#[cfg(feature = "alloc")]
+mod saga;
+#[cfg(feature = "alloc")]
+pub use saga::{PipeSaga, Saga, SagaFailed, SagaStep};
+
+#[cfg(feature = "alloc")]
+mod migrate;
+#[cfg(feature = "alloc")]
+pub use migrate::{Migration, MigrationError, Migrator, PipeMigrate};
+
+#[cfg(feature = "alloc")]
+mod export_ext;
+#[cfg(feature = "std")]
+pub use export_ext::DebugMetricsExporter;
+#[cfg(feature = "alloc")]
+pub use export_ext::{Metric, MetricsExporter, TapExport, VecMetricsExporter};
+
+#[cfg(feature = "std")]
+mod panic;
+#[cfg(feature = "std")]
+pub use panic::PipePanic;
+
+#[cfg(feature = "std")]
+mod dispatch;
+#[cfg(feature = "std")]
+pub use dispatch::{PipeDispatch, PipeDispatchTable};
+
+#[cfg(feature = "std")]
+mod circuit;
+#[cfg(feature = "std")]
+pub use circuit::{CircuitBreaker, CircuitBreakerConfig, CircuitOpen, CircuitState, PipeCircuitBreaker};
+
+#[cfg(feature = "std")]
+mod retry;
+#[cfg(feature = "std")]
+pub use retry::{BackoffPolicy, PipeRetry, RetryExhausted, RetryStrategy};
+
+#[cfg(feature = "std")]
+mod rate_limit;
+#[cfg(feature = "std")]
+pub use rate_limit::{PipeRateLimit, RateLimited, RateLimiter};
+
+#[cfg(feature = "std")]
+mod deadline;
+#[cfg(feature = "std")]
+pub use deadline::{DeadlineExceeded, PipeDeadline};
+
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
+pub use cache::PipeCache;
+
+#[cfg(feature = "std")]
+mod audit;
+#[cfg(feature = "std")]
+pub use audit::{AuditEntry, AuditLog, PipeAudit};
+
+#[cfg(feature = "std")]
+mod profiler;
+#[cfg(feature = "std")]
+pub use profiler::{MetricSpan, PipeProfile, Profiler, SimpleProfiler, TapMetricSpan};
+
+#[cfg(feature = "db")]
+pub mod db;
+
+#[cfg(feature = "async")]
+mod future;
+#[cfg(feature = "async")]
+pub use future::{PipeAsync, TapAsync};
+
+#[cfg(feature = "std")]
+mod scoped;
+
+#[cfg(feature = "std")]
+pub mod trace;
+
+#[cfg(feature = "std")]
+pub mod request_ctx;
+
+#[cfg(feature = "std")]
+pub mod correlation;
+
+#[cfg(feature = "std")]
+pub mod execution_ctx;
+
+#[cfg(feature = "std")]
+pub mod tenant;
+
+#[cfg(feature = "std")]
+pub mod rbac;
+
+#[cfg(feature = "std")]
+mod env;
+#[cfg(feature = "std")]
+pub use env::{EnvError, PipeFromEnv};
+
+#[cfg(feature = "std")]
+mod backpressure;
+#[cfg(feature = "std")]
+pub use backpressure::PipeBackpressure;
+
+#[cfg(feature = "std")]
+mod pool;
+#[cfg(feature = "std")]
+pub use pool::{PipePool, PoolHandle, ThreadPool};
+
+#[cfg(feature = "rayon")]
+mod traverse;
+#[cfg(feature = "rayon")]
+pub use traverse::PipeTraverse;
+
+#[cfg(feature = "rand")]
+mod sampling;
+#[cfg(feature = "rand")]
+pub use sampling::PipeSampling;
+
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "serde")]
+pub use serialize::{DeserializerPipe, PipeDeserialize, PipeSerialize};
+#[cfg(feature = "json-patch")]
+pub use serialize::TapJsonPatch;
+
+#[cfg(all(feature = "serde", feature = "std"))]
+mod idempotency;
+#[cfg(all(feature = "serde", feature = "std"))]
+pub use idempotency::{IdempotencyResult, IdempotencyStore, PipeIdempotency};
+
+#[cfg(feature = "log")]
+mod log_ext;
+#[cfg(feature = "log")]
+pub use log_ext::TapConditionalLog;
+#[cfg(all(feature = "log", feature = "serde", feature = "std"))]
+pub use log_ext::TapStructuredLog;
+
+#[cfg(feature = "flate2")]
+mod compression;
+#[cfg(feature = "flate2")]
+pub use compression::PipeGzip;
+
+#[cfg(feature = "metrics")]
+mod metrics_ext;
+#[cfg(feature = "metrics")]
+pub use metrics_ext::TapMetrics;
+
+#[cfg(feature = "opentelemetry")]
+mod otel_ext;
+#[cfg(feature = "opentelemetry")]
+pub use otel_ext::PipeOtelSpan;
+
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "futures")]
+pub use stream::PipeAsyncStream;
+
+#[cfg(feature = "macros")]
+pub use pipei_macros::{pipe_derive, TapFields, Validate};
+
 #[doc(hidden)]
 /// Marker type: pass the pipeline value by shared reference (`&T`).
 pub struct Imm;
@@ -350,14 +1165,40 @@ pub struct TapMark;
 /// Marker type: `pipe` semantics (return the function's result).
 pub struct PipeMark;
 #[doc(hidden)]
+/// Marker type: `pipe_zip_with` semantics (curry two leading values).
+pub struct ZipMark;
+#[doc(hidden)]
+/// Marker type: `pipe_branch` semantics (one of two arms, chosen by a predicate).
+pub struct BranchMark;
+#[cfg(feature = "either")]
+#[doc(hidden)]
+/// Marker type: `pipe_branch_either` semantics (one of two arms with different result types).
+pub struct BranchEitherMark;
+#[doc(hidden)]
+/// Marker type: `pipe_if` semantics (pipe only if a predicate holds).
+pub struct PipeIfMark;
+#[doc(hidden)]
+/// Marker type: the call is wrapped by an [`Around`] implementation.
+pub struct AroundMark;
+#[doc(hidden)]
 /// Marker type: `tap_proj` semantics (unconditional projection).
 pub struct Proj;
 #[doc(hidden)]
 /// Marker type: `tap_cond` semantics (conditional projection via Option).
 pub struct Cond;
+#[doc(hidden)]
+/// Marker type: `tap_if` semantics (tap only if a predicate holds).
+pub struct TapIfMark;
+#[doc(hidden)]
+/// Marker type: `tap_side_channel_fmt` semantics (write to a captured sink).
+pub struct WriteMark;
 
 #[doc(hidden)]
 /// Internal: curries a function's first argument, producing a closure over the remaining arguments.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be curried with `{A0}` as its first argument",
+    note = "check the function's signature, and that its arity is enabled: enable the `N` (or an `up_to_N`) feature to use arity-N pipe functions"
+)]
 pub trait Curry<const ARITY: usize, Params, AState, RState, MARK, A0: ?Sized, R: ?Sized> {
     type Curry;
     fn curry(self, arg0: A0) -> Self::Curry;
@@ -365,7 +1206,19 @@ pub trait Curry<const ARITY: usize, Params, AState, RState, MARK, A0: ?Sized, R:
 
 #[doc(hidden)]
 /// Internal: curries a function's first argument through a projection (conditional or unconditional).
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be curried with `{A0}` and `{P}`",
+    note = "check the function's signature, and that its arity is enabled: enable the `N` (or an `up_to_N`) feature to use arity-N pipe functions"
+)]
 pub trait CurryWith<const ARITY: usize, Params, State, MARK, A0: ?Sized, P, R: ?Sized> {
     type Curry;
     fn curry_with(self, arg0: A0, proj: P) -> Self::Curry;
 }
+
+#[doc(hidden)]
+/// Internal: wraps the call of a curried function, e.g. to guard, time, or recover from it.
+/// `call` runs the function with the remaining arguments already applied.
+pub trait Around<A0, R> {
+    type Output;
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output;
+}
@@ -0,0 +1,135 @@
+//! Taps that emit log records through the `log` facade.
+
+#[cfg(all(feature = "serde", feature = "std"))]
+use core::fmt::Debug;
+#[cfg(all(feature = "serde", feature = "std"))]
+use std::{collections::HashMap, format, string::String};
+
+use log::Level;
+#[cfg(all(feature = "serde", feature = "std"))]
+use serde_json::{Map, Value};
+
+use crate::{CurryWith, Own};
+
+/// Extension trait for logging a value only when it is of interest.
+pub trait TapConditionalLog<const ARITY: usize> {
+    /// Logs `format_fn(&self, &args..)` at `level` only if that level is
+    /// enabled and `pred(&self)` holds, then returns `self` in all cases.
+    /// Neither `pred` nor `format_fn` runs while the level is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapConditionalLog;
+    /// use log::Level;
+    ///
+    /// fn slow(ms: &u64, op: &&str) -> String { format!("{op} took {ms} ms") }
+    ///
+    /// // Logs "query took 950 ms" at warn level; 20 ms is not logged.
+    /// let latency = 950.tap_with_conditional_log(Level::Warn, |ms| *ms > 500, slow)("query");
+    /// assert_eq!(latency, 950);
+    /// assert_eq!(20.tap_with_conditional_log(Level::Warn, |ms| *ms > 500, slow)("query"), 20);
+    /// ```
+    #[inline(always)]
+    fn tap_with_conditional_log<Pr, M, F, Params>(self, level: Level, pred: Pr, format_fn: F) -> F::Curry
+    where
+        Pr: FnOnce(&Self) -> bool,
+        F: CurryWith<ARITY, Params, Own, ConditionalLogMark, Self, (Level, Pr), M>,
+        Self: Sized,
+    {
+        format_fn.curry_with(self, (level, pred))
+    }
+}
+impl<const ARITY: usize, T> TapConditionalLog<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `tap_with_conditional_log` semantics (log only if enabled and a predicate holds).
+pub struct ConditionalLogMark;
+
+/// Extension trait for emitting structured log records mid-pipeline.
+///
+/// Records are logged as a JSON object, so a backend that forwards messages
+/// to a structured log store can index their fields.
+#[cfg(all(feature = "serde", feature = "std"))]
+pub trait TapStructuredLog: Debug + Sized {
+    /// Logs `fields` at `level` as a JSON object, with `self`'s `Debug`
+    /// representation added as the `"value"` field, then returns `self`.
+    /// The `"value"` field replaces an entry of the same name in `fields`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, TapStructuredLog};
+    /// use std::collections::HashMap;
+    ///
+    /// fn retries(attempts: u32) -> u32 { attempts - 1 }
+    ///
+    /// let fields = HashMap::from([("stage".to_string(), "fetch".into())]);
+    /// // Logs `{"stage":"fetch","value":"3"}` at info level.
+    /// let n = 3.tap_with_structured_log(log::Level::Info, fields).pipe(retries)();
+    /// assert_eq!(n, 2);
+    /// ```
+    #[inline]
+    fn tap_with_structured_log(self, level: Level, fields: HashMap<String, Value>) -> Self {
+        if log::log_enabled!(level) {
+            let mut record: Map<String, Value> = fields.into_iter().collect();
+            record.insert("value".into(), Value::String(format!("{self:?}")));
+            log::log!(level, "{}", Value::Object(record));
+        }
+        self
+    }
+
+    /// Like [`tap_with_structured_log`](TapStructuredLog::tap_with_structured_log)
+    /// at info level, with `event_name` added as the `"event"` field.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapStructuredLog;
+    /// use std::collections::HashMap;
+    ///
+    /// let fields = HashMap::from([("user".to_string(), 42.into())]);
+    /// // Logs `{"event":"login","user":42,"value":"true"}` at info level.
+    /// assert!(true.tap_structured_event("login", fields));
+    /// ```
+    #[inline]
+    fn tap_structured_event(self, event_name: &str, mut fields: HashMap<String, Value>) -> Self {
+        fields.insert("event".into(), event_name.into());
+        self.tap_with_structured_log(Level::Info, fields)
+    }
+}
+
+#[cfg(all(feature = "serde", feature = "std"))]
+impl<T: Debug> TapStructuredLog for T {}
+
+macro_rules! impl_conditional_log_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{ConditionalLogMark, CurryWith, Level, Own};
+            #[cfg(feature = $feat)]
+            use core::fmt::Display;
+
+            // --- Conditional Log ---
+            #[cfg(feature = $feat)]
+            impl<F, Pr, A0, $($Params,)* M: Display> CurryWith<$N, $TupleType, Own, ConditionalLogMark, A0, (Level, Pr), M> for F
+            where F: FnOnce(&A0, $(&$Params),*) -> M, Pr: FnOnce(&A0) -> bool {
+                type Curry = impl FnOnce($($Params),*) -> A0;
+                #[inline(always)] fn curry_with(self, arg0: A0, (level, pred): (Level, Pr)) -> Self::Curry {
+                    move |$($Params),*| {
+                        if log::log_enabled!(level) && pred(&arg0) {
+                            log::log!(level, "{}", self(&arg0, $(&$Params),*));
+                        }
+                        arg0
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_conditional_log_arity);
+}
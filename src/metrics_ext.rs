@@ -0,0 +1,53 @@
+//! Taps that record to the `metrics` facade.
+
+use metrics::{IntoF64, IntoLabels, KeyName};
+
+/// Extension trait for recording metrics mid-pipeline through the installed
+/// `metrics` recorder, e.g. a Prometheus or StatsD exporter.
+///
+/// Without an installed recorder, the taps do nothing.
+pub trait TapMetrics: Sized {
+    /// Increments the counter `counter_name` with `labels` by one, then
+    /// returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, TapMetrics};
+    /// fn parse(line: &str) -> usize { line.split(',').count() }
+    ///
+    /// let fields = "a,b,c".tap_with_counter("lines_parsed", &[("format", "csv")]).pipe(parse)();
+    /// assert_eq!(fields, 3);
+    /// ```
+    #[inline]
+    fn tap_with_counter<N, L>(self, counter_name: N, labels: L) -> Self
+    where
+        N: Into<KeyName>,
+        L: IntoLabels,
+    {
+        metrics::counter!(counter_name, labels).increment(1);
+        self
+    }
+
+    /// Records `value_fn(&self)` as an observation of the histogram
+    /// `histogram_name`, then returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapMetrics;
+    /// let batch = vec![1, 2, 3].tap_with_histogram("batch_size", |b| b.len() as f64);
+    /// assert_eq!(batch, [1, 2, 3]);
+    /// ```
+    #[inline]
+    fn tap_with_histogram<N, V, F>(self, histogram_name: N, value_fn: F) -> Self
+    where
+        N: Into<KeyName>,
+        V: IntoF64,
+        F: FnOnce(&Self) -> V,
+    {
+        metrics::histogram!(histogram_name).record(value_fn(&self));
+        self
+    }
+}
+impl<T> TapMetrics for T {}
@@ -0,0 +1,116 @@
+//! Version-by-version data migrations applied as a pipe stage.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// A transformation from one version of a data type to the next.
+///
+/// Implemented for every `Fn(T) -> U`, so closures and functions can be
+/// registered directly.
+pub trait Migration<T, U> {
+    /// Migrates `value` forward by one version.
+    fn migrate(&self, value: T) -> U;
+}
+
+impl<T, U, F: Fn(T) -> U> Migration<T, U> for F {
+    #[inline(always)]
+    fn migrate(&self, value: T) -> U {
+        self(value)
+    }
+}
+
+/// Error returned when a [`Migrator`] has no path between two versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MigrationError {
+    from: u32,
+    to: u32,
+}
+
+impl MigrationError {
+    /// Returns the version the migration started from.
+    #[inline]
+    pub const fn from_version(&self) -> u32 {
+        self.from
+    }
+
+    /// Returns the version the migration was asked to reach.
+    #[inline]
+    pub const fn to_version(&self) -> u32 {
+        self.to
+    }
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no migration path from version {} to version {}", self.from, self.to)
+    }
+}
+
+impl core::error::Error for MigrationError {}
+
+/// An ordered list of migrations: the `n`-th registered migration takes a
+/// value from version `n` to version `n + 1`.
+pub struct Migrator<T> {
+    migrations: Vec<Box<dyn Migration<T, T>>>,
+}
+
+impl<T> Migrator<T> {
+    /// Creates a migrator with no migrations, which only supports version 0.
+    pub fn new() -> Self {
+        Self { migrations: Vec::new() }
+    }
+
+    /// Registers `migration` as the step to the next version, and returns the migrator.
+    pub fn add_migration(mut self, migration: impl Migration<T, T> + 'static) -> Self {
+        self.migrations.push(Box::new(migration));
+        self
+    }
+
+    /// Returns the latest version `value` can be migrated to.
+    pub fn latest_version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    /// Applies the migrations from `from_version` up to `to_version`, in order.
+    ///
+    /// Fails without running any migration if `to_version` is earlier than
+    /// `from_version` or later than [`latest_version`](Migrator::latest_version).
+    pub fn migrate(&self, value: T, from_version: u32, to_version: u32) -> Result<T, MigrationError> {
+        let steps = self
+            .migrations
+            .get(from_version as usize..to_version as usize)
+            .ok_or(MigrationError { from: from_version, to: to_version })?;
+        Ok(steps.iter().fold(value, |value, step| step.migrate(value)))
+    }
+}
+
+impl<T> Default for Migrator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extension trait for migrating a value between versions.
+pub trait PipeMigrate: Sized {
+    /// Pipes `self` through the migrations of `migrator` that take it from
+    /// `from_version` to `to_version`, as [`Migrator::migrate`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Migrator, PipeMigrate};
+    /// let migrator = Migrator::new()
+    ///     .add_migration(|s: String| s.replace("colour", "color"))
+    ///     .add_migration(|s: String| s.to_uppercase());
+    ///
+    /// assert_eq!("colour".to_string().pipe_migrate(&migrator, 0, 2).unwrap(), "COLOR");
+    /// assert_eq!("color".to_string().pipe_migrate(&migrator, 1, 1).unwrap(), "color");
+    /// assert!("color".to_string().pipe_migrate(&migrator, 1, 3).is_err());
+    /// ```
+    #[inline]
+    fn pipe_migrate(self, migrator: &Migrator<Self>, from_version: u32, to_version: u32) -> Result<Self, MigrationError> {
+        migrator.migrate(self, from_version, to_version)
+    }
+}
+impl<T> PipeMigrate for T {}
@@ -0,0 +1,196 @@
+//! A unified `bind` for `Option` and `Result`, and the matching pipes.
+
+use crate::{Curry, CurryWith, Own};
+
+/// A type whose success value can be fed into a function returning the same
+/// kind of wrapper, short-circuiting otherwise.
+///
+/// For `Option` and `Result`, `bind` is `and_then`.
+pub trait PipeMonad<T> {
+    /// The same wrapper with its success value replaced by `B`.
+    type Wrapped<B>;
+
+    /// Calls `f` with the success value, or propagates the short-circuiting variant.
+    fn bind<B, F: FnOnce(T) -> Self::Wrapped<B>>(self, f: F) -> Self::Wrapped<B>;
+}
+
+impl<T> PipeMonad<T> for Option<T> {
+    type Wrapped<B> = Option<B>;
+
+    #[inline(always)]
+    fn bind<B, F: FnOnce(T) -> Option<B>>(self, f: F) -> Option<B> {
+        self.and_then(f)
+    }
+}
+
+impl<T, E> PipeMonad<T> for Result<T, E> {
+    type Wrapped<B> = Result<B, E>;
+
+    #[inline(always)]
+    fn bind<B, F: FnOnce(T) -> Result<B, E>>(self, f: F) -> Result<B, E> {
+        self.and_then(f)
+    }
+}
+
+/// Extension trait for binding a [`PipeMonad`] through a multi-argument function.
+pub trait PipeBind<const ARITY: usize> {
+    /// Curries the success value of `self` into `f`, returning a closure over
+    /// the remaining arguments that evaluates `self.bind(|x| f(x, args..))`.
+    /// `f` is not called if `self` is `None` or `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBind;
+    /// fn checked_div(x: i32, y: i32) -> Option<i32> { x.checked_div(y) }
+    /// fn parse_in(s: &str, radix: u32) -> Result<i32, std::num::ParseIntError> { i32::from_str_radix(s, radix) }
+    ///
+    /// assert_eq!(Some(12).pipe_bind(checked_div)(4), Some(3));
+    /// assert_eq!(Some(12).pipe_bind(checked_div)(0), None);
+    /// assert_eq!(Ok::<_, std::num::ParseIntError>("ff").pipe_bind(parse_in)(16), Ok(255));
+    /// ```
+    #[inline(always)]
+    fn pipe_bind<T, B, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, BindMark, Self, (T, B)>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, M> PipeBind<ARITY> for M {}
+
+/// Extension trait for mapping both variants of a `Result`, or (with the
+/// `either` feature) both sides of an `either::Either`, at once.
+pub trait PipeBimap<const ARITY: usize> {
+    /// Returns a closure over the remaining arguments that maps `Ok(v)` to
+    /// `Ok(ok_fn(v, args..))` and `Err(e)` to `Err(err_fn(e, args..))`. Both
+    /// functions take the same remaining arguments; only one of them is called.
+    /// With the `either` feature, an `Either` maps `Left(l)` with the first
+    /// function and `Right(r)` with the second.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeBimap;
+    /// fn label(x: i32, unit: &str) -> String { format!("{x}{unit}") }
+    /// fn code(e: &str, _unit: &str) -> usize { e.len() }
+    ///
+    /// assert_eq!(Ok::<_, &str>(5).pipe_bimap(label, code)("ms"), Ok("5ms".to_string()));
+    /// assert_eq!(Err::<i32, _>("timeout").pipe_bimap(label, code)("ms"), Err(7));
+    /// ```
+    #[inline(always)]
+    fn pipe_bimap<G, R, F, Params>(self, ok_fn: F, err_fn: G) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, BimapMark, Self, G, R>,
+        Self: Sized,
+    {
+        ok_fn.curry_with(self, err_fn)
+    }
+}
+impl<const ARITY: usize, T> PipeBimap<ARITY> for T {}
+
+/// Extension trait for recovering from some errors of a `Result`.
+pub trait PipeRecover<const ARITY: usize> {
+    /// Returns a closure over the remaining arguments that evaluates
+    /// `self.or_else(|e| recovery_fn(e, args..))`. Since `recovery_fn` returns a
+    /// `Result`, it can recover from some errors and propagate the others.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeRecover;
+    /// #[derive(Debug, PartialEq)]
+    /// enum Error { Timeout, NotFound }
+    ///
+    /// fn retry_timeout(e: Error, fallback: u32) -> Result<u32, Error> {
+    ///     match e { Error::Timeout => Ok(fallback), e => Err(e) }
+    /// }
+    ///
+    /// assert_eq!(Err(Error::Timeout).pipe_recover(retry_timeout)(7), Ok(7));
+    /// assert_eq!(Err(Error::NotFound).pipe_recover(retry_timeout)(7), Err(Error::NotFound));
+    /// assert_eq!(Ok(1).pipe_recover(retry_timeout)(7), Ok(1));
+    /// ```
+    #[inline(always)]
+    fn pipe_recover<R, F, Params>(self, recovery_fn: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, RecoverMark, Self, R>,
+        Self: Sized,
+    {
+        recovery_fn.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeRecover<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_bimap` semantics (map whichever variant is present).
+pub struct BimapMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_recover` semantics (`or_else` with a fallible recovery).
+pub struct RecoverMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_bind` semantics. The result slot carries `(T, B)`,
+/// the success types before and after the bind.
+pub struct BindMark;
+
+macro_rules! impl_monad_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{BimapMark, BindMark, Curry, CurryWith, Own, PipeMonad, RecoverMark};
+
+            // --- Bind ---
+            #[cfg(feature = $feat)]
+            impl<F, M, T, B, $($Params),*> Curry<$N, $TupleType, Own, Own, BindMark, M, (T, B)> for F
+            where M: PipeMonad<T>, F: FnOnce(T, $($Params),*) -> M::Wrapped<B> {
+                type Curry = impl FnOnce($($Params),*) -> M::Wrapped<B>;
+                #[inline(always)] fn curry(self, arg0: M) -> Self::Curry {
+                    |$($Params),*| arg0.bind(|x| self(x, $($Params),*))
+                }
+            }
+
+            // --- Bimap ---
+            #[cfg(feature = $feat)]
+            impl<F, G, T, E, $($Params,)* U, V> CurryWith<$N, $TupleType, Own, BimapMark, Result<T, E>, G, Result<U, V>> for F
+            where F: FnOnce(T, $($Params),*) -> U, G: FnOnce(E, $($Params),*) -> V {
+                type Curry = impl FnOnce($($Params),*) -> Result<U, V>;
+                #[inline(always)] fn curry_with(self, arg0: Result<T, E>, err_fn: G) -> Self::Curry {
+                    |$($Params),*| match arg0 {
+                        Ok(v) => Ok(self(v, $($Params),*)),
+                        Err(e) => Err(err_fn(e, $($Params),*)),
+                    }
+                }
+            }
+
+            #[cfg(all(feature = $feat, feature = "either"))]
+            impl<F, G, A, B, $($Params,)* U, V> CurryWith<$N, $TupleType, Own, BimapMark, either::Either<A, B>, G, either::Either<U, V>> for F
+            where F: FnOnce(A, $($Params),*) -> U, G: FnOnce(B, $($Params),*) -> V {
+                type Curry = impl FnOnce($($Params),*) -> either::Either<U, V>;
+                #[inline(always)] fn curry_with(self, arg0: either::Either<A, B>, right_fn: G) -> Self::Curry {
+                    |$($Params),*| match arg0 {
+                        either::Either::Left(l) => either::Either::Left(self(l, $($Params),*)),
+                        either::Either::Right(r) => either::Either::Right(right_fn(r, $($Params),*)),
+                    }
+                }
+            }
+
+            // --- Recover ---
+            #[cfg(feature = $feat)]
+            impl<F, T, E, $($Params,)* E2> Curry<$N, $TupleType, Own, Own, RecoverMark, Result<T, E>, Result<T, E2>> for F
+            where F: FnOnce(E, $($Params),*) -> Result<T, E2> {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, E2>;
+                #[inline(always)] fn curry(self, arg0: Result<T, E>) -> Self::Curry {
+                    |$($Params),*| arg0.or_else(|e| self(e, $($Params),*))
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_monad_arity);
+}
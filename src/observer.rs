@@ -0,0 +1,276 @@
+//! Observable values: pipe stages that notify registered callbacks of each new value.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Curry, Own};
+
+/// A value with a list of observers that are notified after every
+/// [`pipe_observer`](Observable::pipe_observer) stage.
+///
+/// Observers receive a reference to the new value; since they are typed on
+/// `T`, each stage maps `T` to `T`. A stage returning some other `R` would
+/// have to drop every observer, as none of them can be called with an `&R`,
+/// and a subscriber that silently stops firing is worse than a type error.
+/// To change the type, leave the observable with
+/// [`into_inner`](Observable::into_inner) and wrap the result again.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::Observable;
+/// use std::cell::Cell;
+///
+/// fn scale(x: i32, k: i32) -> i32 { x * k }
+///
+/// let last = Cell::new(0);
+/// let obs = Observable::new(3).on(|x| last.set(*x));
+/// let obs = obs.pipe_observer(scale)(4);
+/// assert_eq!((*obs.get(), last.get()), (12, 12));
+/// ```
+pub struct Observable<'a, T> {
+    value: T,
+    observers: Vec<Observer<'a, T>>,
+}
+
+type Observer<'a, T> = Box<dyn Fn(&T) + 'a>;
+
+impl<'a, T> Observable<'a, T> {
+    /// Wraps `value` with no observers.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Observable { value, observers: Vec::new() }
+    }
+
+    /// Registers `callback` to be called with each new value.
+    #[inline]
+    pub fn on<C: Fn(&T) + 'a>(mut self, callback: C) -> Self {
+        self.observers.push(Box::new(callback));
+        self
+    }
+
+    /// Returns a reference to the current value.
+    #[inline]
+    pub const fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the number of registered observers.
+    #[inline]
+    pub fn observer_count(&self) -> usize {
+        self.observers.len()
+    }
+
+    /// Extracts the current value, dropping the observers.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Curries the current value into `f`, returning a closure over the remaining
+    /// arguments that stores `f`'s result, notifies every observer with it in
+    /// registration order, and returns the observable.
+    ///
+    /// `f` must return `T`, so that the registered observers can be called
+    /// with its result:
+    ///
+    /// ```rust,compile_fail
+    /// # use pipei::Observable;
+    /// let obs = Observable::new(3).on(|x: &i32| assert!(*x > 0));
+    /// let obs = obs.pipe_observer(|x: i32| x.to_string())();
+    /// ```
+    #[inline(always)]
+    pub fn pipe_observer<const ARITY: usize, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, ObserveMark, Self, T>,
+    {
+        f.curry(self)
+    }
+}
+
+any_arity! {
+    impl<T> Observable<'_, T> {
+        #[inline]
+        fn notify(&self) {
+            for observer in &self.observers {
+                observer(&self.value);
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Observable<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Observable")
+            .field("value", &self.value)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+pub use shared::SharedObservable;
+
+#[cfg(feature = "std")]
+mod shared {
+    use std::fmt;
+    use std::sync::{Arc, PoisonError, RwLock};
+    use std::vec::Vec;
+
+    use crate::{Curry, Imm};
+
+    use super::ObserveMark;
+
+    struct Inner<T> {
+        value: Arc<T>,
+        observers: Vec<SharedObserver<T>>,
+    }
+
+    type SharedObserver<T> = Arc<dyn Fn(&T) + Send + Sync>;
+
+    /// A thread-safe [`Observable`](super::Observable) handle. Clones share the
+    /// same value and observers.
+    ///
+    /// A stage's function runs while the write lock is held, so it must not
+    /// access the same `SharedObservable`. Observers run after the lock is
+    /// released, so they may read it; when stages race on several threads,
+    /// each observer still sees every new value, but not necessarily in the
+    /// order they were stored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::SharedObservable;
+    /// use std::sync::atomic::{AtomicI32, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// fn add(x: &i32, y: i32) -> i32 { x + y }
+    ///
+    /// let seen = Arc::new(AtomicI32::new(0));
+    /// let obs = SharedObservable::new(1);
+    /// let sink = Arc::clone(&seen);
+    /// obs.on(move |x| sink.store(*x, Ordering::SeqCst));
+    ///
+    /// let handle = obs.clone();
+    /// std::thread::spawn(move || handle.pipe_observer(add)(41)).join().unwrap();
+    /// assert_eq!((obs.get(), seen.load(Ordering::SeqCst)), (42, 42));
+    /// ```
+    pub struct SharedObservable<T> {
+        inner: Arc<RwLock<Inner<T>>>,
+    }
+
+    impl<T> SharedObservable<T> {
+        /// Wraps `value` with no observers.
+        #[inline]
+        pub fn new(value: T) -> Self {
+            SharedObservable { inner: Arc::new(RwLock::new(Inner { value: Arc::new(value), observers: Vec::new() })) }
+        }
+
+        /// Registers `callback` to be called with each new value.
+        #[inline]
+        pub fn on<C: Fn(&T) + Send + Sync + 'static>(&self, callback: C) -> &Self {
+            self.inner.write().unwrap_or_else(PoisonError::into_inner).observers.push(Arc::new(callback));
+            self
+        }
+
+        /// Returns a copy of the current value.
+        #[inline]
+        pub fn get(&self) -> T
+        where
+            T: Clone,
+        {
+            T::clone(&self.inner.read().unwrap_or_else(PoisonError::into_inner).value)
+        }
+
+        /// Curries a reference to the current value into `f`, returning a closure
+        /// over the remaining arguments that stores `f`'s result, notifies every
+        /// observer with it, and returns the handle.
+        #[inline(always)]
+        pub fn pipe_observer<const ARITY: usize, F, Params>(self, f: F) -> F::Curry
+        where
+            F: Curry<ARITY, Params, Imm, Imm, ObserveMark, Self, T>,
+        {
+            f.curry(self)
+        }
+    }
+
+    any_arity! {
+        impl<T> SharedObservable<T> {
+            pub(crate) fn update(&self, f: impl FnOnce(&T) -> T) {
+                let (value, observers) = {
+                    let mut inner = self.inner.write().unwrap_or_else(PoisonError::into_inner);
+                    inner.value = Arc::new(f(&inner.value));
+                    (Arc::clone(&inner.value), inner.observers.clone())
+                };
+                for observer in &observers {
+                    observer(&value);
+                }
+            }
+        }
+    }
+
+    impl<T> Clone for SharedObservable<T> {
+        #[inline]
+        fn clone(&self) -> Self {
+            SharedObservable { inner: Arc::clone(&self.inner) }
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for SharedObservable<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let inner = self.inner.read().unwrap_or_else(PoisonError::into_inner);
+            f.debug_struct("SharedObservable")
+                .field("value", &inner.value)
+                .field("observers", &inner.observers.len())
+                .finish()
+        }
+    }
+}
+
+#[doc(hidden)]
+/// Marker type: `pipe_observer` semantics (store the result and notify observers).
+pub struct ObserveMark;
+
+macro_rules! impl_observer_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, ObserveMark, Observable, Own};
+
+            // --- Pipe Observer ---
+            #[cfg(feature = $feat)]
+            impl<'a, F, A0, $($Params),*> Curry<$N, $TupleType, Own, Own, ObserveMark, Observable<'a, A0>, A0> for F
+            where F: FnOnce(A0, $($Params),*) -> A0 {
+                type Curry = impl FnOnce($($Params),*) -> Observable<'a, A0>;
+                #[inline(always)] fn curry(self, arg0: Observable<'a, A0>) -> Self::Curry {
+                    move |$($Params),*| {
+                        let Observable { value, observers } = arg0;
+                        let next = Observable { value: self(value, $($Params),*), observers };
+                        next.notify();
+                        next
+                    }
+                }
+            }
+
+            // --- Shared Pipe Observer ---
+            #[cfg(all(feature = $feat, feature = "std"))]
+            impl<F, A0, $($Params),*> Curry<$N, $TupleType, crate::Imm, crate::Imm, ObserveMark, super::SharedObservable<A0>, A0> for F
+            where F: FnOnce(&A0, $($Params),*) -> A0 {
+                type Curry = impl FnOnce($($Params),*) -> super::SharedObservable<A0>;
+                #[inline(always)] fn curry(self, arg0: super::SharedObservable<A0>) -> Self::Curry {
+                    move |$($Params),*| {
+                        arg0.update(|value| self(value, $($Params),*));
+                        arg0
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_observer_arity);
+}
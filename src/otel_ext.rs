@@ -0,0 +1,76 @@
+//! Pipe stages that run inside an OpenTelemetry span.
+
+use std::borrow::Cow;
+use std::thread;
+
+use opentelemetry::trace::{Status, TraceContextExt, Tracer};
+use opentelemetry::Context;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Extension trait for tracing pipe stages with OpenTelemetry.
+pub trait PipeOtelSpan<const ARITY: usize> {
+    /// Like `pipe`, but starts a span named `span_name` with `tracer` and makes
+    /// it the current span while `f` runs, so spans started inside `f` become
+    /// its children. The span is ended when `f` returns, with status `Ok`, or
+    /// when it panics, with status `Error`.
+    ///
+    /// Inside `f`, `Context::current().span().span_context()` gives the trace
+    /// and span ids to propagate to downstream services.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeOtelSpan;
+    /// use opentelemetry::trace::noop::NoopTracer;
+    ///
+    /// fn charge(cents: u64, fee: u64) -> u64 { cents + fee }
+    ///
+    /// let tracer = NoopTracer::new();
+    /// assert_eq!(1000.pipe_with_otel_span(&tracer, "charge", charge)(30), 1030);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_otel_span<'t, Tr, N, R, F, Params>(self, tracer: &'t Tr, span_name: N, f: F) -> F::Curry
+    where
+        Tr: Tracer,
+        N: Into<Cow<'static, str>>,
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, OtelSpan<'t, Tr>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, OtelSpan { tracer, name: span_name.into() })
+    }
+}
+impl<const ARITY: usize, T> PipeOtelSpan<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call inside a span that is current for its duration.
+pub struct OtelSpan<'t, Tr> {
+    tracer: &'t Tr,
+    name: Cow<'static, str>,
+}
+
+impl<Tr: Tracer, A0, R> Around<A0, R> for OtelSpan<'_, Tr>
+where
+    Tr::Span: Send + Sync + 'static,
+{
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        let cx = Context::current_with_span(self.tracer.start(self.name));
+        let _attached = cx.clone().attach();
+        // Declared after the guard, so the span ends before it stops being current.
+        let _end = EndSpan(cx);
+        call(arg0)
+    }
+}
+
+/// Ends the span of a context when dropped, marking it failed during a panic.
+struct EndSpan(Context);
+
+impl Drop for EndSpan {
+    fn drop(&mut self) {
+        let span = self.0.span();
+        span.set_status(if thread::panicking() { Status::error("panicked") } else { Status::Ok });
+        span.end();
+    }
+}
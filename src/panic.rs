@@ -0,0 +1,116 @@
+//! Pipes that control how a panicking stage unwinds.
+
+use crate::{Around, AroundMark, CurryWith, Own};
+use std::any::Any;
+use std::boxed::Box;
+use std::panic::{self, AssertUnwindSafe, UnwindSafe};
+use std::process;
+
+/// Extension trait for running pipe stages that may panic.
+pub trait PipePanic<const ARITY: usize> {
+    /// Like `pipe`, but catches a panic raised by `f` and returns it as
+    /// `Err(payload)`, so the pipeline can recover and continue.
+    ///
+    /// The receiver must be [`UnwindSafe`]; the function and the remaining
+    /// arguments are asserted to be unwind safe.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipePanic;
+    /// fn checked_div(x: i32, y: i32) -> i32 { x / y }
+    ///
+    /// assert_eq!(10.pipe_with_error_boundary(checked_div)(2).ok(), Some(5));
+    /// assert!(10.pipe_with_error_boundary(checked_div)(0).is_err());
+    /// ```
+    #[inline(always)]
+    fn pipe_with_error_boundary<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, CatchUnwind, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, CatchUnwind)
+    }
+
+    /// Like [`pipe_with_error_boundary`](PipePanic::pipe_with_error_boundary),
+    /// but returns `R::default()` when `f` panics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipePanic;
+    /// fn nth(v: Vec<i32>, i: usize) -> i32 { v[i] }
+    ///
+    /// assert_eq!(vec![1, 2].pipe_with_error_boundary_default(nth)(5), 0);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_error_boundary_default<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, CatchUnwindOrDefault, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, CatchUnwindOrDefault)
+    }
+
+    /// Like `pipe`, but aborts the process if `f` panics instead of letting the
+    /// panic unwind into the caller. Use it for stages where unwinding would
+    /// leave shared state corrupted, such as FFI callbacks; unlike
+    /// [`pipe_with_error_boundary`](PipePanic::pipe_with_error_boundary), the
+    /// pipeline cannot recover.
+    ///
+    /// The panic message is still printed by the panic hook before aborting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipePanic;
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// assert_eq!(1.pipe_with_abort_on_panic(add)(2), 3);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_abort_on_panic<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, AbortOnPanic, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, AbortOnPanic)
+    }
+}
+impl<const ARITY: usize, T> PipePanic<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: catches a panic and returns its payload as `Err`.
+pub struct CatchUnwind;
+
+impl<A0: UnwindSafe, R> Around<A0, R> for CatchUnwind {
+    type Output = Result<R, Box<dyn Any + Send>>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        panic::catch_unwind(AssertUnwindSafe(|| call(arg0)))
+    }
+}
+
+#[doc(hidden)]
+/// Wrapper: catches a panic and returns `R::default()` instead.
+pub struct CatchUnwindOrDefault;
+
+impl<A0: UnwindSafe, R: Default> Around<A0, R> for CatchUnwindOrDefault {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        CatchUnwind.around(arg0, call).unwrap_or_default()
+    }
+}
+
+#[doc(hidden)]
+/// Wrapper: aborts the process if the call panics.
+pub struct AbortOnPanic;
+
+impl<A0, R> Around<A0, R> for AbortOnPanic {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        panic::catch_unwind(AssertUnwindSafe(|| call(arg0))).unwrap_or_else(|_| process::abort())
+    }
+}
@@ -0,0 +1,192 @@
+//! A fixed-size thread pool that runs pipe stages off the calling thread.
+
+use std::boxed::Box;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::vec::Vec;
+
+use crate::{CurryWith, Own};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A pool of worker threads that take jobs from a shared queue.
+///
+/// Dropping the pool waits for every queued job to finish.
+pub struct ThreadPool {
+    queue: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    /// Starts a pool of `threads` workers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` is zero.
+    pub fn new(threads: usize) -> Self {
+        assert!(threads > 0, "a thread pool needs at least one thread");
+        let (queue, jobs) = mpsc::channel::<Job>();
+        let jobs = Arc::new(Mutex::new(jobs));
+        let workers = (0..threads)
+            .map(|_| {
+                let jobs = Arc::clone(&jobs);
+                thread::spawn(move || loop {
+                    // The lock is released before the job runs.
+                    let job = jobs.lock().unwrap().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+        ThreadPool { queue: Some(queue), workers }
+    }
+
+    /// Returns the number of worker threads.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// Queues `call` on the pool, returning a handle to its result.
+    pub fn spawn<R, C>(&self, call: C) -> PoolHandle<R>
+    where
+        C: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let job: Job = Box::new(move || {
+            // The handle may have been dropped; the result is then discarded.
+            let _ = tx.send(panic::catch_unwind(AssertUnwindSafe(call)));
+        });
+        self.queue.as_ref().unwrap().send(job).expect("thread pool workers have exited");
+        PoolHandle(rx)
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.queue.take());
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// A handle to the result of a stage running on a [`ThreadPool`].
+pub struct PoolHandle<R>(Receiver<thread::Result<R>>);
+
+impl<R> PoolHandle<R> {
+    /// Waits for the stage to finish, returning its result, or `Err` with the
+    /// panic payload if it panicked, as [`JoinHandle::join`] does.
+    pub fn join(self) -> thread::Result<R> {
+        self.0.recv().expect("thread pool dropped a queued job")
+    }
+}
+
+/// Extension trait for running pipe stages on a [`ThreadPool`].
+pub trait PipePool<const ARITY: usize> {
+    /// Like `pipe`, but queues `f(self, args..)` on `pool` and returns a
+    /// [`PoolHandle`] to its result instead of waiting for it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipePool, ThreadPool};
+    /// fn add(x: u64, y: u64) -> u64 { x + y }
+    ///
+    /// let pool = ThreadPool::new(2);
+    /// let handles: Vec<_> = (0..4).map(|i| i.pipe_with_pool(&pool, add)(10)).collect();
+    /// let sums: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+    /// assert_eq!(sums, [10, 11, 12, 13]);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_pool<'p, R, F, Params>(self, pool: &'p ThreadPool, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, PoolMark, Self, &'p ThreadPool, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, pool)
+    }
+
+    /// Like [`pipe_with_pool`](PipePool::pipe_with_pool), but waits for the
+    /// result, resuming the panic on the calling thread if `f` panicked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipePool, ThreadPool};
+    /// fn add(x: u64, y: u64) -> u64 { x + y }
+    ///
+    /// let pool = ThreadPool::new(2);
+    /// assert_eq!(1.pipe_with_pool_blocking(&pool, add)(2), 3);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_pool_blocking<'p, R, F, Params>(self, pool: &'p ThreadPool, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, PoolBlockingMark, Self, &'p ThreadPool, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, pool)
+    }
+}
+impl<const ARITY: usize, T> PipePool<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_with_pool` semantics (queue the call, return a handle).
+pub struct PoolMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_with_pool_blocking` semantics (queue the call, wait for it).
+pub struct PoolBlockingMark;
+
+macro_rules! impl_pool_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryWith, Own, PoolBlockingMark, PoolHandle, PoolMark, ThreadPool};
+
+            // --- Queue ---
+            #[cfg(feature = $feat)]
+            impl<'p, F, A0, $($Params,)* R> CurryWith<$N, $TupleType, Own, PoolMark, A0, &'p ThreadPool, R> for F
+            where
+                F: FnOnce(A0, $($Params),*) -> R + Send + 'static,
+                A0: Send + 'static,
+                $($Params: Send + 'static,)*
+                R: Send + 'static,
+            {
+                type Curry = impl FnOnce($($Params),*) -> PoolHandle<R>;
+                #[inline(always)] fn curry_with(self, arg0: A0, pool: &'p ThreadPool) -> Self::Curry {
+                    move |$($Params),*| pool.spawn(move || self(arg0, $($Params),*))
+                }
+            }
+
+            // --- Queue and wait ---
+            #[cfg(feature = $feat)]
+            impl<'p, F, A0, $($Params,)* R> CurryWith<$N, $TupleType, Own, PoolBlockingMark, A0, &'p ThreadPool, R> for F
+            where
+                F: FnOnce(A0, $($Params),*) -> R + Send + 'static,
+                A0: Send + 'static,
+                $($Params: Send + 'static,)*
+                R: Send + 'static,
+            {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry_with(self, arg0: A0, pool: &'p ThreadPool) -> Self::Curry {
+                    move |$($Params),*| match pool.spawn(move || self(arg0, $($Params),*)).join() {
+                        Ok(r) => r,
+                        Err(payload) => std::panic::resume_unwind(payload),
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_pool_arity);
+}
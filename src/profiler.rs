@@ -0,0 +1,227 @@
+//! Pipe stages timed through a pluggable profiler.
+
+use core::fmt::Write;
+use std::string::String;
+use std::time::{Duration, Instant};
+use std::vec::Vec;
+
+use crate::{Around, AroundMark, CurryWith, Imm, Own};
+
+/// A profiling backend notified around each timed stage.
+///
+/// The trait is object safe, so stages can report to a `&mut dyn Profiler`.
+pub trait Profiler {
+    /// Called just before the stage named `name` runs.
+    fn before_call(&mut self, name: &str);
+
+    /// Called after the stage named `name` ran for `duration_ns` nanoseconds.
+    fn after_call(&mut self, name: &str, duration_ns: u64);
+}
+
+/// A [`Profiler`] that records every stage's name and duration.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimpleProfiler {
+    records: Vec<(String, u64)>,
+}
+
+impl SimpleProfiler {
+    /// Creates a profiler with no records.
+    pub const fn new() -> Self {
+        SimpleProfiler { records: Vec::new() }
+    }
+
+    /// Returns the recorded `(name, duration_ns)` pairs in completion order.
+    pub fn records(&self) -> &[(String, u64)] {
+        &self.records
+    }
+}
+
+impl Profiler for SimpleProfiler {
+    fn before_call(&mut self, _name: &str) {}
+
+    fn after_call(&mut self, name: &str, duration_ns: u64) {
+        self.records.push((name.into(), duration_ns));
+    }
+}
+
+/// Extension trait for profiling pipe stages.
+pub trait PipeProfile<const ARITY: usize> {
+    /// Like `pipe`, but calls `profiler.before_call(name)` first, then times
+    /// `f(self, args..)` and reports the elapsed nanoseconds through
+    /// `profiler.after_call(name, ..)`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipeProfile, SimpleProfiler};
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let mut profiler = SimpleProfiler::new();
+    /// assert_eq!(1.pipe_with_profiler("add", &mut profiler, add)(2), 3);
+    /// assert_eq!(profiler.records()[0].0, "add");
+    /// ```
+    #[inline(always)]
+    fn pipe_with_profiler<'n, 'p, P, R, F, Params>(self, name: &'n str, profiler: &'p mut P, f: F) -> F::Curry
+    where
+        P: Profiler + ?Sized,
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Profiled<'n, 'p, P>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Profiled { name, profiler })
+    }
+}
+impl<const ARITY: usize, T> PipeProfile<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: reports the call's duration to a profiler.
+pub struct Profiled<'n, 'p, P: ?Sized> {
+    name: &'n str,
+    profiler: &'p mut P,
+}
+
+impl<P: Profiler + ?Sized, A0, R> Around<A0, R> for Profiled<'_, '_, P> {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        self.profiler.before_call(self.name);
+        let start = Instant::now();
+        let result = call(arg0);
+        let elapsed = u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.profiler.after_call(self.name, elapsed);
+        result
+    }
+}
+
+/// A timed stage and the stages timed while it ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetricSpan {
+    /// The stage's name.
+    pub name: String,
+    /// When the stage started.
+    pub start: Instant,
+    /// How long the stage ran, including its children.
+    pub duration: Duration,
+    /// The spans recorded inside this one, in completion order.
+    pub children: Vec<MetricSpan>,
+}
+
+impl MetricSpan {
+    /// Serializes the span tree as JSON, with durations in nanoseconds:
+    /// `{"name":"..","duration_ns":..,"children":[..]}`.
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out);
+        out
+    }
+
+    fn write_json(&self, out: &mut String) {
+        out.push_str("{\"name\":\"");
+        for c in self.name.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if c < ' ' => {
+                    let _ = write!(out, "\\u{:04x}", c as u32);
+                }
+                c => out.push(c),
+            }
+        }
+        let _ = write!(out, "\",\"duration_ns\":{},\"children\":[", self.duration.as_nanos());
+        for (i, child) in self.children.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            child.write_json(out);
+        }
+        out.push_str("]}");
+    }
+}
+
+/// Extension trait for recording a tree of timed stages.
+pub trait TapMetricSpan<const ARITY: usize> {
+    /// Like `tap`, but times `f(&self, children, args..)` and appends a
+    /// [`MetricSpan`] named `name` to `span_log`. Spans that `f` records into
+    /// `children` become the children of the new span.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{MetricSpan, TapMetricSpan};
+    /// fn leaf(_: &i32, _: &mut Vec<MetricSpan>) {}
+    /// fn parent(x: &i32, children: &mut Vec<MetricSpan>) {
+    ///     (*x).tap_with_metric_span("leaf", children, leaf)();
+    /// }
+    ///
+    /// let mut log = Vec::new();
+    /// 1.tap_with_metric_span("parent", &mut log, parent)();
+    /// assert_eq!(log[0].name, "parent");
+    /// assert_eq!(log[0].children[0].name, "leaf");
+    /// ```
+    #[inline(always)]
+    fn tap_with_metric_span<'n, 'l, R, F, Params>(self, name: &'n str, span_log: &'l mut Vec<MetricSpan>, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Imm, MetricSpanMark, Self, SpanTarget<'n, 'l>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, SpanTarget { name, span_log })
+    }
+}
+impl<const ARITY: usize, T> TapMetricSpan<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `tap_with_metric_span` semantics (time a tap into a span tree).
+pub struct MetricSpanMark;
+
+any_arity! {
+    allow_dead_code;
+
+    #[doc(hidden)]
+    /// Internal: the name of the span to record and the log to append it to.
+    pub struct SpanTarget<'n, 'l> {
+        name: &'n str,
+        span_log: &'l mut Vec<MetricSpan>,
+    }
+}
+
+any_arity! {
+    impl SpanTarget<'_, '_> {
+        #[inline(always)]
+        fn record<R>(self, call: impl FnOnce(&mut Vec<MetricSpan>) -> R) -> R {
+            let mut children = Vec::new();
+            let start = Instant::now();
+            let result = call(&mut children);
+            let duration = start.elapsed();
+            self.span_log.push(MetricSpan { name: self.name.into(), start, duration, children });
+            result
+        }
+    }
+}
+
+macro_rules! impl_metric_span_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryWith, Imm, MetricSpan, MetricSpanMark, SpanTarget};
+            #[cfg(feature = $feat)]
+            use std::vec::Vec;
+
+            #[cfg(feature = $feat)]
+            impl<'n, 'l, F, A0, $($Params,)* R> CurryWith<$N, $TupleType, Imm, MetricSpanMark, A0, SpanTarget<'n, 'l>, R> for F
+            where F: FnOnce(&A0, &mut Vec<MetricSpan>, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> A0;
+                #[inline(always)] fn curry_with(self, arg0: A0, target: SpanTarget<'n, 'l>) -> Self::Curry {
+                    move |$($Params),*| {
+                        target.record(|children| self(&arg0, children, $($Params),*));
+                        arg0
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_metric_span_arity);
+}
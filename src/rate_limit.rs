@@ -0,0 +1,104 @@
+//! Pipe stages guarded by a token-bucket rate limiter.
+
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Error returned when a call is rejected because no token was available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RateLimited;
+
+impl fmt::Display for RateLimited {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("rate limit exceeded")
+    }
+}
+
+impl std::error::Error for RateLimited {}
+
+/// A thread-safe token-bucket rate limiter.
+///
+/// The bucket holds up to one second's worth of tokens (at least one) and
+/// starts full. Tokens refill continuously at the configured rate.
+///
+/// Internally the bucket is tracked as the instant at which it will next be
+/// full, kept in a single atomic, so acquiring a token is lock-free.
+#[derive(Debug)]
+pub struct RateLimiter {
+    origin: Instant,
+    /// Nanoseconds after `origin` at which the bucket is full again.
+    full_at: AtomicU64,
+    interval_ns: u64,
+    burst_ns: u64,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `tokens_per_second` calls per second on average.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tokens_per_second` is not positive.
+    pub fn new(tokens_per_second: f64) -> Self {
+        assert!(tokens_per_second > 0.0, "tokens_per_second must be positive");
+        let interval_ns = ((1e9 / tokens_per_second) as u64).max(1);
+        let capacity = tokens_per_second.max(1.0).floor() as u64;
+        RateLimiter {
+            origin: Instant::now(),
+            full_at: AtomicU64::new(0),
+            interval_ns,
+            burst_ns: interval_ns.saturating_mul(capacity),
+        }
+    }
+
+    /// Takes a token if one is available.
+    pub fn try_acquire(&self) -> bool {
+        let now = self.origin.elapsed().as_nanos() as u64;
+        self.full_at
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |full_at| {
+                let next = full_at.max(now).saturating_add(self.interval_ns);
+                (next <= now.saturating_add(self.burst_ns)).then_some(next)
+            })
+            .is_ok()
+    }
+}
+
+/// Extension trait for piping values through a rate limiter.
+pub trait PipeRateLimit<const ARITY: usize> {
+    /// Like `pipe`, but takes a token from `limiter` first. Returns
+    /// `Ok(f(self, args..))` if one was available and `Err(RateLimited)`
+    /// without calling `f` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipeRateLimit, RateLimited, RateLimiter};
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let limiter = RateLimiter::new(1.0);
+    /// assert_eq!(1.pipe_with_rate_limit(&limiter, add)(2), Ok(3));
+    /// assert_eq!(1.pipe_with_rate_limit(&limiter, add)(2), Err(RateLimited));
+    /// ```
+    #[inline(always)]
+    fn pipe_with_rate_limit<'l, R, F, Params>(self, limiter: &'l RateLimiter, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Limited<'l>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Limited(limiter))
+    }
+}
+impl<const ARITY: usize, T> PipeRateLimit<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call only if the limiter has a token.
+pub struct Limited<'l>(&'l RateLimiter);
+
+impl<A0, R> Around<A0, R> for Limited<'_> {
+    type Output = Result<R, RateLimited>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        if self.0.try_acquire() { Ok(call(arg0)) } else { Err(RateLimited) }
+    }
+}
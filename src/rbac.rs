@@ -0,0 +1,150 @@
+//! Role-based access control: pipe stages that require named capabilities.
+
+use core::fmt;
+use core::iter::{self, Copied, Once};
+use core::slice;
+use std::collections::HashSet;
+use std::string::String;
+
+use crate::{Around, AroundMark, AuthError, Capability, CurryWith, Own};
+
+/// A named role and the capabilities it grants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Role<'a> {
+    /// The role's name, e.g. `"editor"`.
+    pub name: &'a str,
+    /// The capabilities the role grants, e.g. `["read", "write"]`.
+    pub capabilities: &'a [&'a str],
+}
+
+impl<'a> Role<'a> {
+    /// Creates a role granting `capabilities`.
+    #[inline]
+    pub const fn new(name: &'a str, capabilities: &'a [&'a str]) -> Self {
+        Role { name, capabilities }
+    }
+}
+
+/// The set of operations a user may perform.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities(HashSet<String>);
+
+impl Capabilities {
+    /// Creates an empty set.
+    #[inline]
+    pub fn new() -> Self {
+        Capabilities(HashSet::new())
+    }
+
+    /// Collects the capabilities granted by any of `roles`.
+    pub fn from_roles(roles: &[Role<'_>]) -> Self {
+        roles.iter().flat_map(|role| role.capabilities.iter().copied()).collect()
+    }
+
+    /// Adds `capability` to the set.
+    #[inline]
+    pub fn grant(&mut self, capability: impl Into<String>) {
+        self.0.insert(capability.into());
+    }
+
+    /// Returns `true` if the set holds `capability`.
+    #[inline]
+    pub fn contains(&self, capability: &str) -> bool {
+        self.0.contains(capability)
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for Capabilities {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        Capabilities(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// A [`Capabilities`] set permits exactly the operations it holds.
+impl Capability for Capabilities {
+    #[inline]
+    fn permits(&self, operation: &str) -> Result<(), AuthError> {
+        if self.contains(operation) { Ok(()) } else { Err(AuthError::new("missing capability")) }
+    }
+}
+
+/// Error returned when a required capability is missing. Holds its name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Unauthorized(pub String);
+
+impl fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "missing capability '{}'", self.0)
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+/// Extension trait for pipe stages that require capabilities.
+pub trait PipeCapability<const ARITY: usize> {
+    /// Like `pipe`, but returns `Ok(f(self, args..))` only if `caps` holds
+    /// `required`, and `Err(Unauthorized)` without calling `f` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::rbac::{Capabilities, PipeCapability, Role, Unauthorized};
+    /// fn rename(doc: String, name: &str) -> String { format!("{doc} -> {name}") }
+    ///
+    /// let viewer = Capabilities::from_roles(&[Role::new("viewer", &["read"])]);
+    /// assert_eq!(
+    ///     "doc".to_string().pipe_with_capability(&viewer, "write", rename)("new"),
+    ///     Err(Unauthorized("write".into())),
+    /// );
+    /// ```
+    #[inline(always)]
+    fn pipe_with_capability<'c, 'r, R, F, Params>(self, caps: &'c Capabilities, required: &'r str, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, RequireAll<'c, Once<&'r str>>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, RequireAll { caps, required: iter::once(required) })
+    }
+
+    /// Like [`pipe_with_capability`](PipeCapability::pipe_with_capability),
+    /// but requires every capability in `required`. The error names the first
+    /// missing one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::rbac::{Capabilities, PipeCapability, Role};
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let editor = Capabilities::from_roles(&[Role::new("editor", &["read", "write"])]);
+    /// assert_eq!(1.pipe_with_all_capabilities(&editor, &["read", "write"], add)(2), Ok(3));
+    /// assert!(1.pipe_with_all_capabilities(&editor, &["read", "delete"], add)(2).is_err());
+    /// ```
+    #[inline(always)]
+    fn pipe_with_all_capabilities<'c, 'r, R, F, Params>(self, caps: &'c Capabilities, required: &'r [&'r str], f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, RequireAll<'c, Copied<slice::Iter<'r, &'r str>>>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, RequireAll { caps, required: required.iter().copied() })
+    }
+}
+impl<const ARITY: usize, T> PipeCapability<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call only if every required capability is held.
+pub struct RequireAll<'c, I> {
+    caps: &'c Capabilities,
+    required: I,
+}
+
+impl<'r, I: Iterator<Item = &'r str>, A0, R> Around<A0, R> for RequireAll<'_, I> {
+    type Output = Result<R, Unauthorized>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(mut self, arg0: A0, call: C) -> Self::Output {
+        match self.required.find(|cap| !self.caps.contains(cap)) {
+            Some(missing) => Err(Unauthorized(missing.into())),
+            None => Ok(call(arg0)),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Taps that report the strong count of a reference-counted pointer.
+
+use alloc::rc::Rc;
+#[cfg(target_has_atomic = "ptr")]
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Extension trait for observing `Rc` and `Arc` ownership in a pipeline.
+pub trait TapRefCount: Sized {
+    /// Returns the number of strong pointers to the shared value.
+    fn ref_count(&self) -> usize;
+
+    /// Appends the current strong count to `log`, then returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapRefCount;
+    /// use std::rc::Rc;
+    ///
+    /// let mut log = Vec::new();
+    /// let a = Rc::new(1).tap_observe_ref_count(&mut log);
+    /// let b = Rc::clone(&a).tap_observe_ref_count(&mut log);
+    /// assert_eq!(log, [1, 2]);
+    /// # drop(b);
+    /// ```
+    #[inline]
+    fn tap_observe_ref_count(self, log: &mut Vec<usize>) -> Self {
+        log.push(self.ref_count());
+        self
+    }
+
+    /// Returns `self` if its strong count is `expected`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the strong count differs from `expected`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapRefCount;
+    /// use std::sync::Arc;
+    ///
+    /// let unique = Arc::new("cfg").tap_assert_ref_count(1);
+    /// assert_eq!(*unique, "cfg");
+    /// ```
+    #[inline]
+    #[track_caller]
+    fn tap_assert_ref_count(self, expected: usize) -> Self {
+        let actual = self.ref_count();
+        assert_eq!(actual, expected, "unexpected strong count");
+        self
+    }
+}
+
+impl<T: ?Sized> TapRefCount for Rc<T> {
+    #[inline]
+    fn ref_count(&self) -> usize {
+        Rc::strong_count(self)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: ?Sized> TapRefCount for Arc<T> {
+    #[inline]
+    fn ref_count(&self) -> usize {
+        Arc::strong_count(self)
+    }
+}
+
+/// `Arc`-only spelling of [`TapRefCount::tap_observe_ref_count`].
+#[cfg(target_has_atomic = "ptr")]
+pub trait TapArcRefCount: TapRefCount {
+    /// Appends the current strong count of the `Arc` to `log`, then returns
+    /// `self`. Unlike `tap_observe_ref_count`, this does not compile for `Rc`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapArcRefCount;
+    /// use std::sync::Arc;
+    ///
+    /// let mut log = Vec::new();
+    /// let a = Arc::new(1).tap_observe_arc_ref_count(&mut log);
+    /// let b = Arc::clone(&a).tap_observe_arc_ref_count(&mut log);
+    /// assert_eq!(log, [1, 2]);
+    /// # drop(b);
+    /// ```
+    #[inline]
+    fn tap_observe_arc_ref_count(self, log: &mut Vec<usize>) -> Self {
+        self.tap_observe_ref_count(log)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl<T: ?Sized> TapArcRefCount for Arc<T> {}
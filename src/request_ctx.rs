@@ -0,0 +1,80 @@
+//! Thread-local request context: run pipe stages on behalf of a request
+//! without passing its identifiers through every function.
+
+use core::cell::RefCell;
+
+use crate::scoped::with_scoped;
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Identifiers of the request a stage runs on behalf of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RequestContext {
+    /// Identifies the request.
+    pub request_id: u128,
+    /// The authenticated user, if any.
+    pub user_id: Option<u64>,
+    /// The trace the request belongs to.
+    pub trace_id: u128,
+}
+
+std::thread_local! {
+    static CURRENT_REQUEST: RefCell<Option<RequestContext>> = const { RefCell::new(None) };
+}
+
+/// Returns the context of the request being handled on this thread.
+///
+/// # Panics
+///
+/// Panics if no stage started by
+/// [`pipe_with_request_context`](PipeRequestContext::pipe_with_request_context)
+/// is running on this thread; see [`try_current`] for a non-panicking version.
+#[track_caller]
+pub fn current() -> RequestContext {
+    try_current().expect("no request context is active on this thread")
+}
+
+/// Returns the context of the request being handled on this thread, if any.
+pub fn try_current() -> Option<RequestContext> {
+    CURRENT_REQUEST.with_borrow(|ctx| *ctx)
+}
+
+/// Extension trait for running pipe stages on behalf of a request.
+pub trait PipeRequestContext<const ARITY: usize> {
+    /// Like `pipe`, but makes `ctx` the current request context while `f` runs.
+    /// The outer context, if any, is active again once `f` returns or unwinds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::request_ctx::{self, PipeRequestContext, RequestContext};
+    /// fn audit(action: &str, resource: &str) -> String {
+    ///     let ctx = request_ctx::current();
+    ///     format!("user {:?} {action} {resource}", ctx.user_id)
+    /// }
+    ///
+    /// let ctx = RequestContext { request_id: 1, user_id: Some(7), trace_id: 99 };
+    /// assert_eq!("read".pipe_with_request_context(ctx, audit)("/a"), "user Some(7) read /a");
+    /// assert_eq!(request_ctx::try_current(), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_request_context<R, F, Params>(self, ctx: RequestContext, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, WithRequest, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, WithRequest(ctx))
+    }
+}
+impl<const ARITY: usize, T> PipeRequestContext<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call with the request context set.
+pub struct WithRequest(RequestContext);
+
+impl<A0, R> Around<A0, R> for WithRequest {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        with_scoped(&CURRENT_REQUEST, self.0, || call(arg0))
+    }
+}
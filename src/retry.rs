@@ -0,0 +1,142 @@
+//! Pipe stages that retry a fallible function with a backoff policy.
+
+use core::fmt;
+use std::time::Duration;
+
+use crate::{CurryWith, Imm};
+
+/// How long to wait between retries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BackoffPolicy {
+    /// Retry without waiting.
+    Immediate,
+    /// Wait the same duration before every retry.
+    Constant(Duration),
+    /// Wait `base`, then twice as long before each subsequent retry, capped at `max_delay`.
+    Exponential {
+        /// Delay before the first retry.
+        base: Duration,
+        /// Upper bound on any single delay.
+        max_delay: Duration,
+    },
+}
+
+impl BackoffPolicy {
+    /// Returns the delay to wait after failed attempt number `attempt` (starting at 1).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        match *self {
+            BackoffPolicy::Immediate => Duration::ZERO,
+            BackoffPolicy::Constant(d) => d,
+            BackoffPolicy::Exponential { base, max_delay } => {
+                let factor = 1u32.checked_shl(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+                base.checked_mul(factor).map_or(max_delay, |d| d.min(max_delay))
+            }
+        }
+    }
+}
+
+/// How many times to call a function, and how long to wait in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryStrategy {
+    /// Total number of calls, including the first. Zero is treated as one.
+    pub max_attempts: u32,
+    /// Delay between consecutive calls.
+    pub backoff: BackoffPolicy,
+}
+
+/// Error returned when every attempt failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetryExhausted<E> {
+    /// Number of calls made.
+    pub attempts: u32,
+    /// Error returned by the last call.
+    pub last_error: E,
+}
+
+impl<E: fmt::Display> fmt::Display for RetryExhausted<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "gave up after {} attempts: {}", self.attempts, self.last_error)
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for RetryExhausted<E> {}
+
+/// Extension trait for retrying fallible pipe stages.
+pub trait PipeRetry<const ARITY: usize> {
+    /// Curries a reference to `self` into the fallible `f`, returning a closure over
+    /// the remaining arguments that calls `f` until it returns `Ok` or
+    /// `strategy.max_attempts` calls have been made, sleeping between attempts as
+    /// `strategy.backoff` prescribes. The remaining arguments are cloned for each call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{BackoffPolicy, PipeRetry, RetryStrategy};
+    /// use std::cell::Cell;
+    ///
+    /// let strategy = RetryStrategy { max_attempts: 3, backoff: BackoffPolicy::Immediate };
+    /// let calls = Cell::new(0);
+    /// let flaky = |x: &i32, ok_on: i32| {
+    ///     calls.set(calls.get() + 1);
+    ///     if calls.get() == ok_on { Ok(*x) } else { Err("not yet") }
+    /// };
+    ///
+    /// assert_eq!(7.pipe_with_retry_strategy(&strategy, flaky)(3), Ok(7));
+    /// assert_eq!(calls.get(), 3);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_retry_strategy<'s, R, F, Params>(self, strategy: &'s RetryStrategy, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Imm, RetryMark, Self, &'s RetryStrategy, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, strategy)
+    }
+}
+impl<const ARITY: usize, T> PipeRetry<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_with_retry_strategy` semantics (repeat until `Ok`).
+pub struct RetryMark;
+
+macro_rules! impl_retry_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryWith, Imm, RetryExhausted, RetryMark, RetryStrategy};
+
+            // --- Retry ---
+            #[cfg(feature = $feat)]
+            impl<'s, F, A0, $($Params: Clone,)* T, E> CurryWith<$N, $TupleType, Imm, RetryMark, A0, &'s RetryStrategy, Result<T, E>> for F
+            where F: Fn(&A0, $($Params),*) -> Result<T, E> {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, RetryExhausted<E>>;
+                #[inline(always)] fn curry_with(self, arg0: A0, strategy: &'s RetryStrategy) -> Self::Curry {
+                    move |$($Params),*| {
+                        let mut attempt = 1;
+                        loop {
+                            match self(&arg0, $($Params.clone()),*) {
+                                Ok(v) => return Ok(v),
+                                Err(last_error) if attempt >= strategy.max_attempts => {
+                                    return Err(RetryExhausted { attempts: attempt, last_error });
+                                }
+                                Err(_) => {
+                                    let delay = strategy.backoff.delay(attempt);
+                                    if !delay.is_zero() {
+                                        std::thread::sleep(delay);
+                                    }
+                                    attempt += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_retry_arity);
+}
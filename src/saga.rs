@@ -0,0 +1,134 @@
+//! Sagas: sequences of fallible steps that are compensated in reverse when one fails.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// A saga step: the forward action and the action that compensates for it.
+pub type SagaStep<T, E> = (fn(T) -> Result<T, E>, fn(T) -> T);
+
+/// An ordered list of [`SagaStep`]s.
+///
+/// [`run`](Saga::run) applies the forward actions in order. If one fails,
+/// the compensations of that step and of every step before it run in
+/// reverse order, starting from the value the failed step received; the
+/// failed step is compensated too, as its forward action may have partly
+/// applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Saga<T, E> {
+    steps: Vec<SagaStep<T, E>>,
+}
+
+impl<T, E> Saga<T, E> {
+    /// Creates a saga with no steps.
+    #[inline]
+    pub const fn new() -> Self {
+        Saga { steps: Vec::new() }
+    }
+
+    /// Appends a step.
+    #[inline]
+    pub fn add_step(mut self, forward: fn(T) -> Result<T, E>, compensate: fn(T) -> T) -> Self {
+        self.steps.push((forward, compensate));
+        self
+    }
+
+    /// Returns the number of steps.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Returns `true` if the saga has no steps.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl<T: Clone, E> Saga<T, E> {
+    /// Runs the saga on `value`, returning the result of the last step, or
+    /// the compensated value if a step failed.
+    pub fn run(&self, mut value: T) -> Result<T, SagaFailed<T, E>> {
+        for (step, (forward, _)) in self.steps.iter().enumerate() {
+            let input = value.clone();
+            value = match forward(value) {
+                Ok(next) => next,
+                Err(error) => {
+                    let value = self.steps[..=step].iter().rev().fold(input, |v, (_, compensate)| compensate(v));
+                    return Err(SagaFailed { step, error, value });
+                }
+            };
+        }
+        Ok(value)
+    }
+}
+
+impl<T, E> Default for Saga<T, E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error returned when a saga step fails.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SagaFailed<T, E> {
+    /// The index of the step that failed.
+    pub step: usize,
+    /// The error it returned.
+    pub error: E,
+    /// The value after compensation.
+    pub value: T,
+}
+
+impl<T, E: fmt::Display> fmt::Display for SagaFailed<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "saga step {} failed and was compensated: {}", self.step, self.error)
+    }
+}
+
+impl<T: fmt::Debug, E: core::error::Error> core::error::Error for SagaFailed<T, E> {}
+
+/// Extension trait for running a saga after a pipe stage.
+pub trait PipeSaga<const ARITY: usize> {
+    /// Like `pipe`, but runs `saga` on the result of `f(self, args..)`,
+    /// returning its outcome.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipeSaga, Saga};
+    /// fn reserve(stock: i32) -> Result<i32, &'static str> { if stock > 0 { Ok(stock - 1) } else { Err("sold out") } }
+    /// fn release(stock: i32) -> i32 { stock + 1 }
+    /// fn restock(stock: i32, n: i32) -> i32 { stock + n }
+    ///
+    /// let saga = Saga::new().add_step(reserve, release).add_step(reserve, release);
+    /// assert_eq!(0.pipe_with_saga(&saga, restock)(5), Ok(3));
+    ///
+    /// let failed = 0.pipe_with_saga(&saga, restock)(1).unwrap_err();
+    /// assert_eq!((failed.step, failed.error, failed.value), (1, "sold out", 2));
+    /// ```
+    #[inline(always)]
+    fn pipe_with_saga<'s, T, E, F, Params>(self, saga: &'s Saga<T, E>, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, RunSaga<'s, T, E>, T>,
+        Self: Sized,
+    {
+        f.curry_with(self, RunSaga(saga))
+    }
+}
+impl<const ARITY: usize, T> PipeSaga<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs a saga on the result of the call.
+pub struct RunSaga<'s, T, E>(&'s Saga<T, E>);
+
+impl<A0, T: Clone, E> Around<A0, T> for RunSaga<'_, T, E> {
+    type Output = Result<T, SagaFailed<T, E>>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> T>(self, arg0: A0, call: C) -> Self::Output {
+        self.0.run(call(arg0))
+    }
+}
@@ -0,0 +1,97 @@
+//! Pipe stages that run only for a random sample of calls.
+
+use rand::Rng;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Extension trait for running a pipe stage with a given probability.
+pub trait PipeSampling<const ARITY: usize> {
+    /// Like `pipe`, but only calls `f` with probability `rate`, drawing from
+    /// `rng` once per call. Returns `Some(f(self, args..))` if `f` ran and
+    /// `None` otherwise. A `rate` of `0.0` never runs `f`; `1.0` always does.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeSampling;
+    /// use rand::rngs::mock::StepRng;
+    ///
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let mut rng = StepRng::new(0, 0);
+    /// assert_eq!(1.pipe_with_sampling(1.0, &mut rng, add)(2), Some(3));
+    /// assert_eq!(1.pipe_with_sampling(0.0, &mut rng, add)(2), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_sampling<'r, G, R, F, Params>(self, rate: f64, rng: &'r mut G, f: F) -> F::Curry
+    where
+        G: Rng + ?Sized,
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Sampled<'r, G>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Sampled { rate, rng })
+    }
+
+    /// Like [`pipe_with_sampling`](PipeSampling::pipe_with_sampling), but
+    /// returns `default` instead of `None` when `f` is skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeSampling;
+    /// use rand::rngs::mock::StepRng;
+    ///
+    /// fn variant_b(x: u32, bonus: u32) -> u32 { x + bonus }
+    ///
+    /// let mut rng = StepRng::new(0, 0);
+    /// assert_eq!(10.pipe_with_sampling_or(0.0, &mut rng, variant_b, 10)(5), 10);
+    /// assert_eq!(10.pipe_with_sampling_or(1.0, &mut rng, variant_b, 10)(5), 15);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_sampling_or<'r, G, R, F, Params>(self, rate: f64, rng: &'r mut G, f: F, default: R) -> F::Curry
+    where
+        G: Rng + ?Sized,
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, SampledOr<'r, G, R>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, SampledOr { sampled: Sampled { rate, rng }, default })
+    }
+}
+impl<const ARITY: usize, T> PipeSampling<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call only if a draw from the generator falls below the rate.
+pub struct Sampled<'r, G: ?Sized> {
+    rate: f64,
+    rng: &'r mut G,
+}
+
+impl<G: Rng + ?Sized> Sampled<'_, G> {
+    #[inline(always)]
+    fn hit(self) -> bool {
+        self.rng.gen::<f64>() < self.rate
+    }
+}
+
+impl<G: Rng + ?Sized, A0, R> Around<A0, R> for Sampled<'_, G> {
+    type Output = Option<R>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        self.hit().then(|| call(arg0))
+    }
+}
+
+#[doc(hidden)]
+/// Wrapper: like [`Sampled`], falling back to a default result.
+pub struct SampledOr<'r, G: ?Sized, R> {
+    sampled: Sampled<'r, G>,
+    default: R,
+}
+
+impl<G: Rng + ?Sized, A0, R> Around<A0, R> for SampledOr<'_, G, R> {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        if self.sampled.hit() { call(arg0) } else { self.default }
+    }
+}
@@ -0,0 +1,66 @@
+//! Taps that document the types flowing through a pipeline.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any;
+use core::fmt::Write;
+
+/// The stages recorded by [`tap_export_schema`](TapSchema::tap_export_schema)
+/// and the type of the value at each, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportedSchema {
+    stages: Vec<(String, &'static str)>,
+}
+
+impl ExportedSchema {
+    /// Creates an empty schema.
+    #[inline]
+    pub const fn new() -> Self {
+        ExportedSchema { stages: Vec::new() }
+    }
+
+    /// Returns the recorded `(stage_name, type_name)` pairs.
+    #[inline]
+    pub fn stages(&self) -> &[(String, &'static str)] {
+        &self.stages
+    }
+
+    /// Renders the schema as a Markdown table with one row per stage.
+    ///
+    /// Type names come from [`core::any::type_name`] and are meant for
+    /// documentation: their exact contents may change between compiler versions.
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("| Stage | Type |\n| --- | --- |\n");
+        for (stage, ty) in &self.stages {
+            let _ = writeln!(out, "| {} | `{}` |", stage.replace('|', "\\|"), ty);
+        }
+        out
+    }
+}
+
+/// Extension trait for recording the pipeline value's type at each stage.
+pub trait TapSchema: Sized {
+    /// Appends `stage_name` and the name of `Self`'s type to `schema`, then
+    /// returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{ExportedSchema, TapSchema};
+    /// let mut schema = ExportedSchema::new();
+    /// let n = "42"
+    ///     .tap_export_schema(&mut schema, "input")
+    ///     .parse::<u8>()
+    ///     .tap_export_schema(&mut schema, "parsed");
+    ///
+    /// assert_eq!(n, Ok(42));
+    /// assert_eq!(schema.stages()[0], ("input".to_string(), "&str"));
+    /// assert!(schema.to_markdown().contains("| parsed |"));
+    /// ```
+    #[inline]
+    fn tap_export_schema(self, schema: &mut ExportedSchema, stage_name: &str) -> Self {
+        schema.stages.push((stage_name.into(), any::type_name::<Self>()));
+        self
+    }
+}
+impl<T> TapSchema for T {}
@@ -0,0 +1,96 @@
+//! Scope functions: run a closure on a value without the curried call form.
+
+use crate::{Imm, Mut};
+
+/// Extension trait for running a side effect with no further arguments.
+pub trait Also<State>: Sized {
+    /// Calls `f(&self)` or `f(&mut self)`, then returns `self`. This is
+    /// `tap(f)()` without the trailing call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::Also;
+    /// struct Counter { hits: u32 }
+    ///
+    /// let mut seen = 0;
+    /// let c = Counter { hits: 1 }
+    ///     .also(|c: &mut Counter| c.hits += 1)
+    ///     .also(|c: &Counter| seen = c.hits);
+    /// assert_eq!((c.hits, seen), (2, 2));
+    /// ```
+    #[inline(always)]
+    fn also<F>(mut self, f: F) -> Self
+    where
+        F: ScopeFn<Self, State>,
+    {
+        f.call_scoped(&mut self);
+        self
+    }
+}
+impl<State, T> Also<State> for T {}
+
+/// Extension trait for ending a pipeline by consuming the value.
+pub trait Run: Sized {
+    /// Calls `f(self)` and discards its result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::Run;
+    /// let mut saved = Vec::new();
+    /// vec![3, 1, 2].run(|mut v: Vec<i32>| { v.sort(); saved = v; });
+    /// assert_eq!(saved, [1, 2, 3]);
+    /// ```
+    #[inline(always)]
+    fn run<R, F>(self, f: F)
+    where
+        F: FnOnce(Self) -> R,
+    {
+        f(self);
+    }
+
+    /// Like [`run`](Run::run), but returns the result of `f(self)`. Unlike
+    /// `pipe(f)()`, there is no trailing call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::Run;
+    /// assert_eq!("a,b".run_returning(|s: &str| s.split(',').count()), 2);
+    /// ```
+    #[inline(always)]
+    fn run_returning<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(Self) -> R,
+    {
+        f(self)
+    }
+}
+impl<T> Run for T {}
+
+#[doc(hidden)]
+/// Internal: a function taking the value by shared (`Imm`) or exclusive (`Mut`) reference.
+pub trait ScopeFn<A, State> {
+    fn call_scoped(self, arg: &mut A);
+}
+
+impl<F, A, R> ScopeFn<A, Imm> for F
+where
+    F: FnOnce(&A) -> R,
+{
+    #[inline(always)]
+    fn call_scoped(self, arg: &mut A) {
+        self(arg);
+    }
+}
+
+impl<F, A, R> ScopeFn<A, Mut> for F
+where
+    F: FnOnce(&mut A) -> R,
+{
+    #[inline(always)]
+    fn call_scoped(self, arg: &mut A) {
+        self(arg);
+    }
+}
@@ -0,0 +1,39 @@
+//! Thread-local values set for the duration of a call.
+
+use core::cell::RefCell;
+use std::thread::LocalKey;
+
+/// A thread-local slot holding the value of the innermost active scope.
+pub(crate) type Slot<T> = LocalKey<RefCell<Option<T>>>;
+
+/// Puts the previous value back when dropped, including during unwinding,
+/// and hands the value of the ending scope to `done`.
+struct Restore<T: 'static, D: FnOnce(T)> {
+    key: &'static Slot<T>,
+    previous: Option<T>,
+    done: Option<D>,
+}
+
+impl<T: 'static, D: FnOnce(T)> Drop for Restore<T, D> {
+    fn drop(&mut self) {
+        let ended = self.key.replace(self.previous.take());
+        if let (Some(value), Some(done)) = (ended, self.done.take()) {
+            done(value);
+        }
+    }
+}
+
+/// Makes `value` the current value of `key` while `f` runs, restoring the
+/// previous value afterwards, even if `f` panics.
+#[inline]
+pub(crate) fn with_scoped<T, R>(key: &'static Slot<T>, value: T, f: impl FnOnce() -> R) -> R {
+    with_scoped_then(key, value, f, drop)
+}
+
+/// Like [`with_scoped`], but passes the value as `f` left it to `done` when
+/// the scope ends, so changes made while `f` ran can be kept.
+#[inline]
+pub(crate) fn with_scoped_then<T, R>(key: &'static Slot<T>, value: T, f: impl FnOnce() -> R, done: impl FnOnce(T)) -> R {
+    let _restore = Restore { key, previous: key.replace(Some(value)), done: Some(done) };
+    f()
+}
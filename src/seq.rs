@@ -0,0 +1,75 @@
+//! Pipes that thread a value through a list of functions in order.
+
+/// Extension trait for applying a sequence of same-typed transformations.
+pub trait PipeSeq: Sized {
+    /// Applies each function in `fs` to the result of the previous one, starting
+    /// with `self`. The array length is fixed at compile time, so no allocation
+    /// is needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeSeq;
+    /// fn add_one(x: i32) -> i32 { x + 1 }
+    /// fn double(x: i32) -> i32 { x * 2 }
+    ///
+    /// assert_eq!(3.pipe_seq([add_one, double, double]), 16);
+    /// ```
+    #[inline(always)]
+    fn pipe_seq<F: FnOnce(Self) -> Self, const N: usize>(self, fs: [F; N]) -> Self {
+        fs.into_iter().fold(self, |acc, f| f(acc))
+    }
+
+    /// Like [`pipe_seq`](PipeSeq::pipe_seq), but for a list whose length is only
+    /// known at run time, such as a `Vec<Box<dyn Fn(T) -> T>>` built from
+    /// configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeSeq;
+    /// let mut stages: Vec<Box<dyn Fn(String) -> String>> = vec![Box::new(|s| s.trim().to_string())];
+    /// stages.push(Box::new(|s| s.to_uppercase()));
+    ///
+    /// assert_eq!("  hi ".to_string().pipe_seq_dyn(stages), "HI");
+    /// ```
+    #[inline]
+    fn pipe_seq_dyn<F, I>(self, fs: I) -> Self
+    where
+        I: IntoIterator<Item = F>,
+        F: FnOnce(Self) -> Self,
+    {
+        fs.into_iter().fold(self, |acc, f| f(acc))
+    }
+
+    /// Like [`pipe_seq`](PipeSeq::pipe_seq) for fallible functions: applies each
+    /// function to the previous `Ok` value, stopping at and returning the first `Err`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeSeq;
+    /// fn non_empty(s: &str) -> Result<&str, String> { if s.is_empty() { Err("empty".into()) } else { Ok(s) } }
+    /// fn ascii(s: &str) -> Result<&str, String> { if s.is_ascii() { Ok(s) } else { Err(format!("{s:?} is not ascii")) } }
+    ///
+    /// assert_eq!("ok".pipe_sequence_fallible([non_empty, ascii]), Ok("ok"));
+    /// assert_eq!("".pipe_sequence_fallible([non_empty, ascii]), Err("empty".to_string()));
+    /// ```
+    #[inline(always)]
+    fn pipe_sequence_fallible<E, F: FnOnce(Self) -> Result<Self, E>, const N: usize>(self, fs: [F; N]) -> Result<Self, E> {
+        fs.into_iter().try_fold(self, |acc, f| f(acc))
+    }
+
+    /// Like [`pipe_sequence_fallible`](PipeSeq::pipe_sequence_fallible), but for a
+    /// list whose length is only known at run time, such as a
+    /// `Vec<Box<dyn FnOnce(T) -> Result<T, E>>>`.
+    #[inline]
+    fn pipe_sequence_fallible_dyn<E, F, I>(self, fs: I) -> Result<Self, E>
+    where
+        I: IntoIterator<Item = F>,
+        F: FnOnce(Self) -> Result<Self, E>,
+    {
+        fs.into_iter().try_fold(self, |acc, f| f(acc))
+    }
+}
+impl<T> PipeSeq for T {}
@@ -0,0 +1,193 @@
+//! Pipe stages that serialize a value to JSON with serde, or deserialize one from it.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Extension trait for serializing pipeline values to JSON.
+pub trait PipeSerialize: Serialize {
+    /// Serializes `self` to JSON bytes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `self`'s `Serialize` impl fails, or if it contains a map whose
+    /// keys are not strings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeSerialize;
+    /// assert_eq!([1, 2, 3].pipe_serialize().unwrap(), b"[1,2,3]");
+    /// ```
+    #[inline]
+    fn pipe_serialize(&self) -> Result<Vec<u8>, serde_json::Error> {
+        serde_json::to_vec(self)
+    }
+
+    /// Serializes `self` to a JSON string.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`pipe_serialize`](PipeSerialize::pipe_serialize).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeSerialize;
+    /// assert_eq!(("id", 7).pipe_serialize_to_string().unwrap(), r#"["id",7]"#);
+    /// ```
+    #[inline]
+    fn pipe_serialize_to_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Writes `self` to `writer` as JSON, then returns it, so the value can be
+    /// recorded mid-pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Fails if serialization fails or `writer` returns an I/O error; the
+    /// value is dropped in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, PipeSerialize};
+    /// fn total(prices: Vec<u32>) -> u32 { prices.into_iter().sum() }
+    ///
+    /// let mut journal = Vec::new();
+    /// let sum = vec![3, 4].tap_serialize(&mut journal).unwrap().pipe(total)();
+    /// assert_eq!(sum, 7);
+    /// assert_eq!(journal, b"[3,4]");
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    fn tap_serialize<W: std::io::Write + ?Sized>(self, writer: &mut W) -> Result<Self, serde_json::Error>
+    where
+        Self: Sized,
+    {
+        serde_json::to_writer(writer, &self)?;
+        Ok(self)
+    }
+}
+
+impl<T: Serialize + ?Sized> PipeSerialize for T {}
+
+/// Extension trait for deserializing pipeline values from JSON input.
+pub trait PipeDeserialize: AsRef<[u8]> {
+    /// Deserializes a `T` from the JSON bytes in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `self` is not valid JSON or does not match `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, PipeDeserialize};
+    /// fn mean(xs: Vec<f64>) -> f64 { xs.iter().sum::<f64>() / xs.len() as f64 }
+    ///
+    /// let raw = b"[1.0, 2.0, 6.0]";
+    /// assert_eq!(raw.pipe_deserialize::<Vec<f64>>().unwrap().pipe(mean)(), 3.0);
+    /// ```
+    #[inline]
+    fn pipe_deserialize<'de, T: Deserialize<'de>>(&'de self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(self.as_ref())
+    }
+
+    /// Deserializes a `T` from the JSON text in `self`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `self` is not valid JSON or does not match `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeDeserialize;
+    /// let (name, port): (&str, u16) = r#"["db", 5432]"#.pipe_deserialize_json().unwrap();
+    /// assert_eq!((name, port), ("db", 5432));
+    /// ```
+    #[inline]
+    fn pipe_deserialize_json<'de, T: Deserialize<'de>>(&'de self) -> Result<T, serde_json::Error>
+    where
+        Self: AsRef<str>,
+    {
+        serde_json::from_str(AsRef::<str>::as_ref(self))
+    }
+}
+
+impl<B: AsRef<[u8]> + ?Sized> PipeDeserialize for B {}
+
+/// Wraps any serde [`Deserializer`], so a pipeline can start from formats
+/// other than JSON.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::{DeserializerPipe, Pipe};
+/// use serde::de::IntoDeserializer;
+/// use serde::de::value::Error;
+///
+/// fn scale(x: u32, k: u32) -> u32 { x * k }
+///
+/// let input: serde::de::value::U32Deserializer<Error> = 7u32.into_deserializer();
+/// let out = DeserializerPipe(input).pipe_deserialize::<u32>().unwrap().pipe(scale)(3);
+/// assert_eq!(out, 21);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DeserializerPipe<D>(pub D);
+
+impl<'de, D: Deserializer<'de>> DeserializerPipe<D> {
+    /// Deserializes a `T` with the wrapped deserializer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the deserializer's error if the input does not match `T`.
+    #[inline]
+    pub fn pipe_deserialize<T: Deserialize<'de>>(self) -> Result<T, D::Error> {
+        T::deserialize(self.0)
+    }
+}
+
+/// Extension trait for recording the changes made to a value as JSON Patch
+/// (RFC 6902) documents.
+#[cfg(feature = "json-patch")]
+pub trait TapJsonPatch: Serialize + Sized {
+    /// Serializes `before` and `self` to JSON and appends the patch that turns
+    /// the first into the second to `patch_log`, then returns `self`.
+    /// Applying the logged patches in order to `before` replays the changes.
+    ///
+    /// # Errors
+    ///
+    /// Fails if either value cannot be serialized; nothing is logged then.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, TapJsonPatch};
+    /// use serde_json::json;
+    ///
+    /// fn rename(mut v: serde_json::Value, name: &str) -> serde_json::Value { v["name"] = name.into(); v }
+    ///
+    /// let before = json!({"name": "draft", "tags": []});
+    /// let mut log = Vec::new();
+    /// let after = before.clone().pipe(rename)("final").tap_with_json_patch(&before, &mut log).unwrap();
+    /// assert_eq!(after["name"], "final");
+    /// assert_eq!(serde_json::to_value(&log[0]).unwrap(), json!([{"op": "replace", "path": "/name", "value": "final"}]));
+    /// ```
+    #[inline]
+    fn tap_with_json_patch<B: Serialize + ?Sized>(
+        self,
+        before: &B,
+        patch_log: &mut Vec<json_patch::Patch>,
+    ) -> Result<Self, serde_json::Error> {
+        let diff = json_patch::diff(&serde_json::to_value(before)?, &serde_json::to_value(&self)?);
+        patch_log.push(diff);
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "json-patch")]
+impl<T: Serialize> TapJsonPatch for T {}
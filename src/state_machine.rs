@@ -0,0 +1,127 @@
+//! A state machine that carries a value through its transitions.
+
+use crate::{Curry, CurryWith, Imm, Own};
+
+/// A current state `S` together with a value `T` that transitions update.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::StateMachine;
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Door { Open, Closed }
+///
+/// fn toggle(state: Door, moves: u32, _event: (), by: u32) -> (Door, u32) {
+///     match state { Door::Open => (Door::Closed, moves + by), Door::Closed => (Door::Open, moves + by) }
+/// }
+///
+/// let door = StateMachine::new(Door::Closed, 0)
+///     .pipe_transition((), toggle)(1)
+///     .pipe_transition((), toggle)(1);
+/// assert_eq!((*door.current_state(), *door.current_value()), (Door::Closed, 2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateMachine<S, T> {
+    state: S,
+    value: T,
+}
+
+impl<S, T> StateMachine<S, T> {
+    /// Creates a machine in `initial_state`, holding `initial_value`.
+    #[inline]
+    pub const fn new(initial_state: S, initial_value: T) -> Self {
+        StateMachine { state: initial_state, value: initial_value }
+    }
+
+    /// Returns the current state.
+    #[inline]
+    pub const fn current_state(&self) -> &S {
+        &self.state
+    }
+
+    /// Returns the current value.
+    #[inline]
+    pub const fn current_value(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the current state and value, consuming the machine.
+    #[inline]
+    pub fn into_parts(self) -> (S, T) {
+        (self.state, self.value)
+    }
+
+    /// Returns a closure over the remaining arguments that calls
+    /// `f(state, value, event, args..)` and moves the machine to the
+    /// `(new_state, new_value)` it returns.
+    #[inline(always)]
+    pub fn pipe_transition<const ARITY: usize, E, F, Params>(self, event: E, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, TransitionMark, Self, E, (S, T)>,
+    {
+        f.curry_with(self, event)
+    }
+
+    /// Returns a closure over the remaining arguments that calls
+    /// `f(&state, &value, args..)` for a side effect and returns the machine.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::StateMachine;
+    /// fn log(state: &&str, count: &u32, out: &mut Vec<String>) { out.push(format!("{state}={count}")) }
+    ///
+    /// let mut out = Vec::new();
+    /// StateMachine::new("idle", 0).tap_observe(log)(&mut out);
+    /// assert_eq!(out, ["idle=0"]);
+    /// ```
+    #[inline(always)]
+    pub fn tap_observe<const ARITY: usize, R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, TransitionMark, Self, R>,
+    {
+        f.curry(self)
+    }
+}
+
+#[doc(hidden)]
+/// Marker type: `pipe_transition` / `tap_observe` semantics (step or observe a [`StateMachine`]).
+pub struct TransitionMark;
+
+macro_rules! impl_state_machine_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, CurryWith, Imm, Own, StateMachine, TransitionMark};
+
+            // --- Transition ---
+            #[cfg(feature = $feat)]
+            impl<F, S, T, E, $($Params),*> CurryWith<$N, $TupleType, Own, TransitionMark, StateMachine<S, T>, E, (S, T)> for F
+            where F: FnOnce(S, T, E, $($Params),*) -> (S, T) {
+                type Curry = impl FnOnce($($Params),*) -> StateMachine<S, T>;
+                #[inline(always)] fn curry_with(self, arg0: StateMachine<S, T>, event: E) -> Self::Curry {
+                    move |$($Params),*| {
+                        let (state, value) = self(arg0.state, arg0.value, event, $($Params),*);
+                        StateMachine { state, value }
+                    }
+                }
+            }
+
+            // --- Observe ---
+            #[cfg(feature = $feat)]
+            impl<F, S, T, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, TransitionMark, StateMachine<S, T>, R> for F
+            where F: FnOnce(&S, &T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> StateMachine<S, T>;
+                #[inline(always)] fn curry(self, arg0: StateMachine<S, T>) -> Self::Curry {
+                    |$($Params),*| { self(&arg0.state, &arg0.value, $($Params),*); arg0 }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_state_machine_arity);
+}
@@ -0,0 +1,65 @@
+//! Pipe stages that map an async function over a stream.
+
+use futures::Stream;
+
+use crate::{Curry, Own};
+
+/// Extension trait for mapping an async multi-argument function over a stream.
+pub trait PipeAsyncStream<const ARITY: usize>: Stream + Sized {
+    /// Returns a closure over the remaining arguments that lazily maps
+    /// `f(item, args..)` over the items of `self`, awaiting each future in
+    /// turn. The arguments are cloned for each item.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeAsyncStream;
+    /// use futures::{executor::block_on, stream, StreamExt};
+    ///
+    /// async fn lookup(id: u32, prefix: &str) -> String { format!("{prefix}-{id}") }
+    ///
+    /// let names = stream::iter([1, 2]).pipe_async_stream(lookup)("user");
+    /// assert_eq!(block_on(names.collect::<Vec<_>>()), ["user-1", "user-2"]);
+    /// ```
+    #[inline(always)]
+    fn pipe_async_stream<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, AsyncStreamMark, Self, R>,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, S: Stream> PipeAsyncStream<ARITY> for S {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_async_stream` semantics (map an async function over each item).
+pub struct AsyncStreamMark;
+
+macro_rules! impl_stream_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{AsyncStreamMark, Curry, Own};
+            #[cfg(feature = $feat)]
+            use core::future::Future;
+            #[cfg(feature = $feat)]
+            use futures::{Stream, StreamExt};
+
+            // --- Async Stream ---
+            #[cfg(feature = $feat)]
+            impl<F, S: Stream, $($Params: Clone,)* Fut, R> Curry<$N, $TupleType, Own, Own, AsyncStreamMark, S, R> for F
+            where F: FnMut(S::Item, $($Params),*) -> Fut, Fut: Future<Output = R> {
+                type Curry = impl FnOnce($($Params),*) -> impl Stream<Item = R>;
+                #[inline(always)] fn curry(mut self, arg0: S) -> Self::Curry {
+                    move |$($Params),*| arg0.then(move |item| self(item, $($Params.clone()),*))
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_stream_arity);
+}
@@ -0,0 +1,182 @@
+//! Taps that complete immediately: there are no deferred arguments, so the
+//! original value is returned directly instead of through a closure.
+
+use core::fmt::{Debug, Write};
+
+/// Extension trait for side effects that take no deferred arguments.
+pub trait TapValue {
+    /// Writes `"{label}: {self:?}"` as a line to `writer`, then returns `self`.
+    /// Write errors are ignored. Unlike printing, the output can be captured
+    /// and asserted on, e.g. by writing into a `String`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, TapValue};
+    /// fn scale(x: i32, k: i32) -> i32 { x * k }
+    ///
+    /// let mut log = String::new();
+    /// let x = 2
+    ///     .tap_side_channel(&mut log, "start")
+    ///     .pipe(scale)(10)
+    ///     .tap_side_channel(&mut log, "scaled");
+    ///
+    /// assert_eq!(x, 20);
+    /// assert_eq!(log, "start: 2\nscaled: 20\n");
+    /// ```
+    #[inline(always)]
+    fn tap_side_channel<W: Write + ?Sized>(self, writer: &mut W, label: &str) -> Self
+    where
+        Self: Debug + Sized,
+    {
+        let _ = writeln!(writer, "{label}: {self:?}");
+        self
+    }
+
+    /// Calls `diff_fn(old, &self)` with the value seen by the previous call, if
+    /// there was one, then stores a clone of `self` in `prev` and returns `self`.
+    /// Sharing one `prev` between calls turns this into a change detector, e.g.
+    /// to log only when the value differs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapValue;
+    /// let mut prev = None;
+    /// let mut changes = Vec::new();
+    ///
+    /// for x in [1, 1, 3] {
+    ///     x.tap_with_diff(&mut prev, |old: &i32, new: &i32| {
+    ///         if old != new { changes.push((*old, *new)); }
+    ///     });
+    /// }
+    ///
+    /// assert_eq!(changes, [(1, 3)]);
+    /// assert_eq!(prev, Some(3));
+    /// ```
+    #[inline(always)]
+    fn tap_with_diff<D>(self, prev: &mut Option<Self>, diff_fn: D) -> Self
+    where
+        D: FnOnce(&Self, &Self),
+        Self: Clone + Sized,
+    {
+        if let Some(old) = prev {
+            diff_fn(old, &self);
+        }
+        *prev = Some(self.clone());
+        self
+    }
+
+    /// Like [`tap_with_diff`](TapValue::tap_with_diff), but `diff_fn` may modify
+    /// the new value before it is stored in `prev` and returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapValue;
+    /// // Never let a reading drop by more than 10 from the previous one.
+    /// let clamp = |old: &i32, new: &mut i32| *new = (*new).max(old - 10);
+    ///
+    /// let mut prev = None;
+    /// let readings = [50, 20, 15].map(|x| x.tap_with_diff_mut(&mut prev, clamp));
+    /// assert_eq!(readings, [50, 40, 30]);
+    /// ```
+    #[inline(always)]
+    fn tap_with_diff_mut<D>(mut self, prev: &mut Option<Self>, diff_fn: D) -> Self
+    where
+        D: FnOnce(&Self, &mut Self),
+        Self: Clone + Sized,
+    {
+        if let Some(old) = prev {
+            diff_fn(old, &mut self);
+        }
+        *prev = Some(self.clone());
+        self
+    }
+
+    /// Asserts that `pred(&self)` holds, then returns `self`. Unlike
+    /// `debug_assert!`, the check also runs in release builds.
+    ///
+    /// # Panics
+    ///
+    /// Panics with `invariant '{name}' violated` if `pred` returns `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapValue;
+    /// let percent = 42
+    ///     .tap_with_invariant("in_range", |v| (0..=100).contains(v))
+    ///     .tap_with_invariant("not_max", |v| *v != 100);
+    /// assert_eq!(percent, 42);
+    /// ```
+    #[inline(always)]
+    #[track_caller]
+    fn tap_with_invariant<P>(self, name: &str, pred: P) -> Self
+    where
+        P: FnOnce(&Self) -> bool,
+        Self: Sized,
+    {
+        assert!(pred(&self), "invariant '{name}' violated");
+        self
+    }
+
+    /// Stores a clone of `self` in `snapshot`, replacing any earlier one, then
+    /// returns `self`. Useful in tests to check an intermediate stage.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, Snapshot, TapValue};
+    /// fn parse(s: &str) -> Vec<u32> { s.split(',').map(|x| x.parse().unwrap()).collect() }
+    /// fn total(v: Vec<u32>) -> u32 { v.iter().sum() }
+    ///
+    /// let mut parsed = Snapshot::new();
+    /// let sum = "1,2,3".pipe(parse)().tap_snapshot(&mut parsed).pipe(total)();
+    ///
+    /// assert_eq!(sum, 6);
+    /// assert_eq!(parsed.get(), Some(&vec![1, 2, 3]));
+    /// ```
+    #[inline(always)]
+    fn tap_snapshot(self, snapshot: &mut Snapshot<Self>) -> Self
+    where
+        Self: Clone + Sized,
+    {
+        snapshot.value = Some(self.clone());
+        self
+    }
+}
+impl<T> TapValue for T {}
+
+/// A copy of a pipeline value, taken by [`tap_snapshot`](TapValue::tap_snapshot).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Snapshot<T> {
+    value: Option<T>,
+}
+
+impl<T> Snapshot<T> {
+    /// Creates an empty snapshot.
+    #[inline]
+    pub const fn new() -> Self {
+        Snapshot { value: None }
+    }
+
+    /// Returns the captured value, or `None` if nothing was captured yet.
+    #[inline]
+    pub const fn get(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// Returns the captured value, consuming the snapshot.
+    #[inline]
+    pub fn into_inner(self) -> Option<T> {
+        self.value
+    }
+}
+
+impl<T> Default for Snapshot<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,164 @@
+//! Thread-local tenant context: tell multi-tenant pipe stages which tenant
+//! they serve without passing it through every function.
+
+use core::cell::RefCell;
+use core::ops::{BitOr, BitOrAssign};
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::string::String;
+
+use crate::scoped::with_scoped;
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// A single permission a tenant may hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum Permission {
+    /// May read data.
+    Read = 1 << 0,
+    /// May create or modify data.
+    Write = 1 << 1,
+    /// May delete data.
+    Delete = 1 << 2,
+    /// May manage the tenant itself.
+    Admin = 1 << 3,
+}
+
+/// A set of [`Permission`]s, built with `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Permissions(u8);
+
+impl Permissions {
+    /// The empty set.
+    pub const NONE: Permissions = Permissions(0);
+
+    /// Returns `true` if the set holds `permission`.
+    #[inline]
+    pub const fn contains(self, permission: Permission) -> bool {
+        self.0 & permission as u8 != 0
+    }
+}
+
+impl From<Permission> for Permissions {
+    #[inline]
+    fn from(permission: Permission) -> Self {
+        Permissions(permission as u8)
+    }
+}
+
+impl<P: Into<Permissions>> BitOr<P> for Permissions {
+    type Output = Permissions;
+    #[inline]
+    fn bitor(self, rhs: P) -> Permissions {
+        Permissions(self.0 | rhs.into().0)
+    }
+}
+
+impl<P: Into<Permissions>> BitOr<P> for Permission {
+    type Output = Permissions;
+    #[inline]
+    fn bitor(self, rhs: P) -> Permissions {
+        Permissions::from(self) | rhs
+    }
+}
+
+impl<P: Into<Permissions>> BitOrAssign<P> for Permissions {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: P) {
+        *self = *self | rhs;
+    }
+}
+
+/// Per-resource limits of a tenant, e.g. `"requests_per_minute" => 600`.
+pub type QuotaMap = HashMap<String, u64>;
+
+/// The tenant a stage runs on behalf of.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TenantContext {
+    /// Identifies the tenant.
+    pub tenant_id: u64,
+    /// What the tenant may do.
+    pub permissions: Permissions,
+    /// The tenant's limits.
+    pub quotas: QuotaMap,
+}
+
+impl TenantContext {
+    /// Creates a context for `tenant_id` with no permissions and no quotas.
+    pub fn new(tenant_id: u64) -> Self {
+        TenantContext { tenant_id, permissions: Permissions::NONE, quotas: QuotaMap::new() }
+    }
+
+    /// Returns the quota set for `resource`, if any.
+    pub fn quota(&self, resource: &str) -> Option<u64> {
+        self.quotas.get(resource).copied()
+    }
+}
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<Rc<TenantContext>>> = const { RefCell::new(None) };
+}
+
+/// Calls `f` with the tenant of the innermost stage started by
+/// [`pipe_with_tenant`](PipeTenant::pipe_with_tenant) on this thread, or
+/// returns `None` if there is none. `f` may start stages for other tenants.
+pub fn with_current<R>(f: impl FnOnce(&TenantContext) -> R) -> Option<R> {
+    CURRENT.with_borrow(Option::clone).map(|ctx| f(&ctx))
+}
+
+/// Returns a copy of the tenant being served on this thread, if any.
+pub fn current() -> Option<TenantContext> {
+    with_current(TenantContext::clone)
+}
+
+/// Returns the id of the tenant being served on this thread, if any.
+pub fn current_id() -> Option<u64> {
+    with_current(|ctx| ctx.tenant_id)
+}
+
+/// Extension trait for running pipe stages on behalf of a tenant.
+pub trait PipeTenant<const ARITY: usize> {
+    /// Like `pipe`, but makes a copy of `ctx` the current tenant while `f` runs.
+    /// When `f` returns or unwinds, the tenant of the enclosing stage, if any,
+    /// is current again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::tenant::{self, Permission, PipeTenant, TenantContext};
+    /// fn delete(key: &str) -> Result<String, &'static str> {
+    ///     tenant::with_current(|t| {
+    ///         if t.permissions.contains(Permission::Delete) { Ok(format!("{}/{key}", t.tenant_id)) } else { Err("denied") }
+    ///     })
+    ///     .unwrap()
+    /// }
+    ///
+    /// let admin = TenantContext { permissions: Permission::Read | Permission::Delete, ..TenantContext::new(7) };
+    /// let reader = TenantContext { permissions: Permission::Read.into(), ..TenantContext::new(8) };
+    ///
+    /// assert_eq!("a".pipe_with_tenant(&admin, delete)(), Ok("7/a".to_string()));
+    /// assert_eq!("a".pipe_with_tenant(&reader, delete)(), Err("denied"));
+    /// assert_eq!(tenant::current_id(), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_tenant<'c, R, F, Params>(self, ctx: &'c TenantContext, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, WithTenant<'c>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, WithTenant(ctx))
+    }
+}
+impl<const ARITY: usize, T> PipeTenant<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call with the tenant context set.
+pub struct WithTenant<'c>(&'c TenantContext);
+
+impl<A0, R> Around<A0, R> for WithTenant<'_> {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        with_scoped(&CURRENT, Rc::new(self.0.clone()), || call(arg0))
+    }
+}
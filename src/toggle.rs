@@ -0,0 +1,30 @@
+//! Compile-time feature toggles for pipe stages.
+
+/// Selects one of two functions by a Cargo feature of the *calling* crate, for
+/// use with `pipe`. Evaluates to `$enabled` if `feature = $flag` is set and to
+/// `$disabled` otherwise; the unselected function is not compiled into the
+/// expression, so the two may have different types.
+///
+/// This is the compile-time counterpart to
+/// [`pipe_with_feature_toggle`](crate::PipeWith::pipe_with_feature_toggle).
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::{pipe_with_cfg_feature, Pipe};
+/// fn traced(x: i32, y: i32) -> i32 { println!("{x} + {y}"); x + y }
+/// fn plain(x: i32, y: i32) -> i32 { x + y }
+///
+/// let sum = 1.pipe(pipe_with_cfg_feature!("tracing", traced, plain))(2);
+/// assert_eq!(sum, 3);
+/// ```
+#[macro_export]
+macro_rules! pipe_with_cfg_feature {
+    ($flag:literal, $enabled:expr, $disabled:expr $(,)?) => {{
+        #[cfg(feature = $flag)]
+        let selected = $enabled;
+        #[cfg(not(feature = $flag))]
+        let selected = $disabled;
+        selected
+    }};
+}
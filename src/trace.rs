@@ -0,0 +1,120 @@
+//! Distributed tracing: run pipe stages inside child spans of a W3C trace
+//! context, propagated to nested stages through thread-local storage.
+
+use core::cell::RefCell;
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::scoped::with_scoped;
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// A W3C trace context: the trace a span belongs to and its position in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TraceContext {
+    /// Identifies the whole trace; shared by every span in it.
+    pub trace_id: u128,
+    /// Identifies this span.
+    pub span_id: u64,
+    /// The span this one was created from, if any.
+    pub parent_span_id: Option<u64>,
+}
+
+static NEXT_SPAN_ID: AtomicU64 = AtomicU64::new(1);
+
+impl TraceContext {
+    /// Creates the root span of a trace.
+    pub const fn new(trace_id: u128, span_id: u64) -> Self {
+        TraceContext { trace_id, span_id, parent_span_id: None }
+    }
+
+    /// Creates a child span in the same trace with a fresh, process-unique span id.
+    pub fn child(&self) -> Self {
+        TraceContext {
+            trace_id: self.trace_id,
+            span_id: NEXT_SPAN_ID.fetch_add(1, Ordering::Relaxed),
+            parent_span_id: Some(self.span_id),
+        }
+    }
+
+    /// Parses a `traceparent` header (`00-<trace-id>-<span-id>-<flags>`).
+    /// The parent span id is not part of the header and is left unset.
+    pub fn from_traceparent(header: &str) -> Option<Self> {
+        let mut parts = header.trim().split('-');
+        let (version, trace, span, flags) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() || version != "00" || trace.len() != 32 || span.len() != 16 || flags.len() != 2 {
+            return None;
+        }
+        let trace_id = u128::from_str_radix(trace, 16).ok()?;
+        let span_id = u64::from_str_radix(span, 16).ok()?;
+        u8::from_str_radix(flags, 16).ok()?;
+        (trace_id != 0 && span_id != 0).then_some(TraceContext::new(trace_id, span_id))
+    }
+}
+
+/// Formats the context as a `traceparent` header value, with the sampled flag set.
+impl fmt::Display for TraceContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "00-{:032x}-{:016x}-01", self.trace_id, self.span_id)
+    }
+}
+
+std::thread_local! {
+    static CURRENT: RefCell<Option<(TraceContext, &'static str)>> = const { RefCell::new(None) };
+}
+
+/// Returns the span of the innermost traced stage running on this thread.
+pub fn current_context() -> Option<TraceContext> {
+    CURRENT.with_borrow(|current| current.map(|(ctx, _)| ctx))
+}
+
+/// Returns the operation name of the innermost traced stage running on this thread.
+pub fn current_operation() -> Option<&'static str> {
+    CURRENT.with_borrow(|current| current.map(|(_, op)| op))
+}
+
+/// Extension trait for running pipe stages inside trace spans.
+pub trait PipeTrace<const ARITY: usize> {
+    /// Like `pipe`, but runs `f` inside a child span of `ctx` named `operation`.
+    /// While `f` runs, [`current_context`] returns the child span, so nested
+    /// stages can create grandchildren of it; the previous span is restored
+    /// afterwards, even if `f` panics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::trace::{self, PipeTrace, TraceContext};
+    /// fn handle(req: &str, suffix: &str) -> String {
+    ///     let span = trace::current_context().unwrap();
+    ///     format!("{req}{suffix} [{span}]")
+    /// }
+    ///
+    /// let root = TraceContext::new(0xabc, 1);
+    /// let out = "GET /".pipe_with_distributed_trace(&root, "handle", handle)("?q");
+    /// assert!(out.starts_with("GET /?q [00-00000000000000000000000000000abc-"));
+    /// assert_eq!(trace::current_context(), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_distributed_trace<'c, R, F, Params>(self, ctx: &'c TraceContext, operation: &'static str, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, Span<'c>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, Span { parent: ctx, operation })
+    }
+}
+impl<const ARITY: usize, T> PipeTrace<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call as the current span, then restores the previous one.
+pub struct Span<'c> {
+    parent: &'c TraceContext,
+    operation: &'static str,
+}
+
+impl<A0, R> Around<A0, R> for Span<'_> {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        with_scoped(&CURRENT, (self.parent.child(), self.operation), || call(arg0))
+    }
+}
@@ -0,0 +1,167 @@
+//! Pipe stages recorded with their inputs, so a pipeline can be audited and replayed.
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{CurryWith, Own};
+
+/// A recorded stage: its name, its input and output, and the transform it applied.
+struct Stage<T> {
+    name: String,
+    before: T,
+    after: T,
+    apply: Box<dyn Fn(T) -> T>,
+}
+
+/// The stages run through [`pipe_logged`](PipeLogged::pipe_logged), in order.
+///
+/// Each stage is stored with the value it received and the transform it
+/// applied, including its extra arguments, so the log can be replayed.
+pub struct TransformLog<T> {
+    stages: Vec<Stage<T>>,
+}
+
+impl<T> TransformLog<T> {
+    /// Creates an empty log.
+    #[inline]
+    pub const fn new() -> Self {
+        TransformLog { stages: Vec::new() }
+    }
+
+    /// Returns the number of recorded stages.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns `true` if no stage has been recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Returns each stage's name and the value it received, in order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &T)> {
+        self.stages.iter().map(|stage| (stage.name.as_str(), &stage.before))
+    }
+
+    /// Returns the value stage `n` received and the value it produced, or
+    /// `None` if fewer than `n + 1` stages were recorded.
+    pub fn diff_at(&self, n: usize) -> Option<(&T, &T)> {
+        self.stages.get(n).map(|stage| (&stage.before, &stage.after))
+    }
+
+    /// Applies every recorded transform to `initial`, in order.
+    pub fn replay(&self, initial: T) -> T {
+        self.stages.iter().fold(initial, |value, stage| (stage.apply)(value))
+    }
+}
+
+impl<T> Default for TransformLog<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TransformLog<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.entries()).finish()
+    }
+}
+
+/// Extension trait for recording pipe stages in a [`TransformLog`].
+pub trait PipeLogged<const ARITY: usize> {
+    /// Like `pipe`, but records `name`, a clone of `self`, the transform, and
+    /// a clone of its result in `log` before calling `f(self, args..)`. `f`
+    /// must return `Self`, and it and the remaining arguments are kept for
+    /// [`replay`](TransformLog::replay).
+    ///
+    /// Because the log keeps them, `f` and the remaining arguments must be
+    /// `'static`: a stage cannot take a borrowed argument such as a
+    /// non-`'static` `&str`. Pass an owned value instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipeLogged, TransformLog};
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    /// fn mul(x: i32, y: i32) -> i32 { x * y }
+    ///
+    /// let mut log = TransformLog::new();
+    /// let x = 1.pipe_logged("add", &mut log, add)(2);
+    /// let x = x.pipe_logged("mul", &mut log, mul)(4);
+    ///
+    /// assert_eq!(x, 12);
+    /// assert_eq!(log.entries().collect::<Vec<_>>(), [("add", &1), ("mul", &3)]);
+    /// assert_eq!(log.diff_at(1), Some((&3, &12)));
+    /// assert_eq!(log.replay(2), 16);
+    /// ```
+    #[inline(always)]
+    fn pipe_logged<'n, 'l, F, Params>(self, name: &'n str, log: &'l mut TransformLog<Self>, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, LoggedMark, Self, LogTarget<'n, 'l, Self>, Self>,
+        Self: Sized,
+    {
+        f.curry_with(self, LogTarget { name, log })
+    }
+}
+impl<const ARITY: usize, T> PipeLogged<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_logged` semantics (record the stage, then pipe).
+pub struct LoggedMark;
+
+any_arity! {
+    allow_dead_code;
+
+    #[doc(hidden)]
+    /// Internal: the name of the stage to record and the log to record it in.
+    pub struct LogTarget<'n, 'l, T> {
+        name: &'n str,
+        log: &'l mut TransformLog<T>,
+    }
+}
+
+any_arity! {
+    impl<T: Clone> LogTarget<'_, '_, T> {
+        #[inline(always)]
+        fn record(self, before: T, apply: Box<dyn Fn(T) -> T>) -> T {
+            let after = apply(before.clone());
+            self.log.stages.push(Stage { name: self.name.into(), before, after: after.clone(), apply });
+            after
+        }
+    }
+}
+
+macro_rules! impl_transform_log_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryWith, LogTarget, LoggedMark, Own};
+            #[cfg(feature = $feat)]
+            use alloc::boxed::Box;
+
+            #[cfg(feature = $feat)]
+            impl<'n, 'l, F, A0, $($Params),*> CurryWith<$N, $TupleType, Own, LoggedMark, A0, LogTarget<'n, 'l, A0>, A0> for F
+            where
+                F: Fn(A0, $($Params),*) -> A0 + 'static,
+                A0: Clone,
+                $($Params: Clone + 'static,)*
+            {
+                type Curry = impl FnOnce($($Params),*) -> A0;
+                #[inline(always)] fn curry_with(self, arg0: A0, target: LogTarget<'n, 'l, A0>) -> Self::Curry {
+                    move |$($Params),*| target.record(arg0, Box::new(move |value| self(value, $($Params.clone()),*)))
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_transform_log_arity);
+}
@@ -0,0 +1,103 @@
+//! Pipe stages that map a multi-argument function over a collection in parallel with rayon.
+
+use crate::{Curry, Imm, Mut, Own};
+
+/// Extension trait for applying a function to every element of a collection
+/// on rayon's thread pool.
+pub trait PipeTraverse<const ARITY: usize> {
+    /// Calls `f(element, args..)` for every element of `self` in parallel,
+    /// returning the results in input order. The remaining arguments are
+    /// shared across threads and cloned for each call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeTraverse;
+    /// fn scale(x: &i32, k: i32) -> i32 { x * k }
+    ///
+    /// let values = [1, 2, 3, 4];
+    /// assert_eq!(values.pipe_traverse(scale)(10), [10, 20, 30, 40]);
+    /// ```
+    #[inline(always)]
+    fn pipe_traverse<'a, R, F, Params>(&'a self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, TraverseMark, &'a Self, R>,
+    {
+        f.curry(self)
+    }
+
+    /// Calls `f(&mut element, args..)` for every element of `self` in
+    /// parallel. The remaining arguments are shared across threads and cloned
+    /// for each call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeTraverse;
+    /// fn clamp(x: &mut i32, lo: i32, hi: i32) { *x = (*x).clamp(lo, hi); }
+    ///
+    /// let mut values = vec![-5, 3, 12];
+    /// values.pipe_traverse_mut(clamp)(0, 10);
+    /// assert_eq!(values, [0, 3, 10]);
+    /// ```
+    #[inline(always)]
+    fn pipe_traverse_mut<'a, F, Params>(&'a mut self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Mut, Own, TraverseMark, &'a mut Self, ()>,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T: ?Sized> PipeTraverse<ARITY> for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_traverse` semantics (map every element in parallel).
+pub struct TraverseMark;
+
+macro_rules! impl_traverse_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, Imm, Mut, Own, TraverseMark};
+            #[cfg(feature = $feat)]
+            use rayon::iter::{IntoParallelIterator, ParallelIterator};
+            #[cfg(feature = $feat)]
+            use std::vec::Vec;
+
+            // --- Traverse ---
+            #[cfg(feature = $feat)]
+            impl<'a, F, C: ?Sized, T: 'a, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, TraverseMark, &'a C, R> for F
+            where
+                &'a C: IntoParallelIterator<Item = &'a T>,
+                F: Fn(&'a T, $($Params),*) -> R + Sync,
+                $($Params: Clone + Sync,)*
+                R: Send,
+            {
+                type Curry = impl FnOnce($($Params),*) -> Vec<R>;
+                #[inline(always)] fn curry(self, arg0: &'a C) -> Self::Curry {
+                    move |$($Params),*| arg0.into_par_iter().map(|x| self(x, $($Params.clone()),*)).collect()
+                }
+            }
+
+            // --- Traverse Mut ---
+            #[cfg(feature = $feat)]
+            impl<'a, F, C: ?Sized, T: 'a, $($Params),*> Curry<$N, $TupleType, Mut, Own, TraverseMark, &'a mut C, ()> for F
+            where
+                &'a mut C: IntoParallelIterator<Item = &'a mut T>,
+                F: Fn(&'a mut T, $($Params),*) + Sync,
+                $($Params: Clone + Sync,)*
+            {
+                type Curry = impl FnOnce($($Params),*);
+                #[inline(always)] fn curry(self, arg0: &'a mut C) -> Self::Curry {
+                    move |$($Params),*| arg0.into_par_iter().for_each(|x| self(x, $($Params.clone()),*))
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_traverse_arity);
+}
@@ -0,0 +1,73 @@
+//! Names for the closures returned by `pipe` and `tap`.
+//!
+//! The closure returned by `pipe` or `tap` is an opaque type, so it cannot be
+//! written out directly, e.g. to store it in a struct field. The aliases in this
+//! module name it from the function type `F`, the pipeline value type `A0`, the
+//! tuple of remaining argument types `Args`, and the function's return type `R`:
+//!
+//! * [`CurryOwn<F, A0, Args, R>`]: the closure returned by `pipe`, where `F` takes `A0` by value.
+//! * [`TapCurryImm<F, A0, Args, R>`]: the closure returned by `tap`, where `F` takes `&A0`.
+//! * [`TapCurryMut<F, A0, Args, R>`]: the closure returned by `tap`, where `F` takes `&mut A0`.
+//!
+//! `R` defaults to `()` for the tap aliases. Since a function's own type can only be
+//! named for function pointers, structs that store a curried function item are
+//! usually generic over `F`, and built by a function that names `F`, since it
+//! cannot be inferred back from the closure:
+//!
+//! ```rust
+//! # use pipei::Pipe;
+//! use pipei::type_aliases::CurryOwn;
+//!
+//! struct Scaler<F: FnOnce(i32, i32) -> i32> {
+//!     scale: CurryOwn<F, i32, (i32,), i32>,
+//! }
+//!
+//! impl<F: FnOnce(i32, i32) -> i32> Scaler<F> {
+//!     fn new(factor: i32, f: F) -> Self { Scaler { scale: factor.pipe(f) } }
+//! }
+//!
+//! fn mul(x: i32, y: i32) -> i32 { x * y }
+//!
+//! let s = Scaler::new(3, mul);
+//! assert_eq!((s.scale)(4), 12);
+//! ```
+
+use crate::{Imm, Mut, Own, PipeMark, TapMark};
+
+/// The closure returned by `a0.pipe(f)` for `f: FnOnce(A0, Args..) -> R`.
+pub type CurryOwn<F, A0, Args, R> = <Args as CurryArgs<F, A0, Own, PipeMark, R>>::Curry;
+
+/// The closure returned by `a0.tap(f)` for `f: FnOnce(&A0, Args..) -> R`.
+pub type TapCurryImm<F, A0, Args, R = ()> = <Args as CurryArgs<F, A0, Imm, TapMark, R>>::Curry;
+
+/// The closure returned by `a0.tap(f)` for `f: FnOnce(&mut A0, Args..) -> R`.
+pub type TapCurryMut<F, A0, Args, R = ()> = <Args as CurryArgs<F, A0, Mut, TapMark, R>>::Curry;
+
+#[doc(hidden)]
+/// Internal: recovers the arity of a [`Curry`](crate::Curry) implementation from its argument tuple.
+pub trait CurryArgs<F, A0, AState, MARK, R> {
+    type Curry;
+}
+
+macro_rules! impl_type_alias_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{CurryArgs, Own};
+            #[cfg(feature = $feat)]
+            use crate::Curry;
+
+            #[cfg(feature = $feat)]
+            impl<F, A0, AState, MARK, $($Params,)* R> CurryArgs<F, A0, AState, MARK, R> for $TupleType
+            where F: Curry<$N, $TupleType, AState, Own, MARK, A0, R> {
+                type Curry = F::Curry;
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_type_alias_arity);
+}
@@ -0,0 +1,113 @@
+//! Pipes that pass the name of the pipeline value's type along with it, or
+//! check at compile time which traits it implements.
+
+use core::marker::Unsize;
+
+use crate::{Curry, Own};
+
+/// Extension trait for type-aware pipe stages.
+pub trait PipeTypeName<const ARITY: usize> {
+    /// Curries the name of `Self`, as given by [`core::any::type_name`], and
+    /// `self` into the first two arguments of `f`, returning a closure over
+    /// the remaining arguments.
+    ///
+    /// The name is meant for diagnostics: its exact contents are not
+    /// guaranteed to be stable across compiler versions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeTypeName;
+    /// fn describe<T: std::fmt::Debug>(ty: &'static str, value: T, label: &str) -> String {
+    ///     format!("{label} {ty}: {value:?}")
+    /// }
+    ///
+    /// assert_eq!(7u8.pipe_inspect_type_name(describe)("got"), "got u8: 7");
+    /// ```
+    #[inline(always)]
+    fn pipe_inspect_type_name<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, TypeNameMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeTypeName<ARITY> for T {}
+
+/// Implemented by every type that implements the trait of the trait object
+/// `Marker`, e.g. `T: TraitOf<dyn Debug>` for every `T: Debug + 'static`.
+///
+/// This lets a trait be named as a type parameter. The trait must be dyn
+/// compatible; auto traits such as `dyn Send` work too.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not implement the trait of `{Marker}`",
+    note = "the check comes from a `pipe_assert_type_implements::<{Marker}>()` call"
+)]
+pub trait TraitOf<Marker: ?Sized> {}
+impl<T: Unsize<Marker>, Marker: ?Sized> TraitOf<Marker> for T {}
+
+/// Extension trait for compile-time trait checks inside a pipeline.
+pub trait PipeAssertImplements: Sized {
+    /// Returns `self`, failing to compile unless `Self` implements the trait
+    /// of the trait object `Marker`. The check has no run-time cost.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{Pipe, PipeAssertImplements};
+    /// use std::fmt::Debug;
+    ///
+    /// fn sum_on_worker(v: Vec<i32>) -> i32 { std::thread::spawn(move || v.iter().sum()).join().unwrap() }
+    ///
+    /// let total = vec![1, 2, 3]
+    ///     .pipe_assert_type_implements::<dyn Send>()
+    ///     .pipe_assert_type_implements::<dyn Debug>()
+    ///     .pipe(sum_on_worker)();
+    /// assert_eq!(total, 6);
+    /// ```
+    ///
+    /// A type that does not implement the trait is rejected:
+    ///
+    /// ```rust,compile_fail
+    /// # use pipei::PipeAssertImplements;
+    /// std::rc::Rc::new(1).pipe_assert_type_implements::<dyn Send>();
+    /// ```
+    #[inline(always)]
+    fn pipe_assert_type_implements<Marker: ?Sized>(self) -> Self
+    where
+        Self: TraitOf<Marker>,
+    {
+        self
+    }
+}
+impl<T> PipeAssertImplements for T {}
+
+#[doc(hidden)]
+/// Marker type: `pipe_inspect_type_name` semantics (curry the type name first).
+pub struct TypeNameMark;
+
+macro_rules! impl_type_name_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, Own, TypeNameMark};
+
+            // --- Type Name ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R> Curry<$N, $TupleType, Own, Own, TypeNameMark, A0, R> for F
+            where F: FnOnce(&'static str, A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> R;
+                #[inline(always)] fn curry(self, arg0: A0) -> Self::Curry {
+                    |$($Params),*| self(core::any::type_name::<A0>(), arg0, $($Params),*)
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_type_name_arity);
+}
@@ -0,0 +1,107 @@
+//! A wrapper that tracks, in its type, which stages a value has passed.
+
+use core::marker::PhantomData;
+
+use crate::{Curry, Own, PipeMark, Validate, ValidationError};
+
+/// State of a [`TypeStatePipe`] whose value has not been validated yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Unvalidated;
+
+/// State of a [`TypeStatePipe`] whose value passed [`validate`](TypeStatePipe::validate).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Validated;
+
+/// A value tagged with a state type `S`, so that stages which require a state
+/// can only be called once it has been reached.
+///
+/// The state is a phantom type: `TypeStatePipe<S, T>` has the same layout as `T`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::{TypeStatePipe, Unvalidated, ValidationError};
+/// fn positive(x: &i32) -> Result<(), ValidationError> {
+///     if *x > 0 { Ok(()) } else { Err(ValidationError::new("not positive")) }
+/// }
+/// fn scale(x: i32, k: i32) -> i32 { x * k }
+///
+/// let raw = TypeStatePipe::<Unvalidated, _>::new(21);
+/// assert_eq!(raw.validate(&positive).unwrap().process(scale)(2), 42);
+/// ```
+///
+/// Processing unvalidated data does not compile:
+///
+/// ```rust,compile_fail
+/// # use pipei::{TypeStatePipe, Unvalidated};
+/// fn scale(x: i32, k: i32) -> i32 { x * k }
+///
+/// let raw = TypeStatePipe::<Unvalidated, _>::new(21);
+/// raw.process(scale)(2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TypeStatePipe<S, T> {
+    value: T,
+    state: PhantomData<fn() -> S>,
+}
+
+impl<S, T> TypeStatePipe<S, T> {
+    /// Wraps `value` in state `S`.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        TypeStatePipe { value, state: PhantomData }
+    }
+
+    /// Returns a reference to the wrapped value.
+    #[inline]
+    pub const fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Unwraps the value, discarding its state.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Applies a transition `f` to a new state and, possibly, a new value type.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TypeStatePipe;
+    /// struct Parsed;
+    ///
+    /// let raw = TypeStatePipe::<(), _>::new("42");
+    /// let parsed = raw.pipe_next(|p| TypeStatePipe::<Parsed, _>::new(p.into_inner().parse::<u8>()));
+    /// assert_eq!(parsed.into_inner(), Ok(42));
+    /// ```
+    #[inline(always)]
+    pub fn pipe_next<S2, R, F>(self, f: F) -> TypeStatePipe<S2, R>
+    where
+        F: FnOnce(Self) -> TypeStatePipe<S2, R>,
+    {
+        f(self)
+    }
+}
+
+impl<T> TypeStatePipe<Unvalidated, T> {
+    /// Checks the value against `schema`, moving it to the [`Validated`] state if it passes.
+    #[inline]
+    pub fn validate<V: Validate<T> + ?Sized>(self, schema: &V) -> Result<TypeStatePipe<Validated, T>, ValidationError> {
+        schema.validate(&self.value)?;
+        Ok(TypeStatePipe::new(self.value))
+    }
+}
+
+impl<T> TypeStatePipe<Validated, T> {
+    /// Curries the validated value into `f`, returning a closure over the
+    /// remaining arguments, as `pipe` does.
+    #[inline(always)]
+    pub fn process<const ARITY: usize, R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PipeMark, T, R>,
+    {
+        f.curry(self.value)
+    }
+}
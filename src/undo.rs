@@ -0,0 +1,96 @@
+//! Pipe stages that save their input so they can be undone.
+
+use alloc::vec::Vec;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// The inputs of the stages run through [`pipe_with_undo`](PipeUndo::pipe_with_undo),
+/// most recent last.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoStack<T> {
+    saved: Vec<T>,
+}
+
+impl<T> UndoStack<T> {
+    /// Creates an empty stack.
+    #[inline]
+    pub const fn new() -> Self {
+        UndoStack { saved: Vec::new() }
+    }
+
+    /// Removes and returns the input of the most recent stage, or `None` if
+    /// no stage is left to undo.
+    #[inline]
+    pub fn undo(&mut self) -> Option<T> {
+        self.saved.pop()
+    }
+
+    /// Returns the number of stages that can be undone.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.saved.len()
+    }
+
+    /// Returns `true` if no stage can be undone.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.saved.is_empty()
+    }
+
+    /// Discards all saved inputs.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.saved.clear();
+    }
+}
+
+impl<T> Default for UndoStack<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extension trait for reversible pipe stages.
+pub trait PipeUndo<const ARITY: usize> {
+    /// Like `pipe`, but first pushes a clone of `self` onto `stack`, so that
+    /// [`UndoStack::undo`] returns the value as it was before this stage.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipeUndo, UndoStack};
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// let mut history = UndoStack::new();
+    /// let x = 1.pipe_with_undo(&mut history, add)(2);
+    /// let x = x.pipe_with_undo(&mut history, add)(3);
+    ///
+    /// assert_eq!(x, 6);
+    /// assert_eq!(history.undo(), Some(3));
+    /// assert_eq!(history.undo(), Some(1));
+    /// assert_eq!(history.undo(), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_with_undo<'s, R, F, Params>(self, stack: &'s mut UndoStack<Self>, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, SaveInput<'s, Self>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, SaveInput(stack))
+    }
+}
+impl<const ARITY: usize, T> PipeUndo<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: pushes a clone of the input onto an [`UndoStack`] before the call.
+pub struct SaveInput<'s, T>(&'s mut UndoStack<T>);
+
+impl<A0: Clone, R> Around<A0, R> for SaveInput<'_, A0> {
+    type Output = R;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        self.0.saved.push(arg0.clone());
+        call(arg0)
+    }
+}
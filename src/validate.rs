@@ -0,0 +1,151 @@
+//! Pipe stages that validate their input against a schema first.
+
+use core::fmt;
+use core::ops::{RangeFrom, RangeInclusive, RangeToInclusive};
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// Error returned when a value fails a [`Validate`] check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ValidationError {
+    field: Option<&'static str>,
+    reason: &'static str,
+}
+
+impl ValidationError {
+    /// Creates an error with the given reason.
+    #[inline]
+    pub const fn new(reason: &'static str) -> Self {
+        ValidationError { field: None, reason }
+    }
+
+    /// Attaches the name of the field that failed.
+    #[inline]
+    pub const fn with_field(mut self, field: &'static str) -> Self {
+        self.field = Some(field);
+        self
+    }
+
+    /// Returns the name of the field that failed, if known.
+    #[inline]
+    pub const fn field(&self) -> Option<&'static str> {
+        self.field
+    }
+
+    /// Returns why validation failed.
+    #[inline]
+    pub const fn reason(&self) -> &'static str {
+        self.reason
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.field {
+            Some(field) => write!(f, "invalid `{field}`: {}", self.reason),
+            None => f.write_str(self.reason),
+        }
+    }
+}
+
+impl core::error::Error for ValidationError {}
+
+/// A schema that checks values of type `T`.
+///
+/// Implemented for `a..=b`, `a..`, and `..=b` ranges (bounds check), for functions
+/// `Fn(&T) -> Result<(), ValidationError>`, and, with the `macros` feature,
+/// for [`FieldSchema`] by `#[derive(Validate)]`.
+pub trait Validate<T: ?Sized> {
+    /// Returns `Ok(())` if `value` satisfies the schema.
+    fn validate(&self, value: &T) -> Result<(), ValidationError>;
+}
+
+impl<T: PartialOrd> Validate<T> for RangeInclusive<T> {
+    #[inline]
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        if value < self.start() {
+            Err(ValidationError::new("below minimum"))
+        } else if value > self.end() {
+            Err(ValidationError::new("above maximum"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T: PartialOrd> Validate<T> for RangeFrom<T> {
+    #[inline]
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        if *value < self.start {
+            Err(ValidationError::new("below minimum"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T: PartialOrd> Validate<T> for RangeToInclusive<T> {
+    #[inline]
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        if *value > self.end {
+            Err(ValidationError::new("above maximum"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<T: ?Sized, F: Fn(&T) -> Result<(), ValidationError>> Validate<T> for F {
+    #[inline(always)]
+    fn validate(&self, value: &T) -> Result<(), ValidationError> {
+        self(value)
+    }
+}
+
+/// The schema of a struct's own field bounds.
+///
+/// `#[derive(Validate)]` (with the `macros` feature) implements
+/// `Validate<S> for FieldSchema` for a struct `S`, checking each field marked
+/// `#[validate(min = .., max = ..)]` against its bounds; either bound may be
+/// left out. The error names the first field that fails.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FieldSchema;
+
+/// Extension trait for validating a value before piping it.
+pub trait PipeValidate<const ARITY: usize> {
+    /// Like `pipe`, but first checks `self` against `schema`. Returns
+    /// `Ok(f(self, args..))` if it passes and `Err` without calling `f` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeValidate;
+    /// fn percent(x: u32, total: u32) -> u32 { x * 100 / total }
+    ///
+    /// assert_eq!(40.pipe_with_schema(0..=100, percent)(200), Ok(20));
+    /// assert!(400.pipe_with_schema(0..=100, percent)(200).is_err());
+    /// ```
+    #[inline(always)]
+    fn pipe_with_schema<S, R, F, Params>(self, schema: S, f: F) -> F::Curry
+    where
+        S: Validate<Self>,
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, WithSchema<S>, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, WithSchema(schema))
+    }
+}
+impl<const ARITY: usize, T> PipeValidate<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: validates the value before the call, short-circuiting with `Err`.
+pub struct WithSchema<S>(S);
+
+impl<S: Validate<A0>, A0, R> Around<A0, R> for WithSchema<S> {
+    type Output = Result<R, ValidationError>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        self.0.validate(&arg0)?;
+        Ok(call(arg0))
+    }
+}
@@ -0,0 +1,373 @@
+//! Pipes and taps that reach inside one variant of an `Option` or `Result`.
+
+use crate::{Curry, Own};
+
+/// Extension trait for tapping one variant of an `Option`.
+pub trait TapOption<const ARITY: usize, State> {
+    /// Returns a closure over the remaining arguments that calls
+    /// `f(&inner, args..)`, or `f(&mut inner, args..)`, if `self` is `Some`,
+    /// and returns `self`. `f` is not called on `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapOption;
+    /// fn log(x: &i32, out: &mut Vec<i32>) { out.push(*x) }
+    /// fn bump(x: &mut i32, by: i32) { *x += by }
+    ///
+    /// let mut out = Vec::new();
+    /// assert_eq!(Some(1).tap_some(log)(&mut out).tap_some(bump)(10), Some(11));
+    /// assert_eq!(None.tap_some(log)(&mut out).tap_some(bump)(10), None);
+    /// assert_eq!(out, [1]);
+    /// ```
+    #[inline(always)]
+    fn tap_some<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, State, Own, SomeMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Returns a closure over the remaining arguments that calls `f(args..)`
+    /// if `self` is `None`, and returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapOption;
+    /// fn miss(misses: &mut u32) { *misses += 1 }
+    ///
+    /// let mut misses = 0;
+    /// let cache = [Some(1), None, None];
+    /// let hits: Vec<_> = cache.into_iter().filter_map(|x| x.tap_none(miss)(&mut misses)).collect();
+    /// assert_eq!((hits, misses), (vec![1], 2));
+    /// ```
+    #[inline(always)]
+    fn tap_none<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, State, Own, NoneMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, State, T> TapOption<ARITY, State> for Option<T> {}
+
+/// Extension trait for tapping one variant of a `Result`.
+pub trait TapResult<const ARITY: usize, State> {
+    /// Returns a closure over the remaining arguments that calls
+    /// `f(&ok, args..)`, or `f(&mut ok, args..)`, if `self` is `Ok`, and
+    /// returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapResult;
+    /// fn log(x: &i32, out: &mut Vec<i32>) { out.push(*x) }
+    ///
+    /// let mut out = Vec::new();
+    /// assert_eq!(Ok::<_, ()>(1).tap_ok(log)(&mut out), Ok(1));
+    /// assert_eq!(Err::<i32, _>(()).tap_ok(log)(&mut out), Err(()));
+    /// assert_eq!(out, [1]);
+    /// ```
+    #[inline(always)]
+    fn tap_ok<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, State, Own, OkMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Returns a closure over the remaining arguments that calls
+    /// `f(&err, args..)`, or `f(&mut err, args..)`, if `self` is `Err`, and
+    /// returns `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::TapResult;
+    /// fn context(e: &mut String, what: &str) { e.insert_str(0, what) }
+    ///
+    /// assert_eq!(Err::<(), _>("timeout".to_string()).tap_err(context)("fetch: "), Err("fetch: timeout".to_string()));
+    /// ```
+    #[inline(always)]
+    fn tap_err<R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, State, Own, ErrMark, Self, R>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, State, T, E> TapResult<ARITY, State> for Result<T, E> {}
+
+/// Extension trait for transforming one variant of an `Option`.
+pub trait PipeOption<const ARITY: usize> {
+    /// Returns a closure over the remaining arguments that maps `Some(v)` to
+    /// `Some(f(v, args..))` and passes `None` through, like [`Option::map`]
+    /// for a multi-argument `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeOption;
+    /// fn add(x: i32, y: i32) -> i32 { x + y }
+    ///
+    /// assert_eq!(Some(1).pipe_some(add)(2), Some(3));
+    /// assert_eq!(None.pipe_some(add)(2), None);
+    /// ```
+    #[inline(always)]
+    fn pipe_some<U, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PipeSomeMark, Self, U>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Returns a closure over the remaining arguments that replaces `None`
+    /// with `Some(f(args..))` and passes `Some` through. `f` only receives the
+    /// remaining arguments, as `None` holds no value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeOption;
+    /// fn fallback(host: &str, port: u16) -> String { format!("{host}:{port}") }
+    ///
+    /// assert_eq!(None.pipe_none(fallback)("localhost", 80), Some("localhost:80".to_string()));
+    /// assert_eq!(Some("db:5432".to_string()).pipe_none(fallback)("localhost", 80).as_deref(), Some("db:5432"));
+    /// ```
+    #[inline(always)]
+    fn pipe_none<T, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PipeNoneMark, Self, T>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T> PipeOption<ARITY> for Option<T> {}
+
+/// Extension trait for transforming one variant of a `Result`.
+pub trait PipeResult<const ARITY: usize> {
+    /// Returns a closure over the remaining arguments that maps `Ok(v)` to
+    /// `Ok(f(v, args..))` and passes `Err` through, like [`Result::map`] for a
+    /// multi-argument `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeResult;
+    /// fn label(x: i32, unit: &str) -> String { format!("{x}{unit}") }
+    ///
+    /// assert_eq!(Ok::<_, ()>(5).pipe_ok(label)("ms"), Ok("5ms".to_string()));
+    /// assert_eq!(Err::<i32, _>(()).pipe_ok(label)("ms"), Err(()));
+    /// ```
+    #[inline(always)]
+    fn pipe_ok<U, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PipeOkMark, Self, U>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+
+    /// Returns a closure over the remaining arguments that maps `Err(e)` to
+    /// `Err(f(e, args..))` and passes `Ok` through, like [`Result::map_err`]
+    /// for a multi-argument `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::PipeResult;
+    /// fn context(e: &str, op: &str) -> String { format!("{op}: {e}") }
+    ///
+    /// assert_eq!(Err::<(), _>("timeout").pipe_err(context)("fetch"), Err("fetch: timeout".to_string()));
+    /// ```
+    #[inline(always)]
+    fn pipe_err<U, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, PipeErrMark, Self, U>,
+        Self: Sized,
+    {
+        f.curry(self)
+    }
+}
+impl<const ARITY: usize, T, E> PipeResult<ARITY> for Result<T, E> {}
+
+#[doc(hidden)]
+/// Marker type: `tap_some` semantics (tap the `Some` value).
+pub struct SomeMark;
+
+#[doc(hidden)]
+/// Marker type: `tap_none` semantics (side effect on `None`).
+pub struct NoneMark;
+
+#[doc(hidden)]
+/// Marker type: `tap_ok` semantics (tap the `Ok` value).
+pub struct OkMark;
+
+#[doc(hidden)]
+/// Marker type: `tap_err` semantics (tap the `Err` value).
+pub struct ErrMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_some` semantics (map the `Some` value).
+pub struct PipeSomeMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_none` semantics (fill in `None`).
+pub struct PipeNoneMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_ok` semantics (map the `Ok` value).
+pub struct PipeOkMark;
+
+#[doc(hidden)]
+/// Marker type: `pipe_err` semantics (map the `Err` value).
+pub struct PipeErrMark;
+
+macro_rules! impl_variant_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, ErrMark, NoneMark, OkMark, Own, PipeErrMark, PipeNoneMark, PipeOkMark, PipeSomeMark, SomeMark};
+            #[cfg(feature = $feat)]
+            use crate::{Imm, Mut};
+
+            // --- Tap Some ---
+            #[cfg(feature = $feat)]
+            impl<F, T, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, SomeMark, Option<T>, R> for F
+            where F: FnOnce(&T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Option<T>;
+                #[inline(always)] fn curry(self, arg0: Option<T>) -> Self::Curry {
+                    |$($Params),*| {
+                        if let Some(v) = &arg0 { self(v, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, T, $($Params,)* R> Curry<$N, $TupleType, Mut, Own, SomeMark, Option<T>, R> for F
+            where F: FnOnce(&mut T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Option<T>;
+                #[inline(always)] fn curry(self, mut arg0: Option<T>) -> Self::Curry {
+                    |$($Params),*| {
+                        if let Some(v) = &mut arg0 { self(v, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            // --- Tap None ---
+            #[cfg(feature = $feat)]
+            impl<F, T, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, NoneMark, Option<T>, R> for F
+            where F: FnOnce($($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Option<T>;
+                #[inline(always)] fn curry(self, arg0: Option<T>) -> Self::Curry {
+                    |$($Params),*| {
+                        if arg0.is_none() { self($($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            // --- Tap Ok / Err ---
+            #[cfg(feature = $feat)]
+            impl<F, T, E, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, OkMark, Result<T, E>, R> for F
+            where F: FnOnce(&T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, E>;
+                #[inline(always)] fn curry(self, arg0: Result<T, E>) -> Self::Curry {
+                    |$($Params),*| {
+                        if let Ok(v) = &arg0 { self(v, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, T, E, $($Params,)* R> Curry<$N, $TupleType, Mut, Own, OkMark, Result<T, E>, R> for F
+            where F: FnOnce(&mut T, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, E>;
+                #[inline(always)] fn curry(self, mut arg0: Result<T, E>) -> Self::Curry {
+                    |$($Params),*| {
+                        if let Ok(v) = &mut arg0 { self(v, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, T, E, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, ErrMark, Result<T, E>, R> for F
+            where F: FnOnce(&E, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, E>;
+                #[inline(always)] fn curry(self, arg0: Result<T, E>) -> Self::Curry {
+                    |$($Params),*| {
+                        if let Err(e) = &arg0 { self(e, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, T, E, $($Params,)* R> Curry<$N, $TupleType, Mut, Own, ErrMark, Result<T, E>, R> for F
+            where F: FnOnce(&mut E, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, E>;
+                #[inline(always)] fn curry(self, mut arg0: Result<T, E>) -> Self::Curry {
+                    |$($Params),*| {
+                        if let Err(e) = &mut arg0 { self(e, $($Params),*); }
+                        arg0
+                    }
+                }
+            }
+
+            // --- Pipe Some / None ---
+            #[cfg(feature = $feat)]
+            impl<F, T, $($Params,)* U> Curry<$N, $TupleType, Own, Own, PipeSomeMark, Option<T>, U> for F
+            where F: FnOnce(T, $($Params),*) -> U {
+                type Curry = impl FnOnce($($Params),*) -> Option<U>;
+                #[inline(always)] fn curry(self, arg0: Option<T>) -> Self::Curry {
+                    |$($Params),*| arg0.map(|v| self(v, $($Params),*))
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, T, $($Params),*> Curry<$N, $TupleType, Own, Own, PipeNoneMark, Option<T>, T> for F
+            where F: FnOnce($($Params),*) -> T {
+                type Curry = impl FnOnce($($Params),*) -> Option<T>;
+                #[inline(always)] fn curry(self, arg0: Option<T>) -> Self::Curry {
+                    |$($Params),*| Some(arg0.unwrap_or_else(|| self($($Params),*)))
+                }
+            }
+
+            // --- Pipe Ok / Err ---
+            #[cfg(feature = $feat)]
+            impl<F, T, E, $($Params,)* U> Curry<$N, $TupleType, Own, Own, PipeOkMark, Result<T, E>, U> for F
+            where F: FnOnce(T, $($Params),*) -> U {
+                type Curry = impl FnOnce($($Params),*) -> Result<U, E>;
+                #[inline(always)] fn curry(self, arg0: Result<T, E>) -> Self::Curry {
+                    |$($Params),*| arg0.map(|v| self(v, $($Params),*))
+                }
+            }
+
+            #[cfg(feature = $feat)]
+            impl<F, T, E, $($Params,)* U> Curry<$N, $TupleType, Own, Own, PipeErrMark, Result<T, E>, U> for F
+            where F: FnOnce(E, $($Params),*) -> U {
+                type Curry = impl FnOnce($($Params),*) -> Result<T, U>;
+                #[inline(always)] fn curry(self, arg0: Result<T, E>) -> Self::Curry {
+                    |$($Params),*| arg0.map_err(|e| self(e, $($Params),*))
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_variant_arity);
+}
@@ -0,0 +1,101 @@
+//! Pipe stages that only run from a minimum version onwards, or pick an
+//! implementation by version.
+
+use core::fmt;
+
+use crate::{Around, AroundMark, CurryWith, Own};
+
+/// A `(major, minor, patch)` version, ordered component by component.
+pub type Version = (u32, u32, u32);
+
+/// Error returned when the current version is older than a stage requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VersionTooOld {
+    /// The oldest version the stage runs on.
+    pub min_version: Version,
+    /// The version that was checked.
+    pub current_version: Version,
+}
+
+impl fmt::Display for VersionTooOld {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (major, minor, patch) = self.current_version;
+        let (min_major, min_minor, min_patch) = self.min_version;
+        write!(f, "version {major}.{minor}.{patch} is older than the required {min_major}.{min_minor}.{min_patch}")
+    }
+}
+
+impl core::error::Error for VersionTooOld {}
+
+/// Extension trait for version-gated pipe stages.
+pub trait PipeVersionGate<const ARITY: usize> {
+    /// Like `pipe`, but only calls `f` if `current_version >= min_version`,
+    /// returning `Err(VersionTooOld)` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{PipeVersionGate, VersionTooOld};
+    /// fn encode(x: u32, tag: &str) -> String { format!("{tag}:{x}") }
+    ///
+    /// assert_eq!(7.pipe_with_version_gate((2, 0, 0), (2, 1, 0), encode)("v2").as_deref(), Ok("v2:7"));
+    ///
+    /// let err = 7.pipe_with_version_gate((2, 0, 0), (1, 9, 3), encode)("v2").unwrap_err();
+    /// assert_eq!(err, VersionTooOld { min_version: (2, 0, 0), current_version: (1, 9, 3) });
+    /// ```
+    #[inline(always)]
+    fn pipe_with_version_gate<R, F, Params>(self, min_version: Version, current_version: Version, f: F) -> F::Curry
+    where
+        F: CurryWith<ARITY, Params, Own, AroundMark, Self, VersionGate, R>,
+        Self: Sized,
+    {
+        f.curry_with(self, VersionGate(VersionTooOld { min_version, current_version }))
+    }
+}
+impl<const ARITY: usize, T> PipeVersionGate<ARITY> for T {}
+
+#[doc(hidden)]
+/// Wrapper: runs the call only if the current version meets the minimum.
+pub struct VersionGate(VersionTooOld);
+
+impl<A0, R> Around<A0, R> for VersionGate {
+    type Output = Result<R, VersionTooOld>;
+    #[inline(always)]
+    fn around<C: FnOnce(A0) -> R>(self, arg0: A0, call: C) -> Self::Output {
+        if self.0.current_version >= self.0.min_version { Ok(call(arg0)) } else { Err(self.0) }
+    }
+}
+
+/// The ABI version of an implementation of an external function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AbiVersion(pub u32);
+
+/// An implementation of an external function, tagged with its ABI version.
+pub type AbiImpl<T, R> = (AbiVersion, fn(T) -> R);
+
+/// Extension trait for dispatching to the implementation matching an ABI version.
+pub trait PipeAbiVersion: Sized {
+    /// Pipes `self` into the implementation with the highest version not
+    /// above `version`, or returns `None` if every implementation is newer.
+    ///
+    /// `impls` must be sorted by version, oldest first; the lookup is a
+    /// binary search.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::{AbiImpl, AbiVersion, PipeAbiVersion};
+    /// let impls: [AbiImpl<u32, u32>; 2] = [(AbiVersion(1), |x| x), (AbiVersion(3), |x| x * 2)];
+    ///
+    /// assert_eq!(5.pipe_with_abi_version(AbiVersion(2), &impls), Some(5));
+    /// assert_eq!(5.pipe_with_abi_version(AbiVersion(3), &impls), Some(10));
+    /// assert_eq!(5.pipe_with_abi_version(AbiVersion(0), &impls), None);
+    /// ```
+    #[inline]
+    fn pipe_with_abi_version<R>(self, version: AbiVersion, impls: &[AbiImpl<Self, R>]) -> Option<R> {
+        let compatible = impls.partition_point(|(v, _)| *v <= version);
+        let (_, f) = impls.get(compatible.checked_sub(1)?)?;
+        Some(f(self))
+    }
+}
+impl<T> PipeAbiVersion for T {}
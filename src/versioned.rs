@@ -0,0 +1,131 @@
+//! A wrapper that counts the transformations a value has gone through.
+
+use crate::{Curry, Imm, Own};
+
+/// A value paired with the number of pipeline stages applied to it.
+///
+/// Each [`pipe_epoch`](Versioned::pipe_epoch) or [`tap_epoch`](Versioned::tap_epoch)
+/// bumps the version by one, which makes it cheap to tell whether a value has
+/// changed since it was last observed.
+///
+/// # Examples
+///
+/// ```rust
+/// # use pipei::Versioned;
+/// fn add(x: i32, y: i32) -> i32 { x + y }
+///
+/// let v = Versioned::new(1).pipe_epoch(add)(2).pipe_epoch(add)(3);
+/// assert_eq!(v.version(), 2);
+/// assert_eq!(v.unwrap(), 6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Versioned<T> {
+    version: u64,
+    value: T,
+}
+
+impl<T> Versioned<T> {
+    /// Wraps `value` at version 0.
+    #[inline]
+    pub const fn new(value: T) -> Self {
+        Versioned { version: 0, value }
+    }
+
+    /// Returns the number of stages applied so far.
+    #[inline]
+    pub const fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns a reference to the wrapped value.
+    #[inline]
+    pub const fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Extracts the wrapped value, discarding the version.
+    #[inline]
+    pub fn unwrap(self) -> T {
+        self.value
+    }
+
+    /// Curries the wrapped value into `f`, returning a closure over the remaining
+    /// arguments that yields `f`'s result at the next version.
+    #[inline(always)]
+    pub fn pipe_epoch<const ARITY: usize, R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Own, Own, EpochMark, Self, R>,
+    {
+        f.curry(self)
+    }
+
+    /// Curries a reference to the wrapped value into `f` for a side effect,
+    /// returning a closure over the remaining arguments that yields the same
+    /// value at the next version.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pipei::Versioned;
+    /// fn check(x: &i32, max: i32) { assert!(*x <= max) }
+    ///
+    /// let v = Versioned::new(5).tap_epoch(check)(10);
+    /// assert_eq!((v.version(), *v.get()), (1, 5));
+    /// ```
+    #[inline(always)]
+    pub fn tap_epoch<const ARITY: usize, R, F, Params>(self, f: F) -> F::Curry
+    where
+        F: Curry<ARITY, Params, Imm, Own, EpochMark, Self, R>,
+    {
+        f.curry(self)
+    }
+}
+
+impl<T> From<T> for Versioned<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Versioned::new(value)
+    }
+}
+
+#[doc(hidden)]
+/// Marker type: `pipe_epoch` / `tap_epoch` semantics (bump the version of a [`Versioned`]).
+pub struct EpochMark;
+
+macro_rules! impl_versioned_arity {
+    ($N:literal, $feat:literal, [ $($Params:ident),* ], $TupleType:ty) => {
+        const _: () = {
+            #[cfg(feature = $feat)]
+            use super::{Curry, EpochMark, Imm, Own, Versioned};
+
+            // --- Pipe Epoch ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R> Curry<$N, $TupleType, Own, Own, EpochMark, Versioned<A0>, R> for F
+            where F: FnOnce(A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Versioned<R>;
+                #[inline(always)] fn curry(self, arg0: Versioned<A0>) -> Self::Curry {
+                    move |$($Params),*| Versioned { version: arg0.version + 1, value: self(arg0.value, $($Params),*) }
+                }
+            }
+
+            // --- Tap Epoch ---
+            #[cfg(feature = $feat)]
+            impl<F, A0, $($Params,)* R> Curry<$N, $TupleType, Imm, Own, EpochMark, Versioned<A0>, R> for F
+            where F: FnOnce(&A0, $($Params),*) -> R {
+                type Curry = impl FnOnce($($Params),*) -> Versioned<A0>;
+                #[inline(always)] fn curry(self, arg0: Versioned<A0>) -> Self::Curry {
+                    move |$($Params),*| {
+                        self(&arg0.value, $($Params),*);
+                        Versioned { version: arg0.version + 1, value: arg0.value }
+                    }
+                }
+            }
+        };
+    };
+}
+
+mod generated {
+    #![allow(non_snake_case)]
+
+    for_each_arity!(impl_versioned_arity);
+}
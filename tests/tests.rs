@@ -1,3 +1,6 @@
+#![allow(clippy::let_and_return)]
+#![cfg_attr(feature = "macros", feature(impl_trait_in_assoc_type))]
+
 use pipei::{Pipe, Tap, TapWith};
 
 #[test]
@@ -950,3 +953,5300 @@ mod cross_arity_chain_tests {
         assert_eq!(result, 11);
     }
 }
+
+// ============================================================================================
+// Collection tests
+// ============================================================================================
+
+#[cfg(all(feature = "alloc", feature = "1"))]
+mod collection_tests {
+    use core::cmp::Ordering;
+    use pipei::PipeCollection;
+
+    #[derive(Debug, PartialEq)]
+    struct Person {
+        name: &'static str,
+        age: u32,
+    }
+
+    enum Locale {
+        CaseSensitive,
+        CaseInsensitive,
+    }
+
+    fn people() -> Vec<Person> {
+        vec![
+            Person { name: "bob", age: 30 },
+            Person { name: "Carol", age: 25 },
+            Person { name: "alice", age: 35 },
+        ]
+    }
+
+    fn names(people: &[Person]) -> Vec<&'static str> {
+        people.iter().map(|p| p.name).collect()
+    }
+
+    fn by_name(a: &Person, b: &Person, locale: &Locale) -> Ordering {
+        match locale {
+            Locale::CaseSensitive => a.name.cmp(b.name),
+            Locale::CaseInsensitive => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        }
+    }
+
+    #[test]
+    fn sort_by_with_passes_context() {
+        let sorted = people().pipe_sort_by_with(by_name)(Locale::CaseSensitive);
+        assert_eq!(names(&sorted), ["Carol", "alice", "bob"]);
+
+        let sorted = people().pipe_sort_by_with(by_name)(Locale::CaseInsensitive);
+        assert_eq!(names(&sorted), ["alice", "bob", "Carol"]);
+    }
+
+    #[test]
+    fn sort_by_key_with_passes_context() {
+        fn age_distance(p: &Person, target: &u32) -> u32 {
+            p.age.abs_diff(*target)
+        }
+
+        let sorted = people().pipe_sort_by_key_with(age_distance)(33);
+        assert_eq!(names(&sorted), ["alice", "bob", "Carol"]);
+    }
+
+    #[test]
+    #[cfg(feature = "2")]
+    fn sort_by_with_arity_2() {
+        fn by_field(a: &Person, b: &Person, locale: &Locale, descending: &bool) -> Ordering {
+            let ord = by_name(a, b, locale);
+            if *descending { ord.reverse() } else { ord }
+        }
+
+        let sorted = people().pipe_sort_by_with(by_field)(Locale::CaseInsensitive, true);
+        assert_eq!(names(&sorted), ["Carol", "bob", "alice"]);
+    }
+
+    #[test]
+    fn partition_with_splits_in_order() {
+        fn older_than(p: &Person, age: &u32) -> bool {
+            p.age > *age
+        }
+
+        let (old, young) = people().pipe_partition_with(older_than)(28);
+        assert_eq!(names(&old), ["bob", "alice"]);
+        assert_eq!(names(&young), ["Carol"]);
+    }
+
+    #[test]
+    fn partition_with_accepts_iterators() {
+        fn divisible(x: &u32, n: &u32) -> bool {
+            x.is_multiple_of(*n)
+        }
+
+        let (yes, no) = (1..=10).pipe_partition_with(divisible)(3);
+        assert_eq!(yes.len() + no.len(), 10);
+        assert_eq!(yes, [3, 6, 9]);
+
+        let (yes, no) = core::iter::empty::<u32>().pipe_partition_with(divisible)(3);
+        assert!(yes.is_empty() && no.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "either")]
+    fn partition_map_with_splits_by_side() {
+        use either::Either;
+
+        fn by_age(p: Person, age: &u32) -> Either<String, u32> {
+            if p.age > *age { Either::Left(p.name.to_string()) } else { Either::Right(p.age) }
+        }
+
+        let (old, young_ages) = people().pipe_partition_map_with(by_age)(28);
+        assert_eq!(old, ["bob", "alice"]);
+        assert_eq!(young_ages, [25]);
+
+        let (all, none) = people().pipe_partition_map_with(by_age)(0);
+        assert_eq!((all.len(), none.len()), (3, 0));
+    }
+}
+
+#[cfg(feature = "1")]
+mod flat_map_tests {
+    use pipei::PipeCollection;
+
+    fn divisors(n: u32, limit: u32) -> impl Iterator<Item = u32> {
+        (1..=limit.min(n)).filter(move |d| n.is_multiple_of(*d))
+    }
+
+    #[test]
+    fn flat_map_empty() {
+        assert_eq!(12.pipe_flat_map(divisors)(0).count(), 0);
+        assert_eq!("   ".pipe_flat_map(str::split_whitespace)().count(), 0);
+    }
+
+    #[test]
+    fn flat_map_single() {
+        let mut it = 7.pipe_flat_map(Option::Some)();
+        assert_eq!(it.next(), Some(7));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn flat_map_multiple() {
+        let words = "the quick  brown fox".pipe_flat_map(str::split_whitespace)();
+        assert!(words.eq(["the", "quick", "brown", "fox"]));
+
+        let sum: u32 = 12.pipe_flat_map(divisors)(6).sum();
+        assert_eq!(sum, 1 + 2 + 3 + 4 + 6);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn flat_map_collect() {
+        let ds = 12.pipe_flat_map_collect::<Vec<_>, _, _>(divisors)(12);
+        assert_eq!(ds, [1, 2, 3, 4, 6, 12]);
+
+        let none = 12.pipe_flat_map_collect::<Vec<_>, _, _>(divisors)(0);
+        assert!(none.is_empty());
+    }
+}
+
+#[cfg(feature = "1")]
+mod window_tests {
+    use pipei::PipeCollection;
+
+    #[cfg(feature = "alloc")]
+    fn sum_plus(w: &[i32], offset: i32) -> i32 {
+        w.iter().sum::<i32>() + offset
+    }
+
+    #[test]
+    fn window_iter_is_lazy() {
+        let mut calls = 0;
+        let mut it = [1, 2, 3].as_slice().pipe_window_iter(2, |w: &[i32], k: i32| {
+            calls += 1;
+            w[0] * k
+        })(3);
+        assert_eq!(it.size_hint(), (2, Some(2)));
+        assert_eq!(it.next(), Some(3));
+        assert_eq!(it.next(), Some(6));
+        assert_eq!(it.next(), None);
+        assert_eq!(calls, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn window_size_one_visits_each_element() {
+        let out = [4, 5, 6].as_slice().pipe_window(1, sum_plus)(100);
+        assert_eq!(out, [104, 105, 106]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn window_size_len_is_single_window() {
+        let data = [4, 5, 6];
+        let out = data.as_slice().pipe_window(data.len(), sum_plus)(0);
+        assert_eq!(out, [15]);
+
+        let out = data.as_slice().pipe_window(data.len() + 1, sum_plus)(0);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn window_args_reach_every_call() {
+        let label = String::from("w");
+        let out = [1, 2, 3].as_slice().pipe_window(2, |w: &[i32], l: String| {
+            format!("{l}{}{}", w[0], w[1])
+        })(label);
+        assert_eq!(out, ["w12", "w23"]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn chunks_with_leaves_short_tail() {
+        let out = [1, 2, 3, 4, 5].as_slice().pipe_chunks_with(2, sum_plus)(1);
+        assert_eq!(out, [4, 8, 6]);
+    }
+}
+
+// ============================================================================================
+// Zip tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1", feature = "2"))]
+mod zip_tests {
+    use pipei::PipeWith;
+
+    #[test]
+    fn zip_with_two_args() {
+        fn sub(a: i32, b: i32) -> i32 {
+            a - b
+        }
+        assert_eq!(10.pipe_zip_with(3, sub)(), 7);
+    }
+
+    #[test]
+    fn zip_with_three_args() {
+        fn combine(a: Result<i32, ()>, b: Result<i32, ()>, prefer_first: bool) -> Option<i32> {
+            match (a, b) {
+                (Ok(x), Ok(y)) => Some(if prefer_first { x } else { y }),
+                (Ok(x), Err(_)) | (Err(_), Ok(x)) => Some(x),
+                _ => None,
+            }
+        }
+        assert_eq!(Ok(1).pipe_zip_with(Ok(2), combine)(true), Some(1));
+        assert_eq!(Ok(1).pipe_zip_with(Ok(2), combine)(false), Some(2));
+        assert_eq!(Err(()).pipe_zip_with(Ok(2), combine)(true), Some(2));
+    }
+
+    #[test]
+    fn zip_with_four_args() {
+        fn mix(a: String, b: &str, sep: char, times: usize) -> String {
+            let mut out = a;
+            for _ in 0..times {
+                out.push(sep);
+                out.push_str(b);
+            }
+            out
+        }
+        let result = String::from("x").pipe_zip_with("y", mix)('-', 2);
+        assert_eq!(result, "x-y-y");
+    }
+}
+
+// ============================================================================================
+// Arity configuration tests
+// ============================================================================================
+
+mod arity_config_tests {
+    use pipei::{max_supported_arity, MAX_ARITY};
+
+    #[test]
+    fn max_arity_matches_enabled_features() {
+        #[cfg(all(feature = "10", not(feature = "11")))]
+        assert_eq!(MAX_ARITY, 10);
+        #[cfg(all(feature = "5", not(feature = "6")))]
+        assert_eq!(MAX_ARITY, 5);
+        #[cfg(feature = "50")]
+        assert_eq!(MAX_ARITY, 50);
+        const { assert!(MAX_ARITY <= 50) };
+    }
+
+    #[test]
+    fn max_supported_arity_is_the_constant() {
+        const MAX: usize = max_supported_arity();
+        assert_eq!(MAX, MAX_ARITY);
+    }
+}
+
+// ============================================================================================
+// Panic tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod panic_tests {
+    use pipei::{Pipe, PipePanic};
+
+    fn div(x: i32, y: i32) -> i32 {
+        if y == 0 {
+            panic!("division by zero: {x} / {y}");
+        }
+        x / y
+    }
+
+    #[test]
+    fn error_boundary_returns_ok() {
+        assert_eq!(12.pipe_with_error_boundary(div)(4).unwrap(), 3);
+    }
+
+    #[test]
+    fn error_boundary_catches_panic() {
+        let err = 12.pipe_with_error_boundary(div)(0).unwrap_err();
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert_eq!(msg, "division by zero: 12 / 0");
+
+        let err = 1.pipe_with_error_boundary(|_: i32| -> i32 { panic!("static") })().unwrap_err();
+        assert_eq!(err.downcast_ref::<&str>(), Some(&"static"));
+    }
+
+    #[test]
+    fn error_boundary_default_recovers() {
+        assert_eq!(12.pipe_with_error_boundary_default(div)(4), 3);
+        assert_eq!(12.pipe_with_error_boundary_default(div)(0), 0);
+    }
+
+    #[test]
+    fn pipeline_continues_after_recovery() {
+        fn add(x: i32, y: i32) -> i32 {
+            x + y
+        }
+
+        let result = 12
+            .pipe_with_error_boundary(div)(0)
+            .unwrap_or(-1)
+            .pipe(add)(10)
+            .pipe_with_error_boundary(div)(3);
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn abort_on_panic_passes_result() {
+        assert_eq!(12.pipe_with_abort_on_panic(div)(4), 3);
+    }
+
+    #[test]
+    fn abort_on_panic_aborts_process() {
+        const CHILD: &str = "PIPEI_ABORT_ON_PANIC_CHILD";
+        if std::env::var_os(CHILD).is_some() {
+            12.pipe_with_abort_on_panic(div)(0);
+            // Only reached if the panic unwound instead of aborting.
+            std::process::exit(0);
+        }
+
+        let output = std::process::Command::new(std::env::current_exe().unwrap())
+            .args(["--exact", "panic_tests::abort_on_panic_aborts_process", "--nocapture", "--test-threads=1"])
+            .env(CHILD, "1")
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::ExitStatusExt;
+            assert_eq!(output.status.signal(), Some(6), "expected SIGABRT, got {:?}", output.status);
+        }
+        assert!(String::from_utf8_lossy(&output.stderr).contains("division by zero: 12 / 0"));
+    }
+}
+
+// ============================================================================================
+// Side channel tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod side_channel_tests {
+    use core::fmt::Write;
+    use pipei::{Pipe, TapValue, TapWith};
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i32,
+        y: i32,
+    }
+
+    #[test]
+    fn side_channel_captures_label_and_value() {
+        let mut out = String::new();
+        let p = Point { x: 1, y: -2 }.tap_side_channel(&mut out, "origin");
+        assert_eq!(p, Point { x: 1, y: -2 });
+        assert_eq!(out, "origin: Point { x: 1, y: -2 }\n");
+    }
+
+    #[test]
+    fn side_channel_records_each_stage() {
+        fn double(x: i32) -> i32 {
+            x * 2
+        }
+
+        let mut out = String::new();
+        let v = 3
+            .tap_side_channel(&mut out, "in")
+            .pipe(double)()
+            .tap_side_channel(&mut out, "out");
+        assert_eq!(v, 6);
+        assert_eq!(out.lines().collect::<Vec<_>>(), ["in: 3", "out: 6"]);
+    }
+
+    #[test]
+    fn side_channel_fmt_uses_custom_format() {
+        fn coords(p: &Point, out: &mut String) -> core::fmt::Result {
+            write!(out, "({}, {})", p.x, p.y)
+        }
+        fn tagged(p: &Point, out: &mut String, tag: &str) -> core::fmt::Result {
+            write!(out, "[{tag}] x={}", p.x)
+        }
+
+        let mut out = String::new();
+        let p = Point { x: 4, y: 5 }.tap_side_channel_fmt(&mut out, coords)();
+        assert_eq!(out, "(4, 5)");
+
+        out.clear();
+        let p = p.tap_side_channel_fmt(&mut out, tagged)("moved");
+        assert_eq!(out, "[moved] x=4");
+        assert_eq!(p, Point { x: 4, y: 5 });
+    }
+}
+
+// ============================================================================================
+// Atomic tests
+// ============================================================================================
+
+mod atomic_tests {
+    use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering};
+    use pipei::PipeAtomic;
+
+    #[test]
+    fn atomic_update_returns_stored_value() {
+        let a = AtomicI32::new(-4);
+        assert_eq!(a.pipe_atomic_update(i32::abs), 4);
+        assert_eq!(a.load(Ordering::SeqCst), 4);
+
+        let flag = AtomicBool::new(false);
+        assert!(flag.pipe_atomic_update(|b| !b));
+    }
+
+    #[test]
+    fn atomic_fetch_update_follows_fetch_update() {
+        let a = AtomicUsize::new(3);
+        assert_eq!(a.pipe_atomic_fetch_update(|x| x.checked_sub(3)), Ok(3));
+        assert_eq!(a.pipe_atomic_fetch_update(|x| x.checked_sub(1)), Err(0));
+        assert_eq!(a.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn atomic_update_is_consistent_across_threads() {
+        const THREADS: u64 = 8;
+        const ITERS: u64 = 1_000;
+
+        let counter = AtomicU64::new(0);
+        let max_seen = AtomicU64::new(0);
+        std::thread::scope(|s| {
+            for _ in 0..THREADS {
+                s.spawn(|| {
+                    for _ in 0..ITERS {
+                        let now = counter.pipe_atomic_update(|x| x + 1);
+                        max_seen.pipe_atomic_update(|m| m.max(now));
+                    }
+                });
+            }
+        });
+
+        assert_eq!(counter.load(Ordering::SeqCst), THREADS * ITERS);
+        assert_eq!(max_seen.load(Ordering::SeqCst), THREADS * ITERS);
+    }
+}
+
+// ============================================================================================
+// Drop guard tests
+// ============================================================================================
+
+mod drop_guard_tests {
+    use core::cell::Cell;
+    use pipei::PipeOnDrop;
+
+    #[test]
+    fn cleanup_runs_on_drop() {
+        let seen = Cell::new(None);
+        {
+            let _guard = 42.pipe_on_drop(|v| seen.set(Some(v)));
+            assert_eq!(seen.get(), None);
+        }
+        assert_eq!(seen.get(), Some(42));
+    }
+
+    #[test]
+    fn cleanup_skipped_after_defuse() {
+        let calls = Cell::new(0);
+        let guard = String::from("keep").pipe_on_drop(|_| calls.set(calls.get() + 1));
+        let inner = guard.defuse();
+        assert_eq!(inner, "keep");
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn value_accessible_through_deref() {
+        let total = Cell::new(0);
+        {
+            let mut guard = vec![1, 2].pipe_on_drop(|v: Vec<i32>| total.set(v.iter().sum()));
+            assert_eq!(guard.len(), 2);
+            guard.push(3);
+            assert_eq!(guard[2], 3);
+        }
+        assert_eq!(total.get(), 6);
+    }
+
+    #[test]
+    fn cleanup_receives_value_when_unwinding() {
+        let seen = Cell::new(0);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _guard = 7.pipe_on_drop(|v| seen.set(v));
+            panic!("stage failed");
+        }));
+        assert!(result.is_err());
+        assert_eq!(seen.get(), 7);
+    }
+}
+
+// ============================================================================================
+// Iterator tap tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod iter_tap_tests {
+    use core::cell::Cell;
+    use pipei::TapIter;
+
+    #[test]
+    fn progress_reports_once_per_element() {
+        let reports = Cell::new(0);
+        let seen = Cell::new(0);
+        let items = ["a", "b", "c", "d"];
+
+        let count = items
+            .iter()
+            .tap_with_progress(items.len(), |i, total| {
+                assert_eq!(i, reports.get());
+                assert_eq!(total, 4);
+                reports.set(reports.get() + 1);
+            }, |_: &&&str| seen.set(seen.get() + 1))()
+            .count();
+
+        assert_eq!(count, 4);
+        assert_eq!(reports.get(), 4);
+        assert_eq!(seen.get(), 4);
+    }
+
+    #[test]
+    fn progress_is_lazy() {
+        let reports = Cell::new(0);
+        let mut it = (0..10).tap_with_progress(0, |_, total| {
+            assert_eq!(total, 0);
+            reports.set(reports.get() + 1);
+        }, |_: &i32| ())();
+        assert_eq!(reports.get(), 0);
+
+        assert_eq!(it.next(), Some(0));
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(reports.get(), 2);
+    }
+
+    #[test]
+    fn progress_passes_args_to_tap() {
+        fn record(x: &i32, log: &Cell<i32>) {
+            log.set(log.get() * 10 + x);
+        }
+
+        let log = Cell::new(0);
+        let out: Vec<i32> = vec![1, 2, 3].into_iter().tap_with_progress(3, |_, _| (), record)(&log).collect();
+        assert_eq!(out, [1, 2, 3]);
+        assert_eq!(log.get(), 123);
+    }
+}
+
+// ============================================================================================
+// Dispatch tests
+// ============================================================================================
+
+#[cfg(feature = "std")]
+mod dispatch_tests {
+    use pipei::{PipeDispatch, PipeDispatchTable};
+    use std::collections::HashMap;
+
+    struct Request {
+        path: &'static str,
+        body: String,
+    }
+
+    fn get(r: Request) -> String {
+        format!("GET {}", r.path)
+    }
+    fn post(r: Request) -> String {
+        format!("POST {} <{}>", r.path, r.body)
+    }
+    fn delete(r: Request) -> String {
+        format!("DELETE {}", r.path)
+    }
+
+    fn req(path: &'static str) -> Request {
+        Request { path, body: "data".into() }
+    }
+
+    #[test]
+    fn select_from_map_routes_to_handler() {
+        let table: HashMap<&str, fn(Request) -> String> =
+            HashMap::from([("GET", get as fn(Request) -> String), ("POST", post), ("DELETE", delete)]);
+
+        assert_eq!(req("/a").pipe_select_from_map(&table, "GET").unwrap(), "GET /a");
+        assert_eq!(req("/b").pipe_select_from_map(&table, "POST").unwrap(), "POST /b <data>");
+        assert_eq!(req("/c").pipe_select_from_map(&table, "DELETE").unwrap(), "DELETE /c");
+        assert_eq!(req("/d").pipe_select_from_map(&table, "PATCH"), None);
+    }
+
+    #[test]
+    fn select_from_map_with_passes_extra() {
+        let table: HashMap<String, fn(i32, i32) -> i32> =
+            HashMap::from([("max".to_string(), i32::max as fn(i32, i32) -> i32), ("min".to_string(), i32::min)]);
+
+        assert_eq!(5.pipe_select_from_map_with(&table, "max", 9), Some(9));
+        assert_eq!(5.pipe_select_from_map_with(&table, "min", 9), Some(5));
+        assert_eq!(5.pipe_select_from_map_with(&table, "avg", 9), None);
+    }
+
+    #[test]
+    fn dispatch_table_supports_closures() {
+        let prefix = String::from("v2");
+        let table = PipeDispatchTable::new()
+            .route("GET", get)
+            .route("POST", post)
+            .route("DELETE", move |r: Request| format!("{prefix} DELETE {}", r.path));
+
+        assert_eq!(table.len(), 3);
+        assert!(table.contains("GET"));
+        assert_eq!(req("/x").pipe_dispatch(&table, "DELETE").unwrap(), "v2 DELETE /x");
+        assert_eq!(req("/x").pipe_dispatch(&table, "GET").unwrap(), "GET /x");
+        assert!(req("/x").pipe_dispatch(&table, "PUT").is_none());
+    }
+}
+
+// ============================================================================================
+// Bind tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1", feature = "2"))]
+mod bind_tests {
+    use core::cell::Cell;
+    use pipei::{PipeBind, PipeMonad};
+
+    fn checked_sub(x: u32, y: u32) -> Option<u32> {
+        x.checked_sub(y)
+    }
+
+    fn parse_clamped(s: &str, lo: i64, hi: i64) -> Result<i64, String> {
+        let v: i64 = s.parse().map_err(|_| format!("not a number: {s}"))?;
+        if (lo..=hi).contains(&v) { Ok(v) } else { Err(format!("{v} out of range")) }
+    }
+
+    #[test]
+    fn bind_option() {
+        assert_eq!(Some(10).pipe_bind(checked_sub)(3), Some(7));
+        assert_eq!(Some(1).pipe_bind(checked_sub)(3), None);
+        assert_eq!(Some(10).bind(|x| checked_sub(x, 4)), Some(6));
+    }
+
+    #[test]
+    fn bind_result_chain() {
+        let ok: Result<&str, String> = Ok("42");
+        assert_eq!(ok.pipe_bind(parse_clamped)(0, 100), Ok(42));
+
+        let out = Ok::<_, String>("500")
+            .pipe_bind(parse_clamped)(0, 1000)
+            .pipe_bind(|v: i64| if v % 2 == 0 { Ok(v / 2) } else { Err("odd".into()) })();
+        assert_eq!(out, Ok(250));
+    }
+
+    #[test]
+    fn bind_short_circuits() {
+        let calls = Cell::new(0);
+        let counting = |x: u32, y: u32| {
+            calls.set(calls.get() + 1);
+            x.checked_add(y)
+        };
+
+        assert_eq!(None.pipe_bind(counting)(1), None);
+        assert_eq!(calls.get(), 0);
+
+        let err: Result<&str, String> = Err("early".into());
+        assert_eq!(err.pipe_bind(parse_clamped)(0, 1), Err("early".to_string()));
+
+        let out = Ok::<_, String>("x").pipe_bind(parse_clamped)(0, 1);
+        assert_eq!(out, Err("not a number: x".to_string()));
+    }
+}
+
+// ============================================================================================
+// Branch tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod branch_tests {
+    use core::cell::Cell;
+    use pipei::PipeWith;
+
+    fn shout(s: String, suffix: &str) -> String {
+        s.to_uppercase() + suffix
+    }
+
+    fn whisper(s: String, suffix: &str) -> String {
+        s.to_lowercase() + suffix
+    }
+
+    #[test]
+    fn branch_takes_true_arm() {
+        let out = String::from("Hey").pipe_branch(|s| s.len() < 5, shout, whisper)("!");
+        assert_eq!(out, "HEY!");
+    }
+
+    #[test]
+    fn branch_takes_false_arm() {
+        let out = String::from("Hello there").pipe_branch(|s| s.len() < 5, shout, whisper)("!");
+        assert_eq!(out, "hello there!");
+    }
+
+    #[test]
+    fn branch_predicate_borrows_value() {
+        // The value is a non-`Copy` `String`: the predicate sees it by reference and the
+        // chosen arm still receives it by value.
+        let seen = Cell::new(0);
+        let pred = |s: &String| {
+            seen.set(s.len());
+            s.is_empty()
+        };
+        let out = String::from("abc").pipe_branch(pred, shout, whisper)("?");
+        assert_eq!(seen.get(), 3);
+        assert_eq!(out, "abc?");
+    }
+
+    #[test]
+    fn branch_runs_one_arm_only() {
+        let t = Cell::new(0);
+        let f = Cell::new(0);
+        let on_true = |x: i32, y: i32| { t.set(t.get() + 1); x + y };
+        let on_false = |x: i32, y: i32| { f.set(f.get() + 1); x - y };
+
+        assert_eq!(1.pipe_branch(|_| false, on_true, on_false)(1), 0);
+        assert_eq!((t.get(), f.get()), (0, 1));
+
+        let r = 5.pipe_branch(|x| *x > 0, |x: i32| x * 2, |x: i32| -x)();
+        assert_eq!(r, 10);
+    }
+
+    #[test]
+    #[cfg(feature = "either")]
+    fn branch_either_keeps_both_result_types() {
+        use either::Either;
+
+        fn length(s: String, _suffix: &str) -> usize {
+            s.len()
+        }
+
+        let seen = Cell::new(0);
+        let pred = |s: &String| {
+            seen.set(s.len());
+            s.len() < 5
+        };
+
+        let out = String::from("Hey").pipe_branch_either(pred, shout, length)("!");
+        assert_eq!(out, Either::Left(String::from("HEY!")));
+        let out = String::from("Hello there").pipe_branch_either(pred, shout, length)("!");
+        assert_eq!(out, Either::Right(11));
+        assert_eq!(seen.get(), 11);
+    }
+}
+
+// ============================================================================================
+// Versioned tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1", feature = "2"))]
+mod versioned_tests {
+    use pipei::Versioned;
+
+    fn add(x: i32, y: i32) -> i32 {
+        x + y
+    }
+
+    fn clamp(x: i32, lo: i32, hi: i32) -> i32 {
+        x.clamp(lo, hi)
+    }
+
+    fn stringify(x: i32) -> String {
+        x.to_string()
+    }
+
+    #[test]
+    fn versioned_five_stages() {
+        let mut seen = Vec::new();
+        let v = Versioned::new(1)
+            .pipe_epoch(add)(4)
+            .pipe_epoch(clamp)(0, 3)
+            .tap_epoch(|x: &i32| seen.push(*x))()
+            .pipe_epoch(add)(10)
+            .pipe_epoch(stringify)();
+
+        assert_eq!(v.version(), 5);
+        assert_eq!(seen, [3]);
+        assert_eq!(v.unwrap(), "13");
+    }
+
+    #[test]
+    fn versioned_starts_at_zero() {
+        let v = Versioned::new("x");
+        assert_eq!(v.version(), 0);
+        assert_eq!(Versioned::from(7).unwrap(), 7);
+    }
+
+    #[test]
+    fn tap_epoch_preserves_value() {
+        let v = Versioned::new(vec![1, 2]).tap_epoch(|v: &Vec<i32>, n: usize| assert_eq!(v.len(), n))(2);
+        assert_eq!(v.version(), 1);
+        assert_eq!(v.get(), &[1, 2]);
+    }
+}
+
+// ============================================================================================
+// Observer tests
+// ============================================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod observer_tests {
+    use core::cell::RefCell;
+    use pipei::Observable;
+
+    fn double(x: i32) -> i32 {
+        x * 2
+    }
+
+    fn add(x: i32, y: i32) -> i32 {
+        x + y
+    }
+
+    #[test]
+    fn observers_see_new_value() {
+        let first = RefCell::new(Vec::new());
+        let second = RefCell::new(Vec::new());
+
+        let obs = Observable::new(5)
+            .on(|x| first.borrow_mut().push(*x))
+            .on(|x| second.borrow_mut().push(*x * 100));
+        assert_eq!(obs.observer_count(), 2);
+
+        let obs = obs.pipe_observer(double)();
+        assert_eq!(*obs.get(), 10);
+        assert_eq!(*first.borrow(), [10]);
+        assert_eq!(*second.borrow(), [1000]);
+
+        let obs = obs.pipe_observer(add)(1);
+        assert_eq!(obs.into_inner(), 11);
+        assert_eq!(*first.borrow(), [10, 11]);
+    }
+
+    #[test]
+    fn no_notification_without_stage() {
+        let calls = RefCell::new(0);
+        let obs = Observable::new(1).on(|_| *calls.borrow_mut() += 1);
+        assert_eq!(*obs.get(), 1);
+        assert_eq!(*calls.borrow(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn shared_observable_across_threads() {
+        use pipei::SharedObservable;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        fn bump(x: &u64, by: u64) -> u64 {
+            x + by
+        }
+
+        let notified = Arc::new(AtomicUsize::new(0));
+        let obs = SharedObservable::new(0u64);
+        let n = Arc::clone(&notified);
+        obs.on(move |_| {
+            n.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let o = obs.clone();
+                std::thread::spawn(move || {
+                    o.pipe_observer(bump)(5);
+                })
+            })
+            .collect();
+        for h in handles {
+            h.join().unwrap();
+        }
+
+        assert_eq!(obs.get(), 20);
+        assert_eq!(notified.load(Ordering::SeqCst), 4);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn shared_observer_can_read_the_observable() {
+        use pipei::SharedObservable;
+        use std::sync::{Arc, Mutex};
+
+        fn add(x: &i32, y: i32) -> i32 {
+            x + y
+        }
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let obs = SharedObservable::new(1);
+        let (reader, sink) = (obs.clone(), Arc::clone(&seen));
+        obs.on(move |x| sink.lock().unwrap().push((*x, reader.get())));
+
+        obs.clone().pipe_observer(add)(2);
+        assert_eq!(*seen.lock().unwrap(), [(3, 3)]);
+    }
+}
+
+// ============================================================================================
+// Schema validation tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod validate_tests {
+    use core::cell::Cell;
+    use pipei::{PipeValidate, Validate, ValidationError};
+
+    #[derive(Debug)]
+    struct Config {
+        port: u16,
+    }
+
+    /// A schema checking each field of `Config` against its bounds.
+    struct ConfigSchema;
+
+    impl Validate<Config> for ConfigSchema {
+        fn validate(&self, value: &Config) -> Result<(), ValidationError> {
+            (1..=65535).validate(&value.port).map_err(|e| e.with_field("port"))
+        }
+    }
+
+    fn bind_addr(c: Config, host: &str) -> String {
+        format!("{host}:{}", c.port)
+    }
+
+    #[test]
+    fn valid_config_passes() {
+        let out = Config { port: 8080 }.pipe_with_schema(ConfigSchema, bind_addr)("localhost");
+        assert_eq!(out.as_deref(), Ok("localhost:8080"));
+    }
+
+    #[test]
+    fn invalid_config_short_circuits() {
+        let calls = Cell::new(0);
+        let f = |c: Config, _: i32| {
+            calls.set(calls.get() + 1);
+            c.port
+        };
+        let err = Config { port: 0 }.pipe_with_schema(ConfigSchema, f)(1).unwrap_err();
+        assert_eq!(calls.get(), 0);
+        assert_eq!(err.field(), Some("port"));
+        assert_eq!(err.reason(), "below minimum");
+        assert_eq!(err.to_string(), "invalid `port`: below minimum");
+    }
+
+    #[cfg(feature = "macros")]
+    mod derived {
+        use pipei::{FieldSchema, PipeValidate, Validate};
+
+        #[derive(Debug, Validate)]
+        struct Config {
+            #[validate(min = 1, max = 65535)]
+            port: u16,
+        }
+
+        #[derive(Validate)]
+        struct Limits<T: PartialOrd + From<u8>> {
+            #[validate(min = T::from(1))]
+            low: T,
+            #[validate(max = T::from(100))]
+            high: T,
+            #[allow(dead_code)]
+            unchecked: T,
+        }
+
+        fn bind_addr(c: Config, host: &str) -> String {
+            format!("{host}:{}", c.port)
+        }
+
+        #[test]
+        fn derived_schema_accepts_valid_config() {
+            let out = Config { port: 8080 }.pipe_with_schema(FieldSchema, bind_addr)("localhost");
+            assert_eq!(out.as_deref(), Ok("localhost:8080"));
+            assert!(FieldSchema.validate(&Config { port: 65535 }).is_ok());
+        }
+
+        #[test]
+        fn derived_schema_short_circuits_invalid_config() {
+            let calls = core::cell::Cell::new(0);
+            let f = |c: Config| {
+                calls.set(calls.get() + 1);
+                c.port
+            };
+            let err = Config { port: 0 }.pipe_with_schema(FieldSchema, f)().unwrap_err();
+            assert_eq!(calls.get(), 0);
+            assert_eq!(err.to_string(), "invalid `port`: below minimum");
+        }
+
+        #[test]
+        fn derived_schema_with_one_sided_bounds() {
+            assert!(FieldSchema.validate(&Limits { low: 1u32, high: 100, unchecked: 0 }).is_ok());
+            let err = FieldSchema.validate(&Limits { low: 0u32, high: 0, unchecked: 0 }).unwrap_err();
+            assert_eq!((err.field(), err.reason()), (Some("low"), "below minimum"));
+            let err = FieldSchema.validate(&Limits { low: 5.0, high: 100.5, unchecked: -1.0 }).unwrap_err();
+            assert_eq!((err.field(), err.reason()), (Some("high"), "above maximum"));
+        }
+    }
+
+    #[test]
+    fn closure_schema() {
+        let even = |x: &i32| if x % 2 == 0 { Ok(()) } else { Err(ValidationError::new("odd")) };
+        assert_eq!(4.pipe_with_schema(even, |x: i32| x / 2)(), Ok(2));
+        assert_eq!(3.pipe_with_schema(even, |x: i32| x / 2)(), Err(ValidationError::new("odd")));
+    }
+}
+
+// ============================================================================================
+// Authorization tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod authorization_tests {
+    use core::cell::Cell;
+    use pipei::{AuthError, Capability, DenyAll, PermitAll, PipeAuthorize};
+
+    struct Roles(&'static [&'static str]);
+
+    impl Capability for Roles {
+        fn permits(&self, operation: &str) -> Result<(), AuthError> {
+            if self.0.contains(&operation) { Ok(()) } else { Err(AuthError::new("missing role")) }
+        }
+    }
+
+    /// Records when it is dropped, to check that the value is consumed.
+    struct Tracked<'a>(i32, &'a Cell<bool>);
+
+    impl Drop for Tracked<'_> {
+        fn drop(&mut self) {
+            self.1.set(true);
+        }
+    }
+
+    #[test]
+    fn permitted_calls_f() {
+        let dropped = Cell::new(false);
+        let out = Tracked(4, &dropped).pipe_with_authorization(PermitAll, "read", |t: Tracked, k: i32| t.0 * k)(3);
+        assert_eq!(out, Ok(12));
+        assert!(dropped.get());
+    }
+
+    #[test]
+    fn denied_skips_f() {
+        let dropped = Cell::new(false);
+        let calls = Cell::new(0);
+        let f = |t: Tracked, k: i32| {
+            calls.set(calls.get() + 1);
+            t.0 * k
+        };
+        let out = Tracked(4, &dropped).pipe_with_authorization(DenyAll, "read", f)(3);
+        assert_eq!(out, Err(AuthError::new("all operations denied")));
+        assert_eq!(calls.get(), 0);
+        assert!(dropped.get());
+    }
+
+    #[test]
+    fn dyn_capability_by_operation() {
+        let caps: &dyn Capability = &Roles(&["read"]);
+        assert_eq!(10.pipe_with_authorization(caps, "read", |x: i32| x + 1)(), Ok(11));
+        let err = 10.pipe_with_authorization(caps, "delete", |x: i32| x + 1)().unwrap_err();
+        assert_eq!(err.to_string(), "not authorized: missing role");
+    }
+}
+
+// ============================================================================================
+// Circuit breaker tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod circuit_breaker_tests {
+    use core::cell::Cell;
+    use pipei::{CircuitBreaker, CircuitBreakerConfig, CircuitOpen, CircuitState, PipeCircuitBreaker};
+    use std::time::Duration;
+
+    fn checked(x: i32, fail: bool) -> Result<i32, &'static str> {
+        if fail { Err("boom") } else { Ok(x) }
+    }
+
+    fn breaker(reset_timeout: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig { failure_threshold: 5, reset_timeout })
+    }
+
+    #[test]
+    fn trips_after_threshold() {
+        let cb = breaker(Duration::from_secs(60));
+        for i in 0..5 {
+            assert_eq!(cb.state(), CircuitState::Closed, "attempt {i}");
+            assert_eq!(1.pipe_with_circuit_breaker(&cb, checked)(true), Ok(Err("boom")));
+        }
+        assert_eq!(cb.state(), CircuitState::Open);
+
+        let calls = Cell::new(0);
+        let f = |x: i32, _: bool| -> Result<i32, &str> {
+            calls.set(calls.get() + 1);
+            Ok(x)
+        };
+        assert_eq!(1.pipe_with_circuit_breaker(&cb, f)(false), Err(CircuitOpen));
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn success_resets_failure_count() {
+        let cb = breaker(Duration::from_secs(60));
+        for _ in 0..4 {
+            let _ = 1.pipe_with_circuit_breaker(&cb, checked)(true);
+        }
+        assert_eq!(cb.failure_count(), 4);
+        assert_eq!(2.pipe_with_circuit_breaker(&cb, checked)(false), Ok(Ok(2)));
+        assert_eq!(cb.failure_count(), 0);
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn recovers_after_reset_timeout() {
+        let cb = breaker(Duration::from_millis(50));
+        for _ in 0..5 {
+            let _ = 1.pipe_with_circuit_breaker(&cb, checked)(true);
+        }
+        assert_eq!(cb.state(), CircuitState::Open);
+
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+        assert_eq!(7.pipe_with_circuit_breaker(&cb, checked)(false), Ok(Ok(7)));
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn half_open_failure_reopens() {
+        let cb = breaker(Duration::from_millis(50));
+        for _ in 0..5 {
+            let _ = 1.pipe_with_circuit_breaker(&cb, checked)(true);
+        }
+        std::thread::sleep(Duration::from_millis(80));
+        assert_eq!(1.pipe_with_circuit_breaker(&cb, checked)(true), Ok(Err("boom")));
+        assert_eq!(cb.state(), CircuitState::Open);
+    }
+}
+
+// ============================================================================================
+// Retry tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod retry_tests {
+    use core::cell::Cell;
+    use pipei::{BackoffPolicy, PipeRetry, RetryExhausted, RetryStrategy};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn immediate_makes_n_attempts() {
+        let strategy = RetryStrategy { max_attempts: 4, backoff: BackoffPolicy::Immediate };
+        let calls = Cell::new(0);
+        let always_fail = |x: &i32, tag: String| -> Result<i32, String> {
+            calls.set(calls.get() + 1);
+            Err(format!("{tag}{x}"))
+        };
+
+        let start = Instant::now();
+        let out = 5.pipe_with_retry_strategy(&strategy, always_fail)("e".to_string());
+        assert!(start.elapsed() < Duration::from_millis(50));
+        assert_eq!(calls.get(), 4);
+        assert_eq!(out, Err(RetryExhausted { attempts: 4, last_error: "e5".to_string() }));
+    }
+
+    #[test]
+    fn constant_waits_between_attempts() {
+        let strategy = RetryStrategy { max_attempts: 3, backoff: BackoffPolicy::Constant(Duration::from_millis(100)) };
+        let calls = Cell::new(0);
+        let f = |_: &(), n: u32| -> Result<(), u32> {
+            calls.set(calls.get() + 1);
+            Err(n)
+        };
+
+        let start = Instant::now();
+        let out = ().pipe_with_retry_strategy(&strategy, f)(9);
+        assert!(start.elapsed() >= Duration::from_millis(200));
+        assert_eq!(calls.get(), 3);
+        assert_eq!(out.unwrap_err().attempts, 3);
+    }
+
+    #[test]
+    fn stops_on_first_success() {
+        let strategy = RetryStrategy { max_attempts: 10, backoff: BackoffPolicy::Immediate };
+        let calls = Cell::new(0);
+        let f = |x: &i32, _: ()| -> Result<i32, ()> {
+            calls.set(calls.get() + 1);
+            if calls.get() < 2 { Err(()) } else { Ok(x * 2) }
+        };
+        assert_eq!(21.pipe_with_retry_strategy(&strategy, f)(()), Ok(42));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn exponential_doubles_up_to_max() {
+        let policy = BackoffPolicy::Exponential { base: Duration::from_millis(10), max_delay: Duration::from_millis(50) };
+        let delays: Vec<_> = (1..=5).map(|a| policy.delay(a).as_millis()).collect();
+        assert_eq!(delays, [10, 20, 40, 50, 50]);
+        assert_eq!(policy.delay(u32::MAX), Duration::from_millis(50));
+    }
+}
+
+// ============================================================================================
+// Rate limit tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod rate_limit_tests {
+    use pipei::{PipeRateLimit, RateLimited, RateLimiter};
+    use std::time::Duration;
+
+    fn square(x: u64, _tag: &str) -> u64 {
+        x * x
+    }
+
+    fn burst(limiter: &RateLimiter) -> usize {
+        (0..100u64).filter(|&i| i.pipe_with_rate_limit(limiter, square)("req").is_ok()).count()
+    }
+
+    #[test]
+    fn limits_rapid_calls() {
+        let limiter = RateLimiter::new(10.0);
+        let ok = burst(&limiter);
+        assert!((10..=11).contains(&ok), "{ok} calls succeeded");
+        assert_eq!(3.pipe_with_rate_limit(&limiter, square)("req"), Err(RateLimited));
+    }
+
+    #[test]
+    fn rate_restored_after_sleep() {
+        let limiter = RateLimiter::new(10.0);
+        burst(&limiter);
+        std::thread::sleep(Duration::from_secs(1));
+        let ok = burst(&limiter);
+        assert!((10..=11).contains(&ok), "{ok} calls succeeded");
+    }
+
+    #[test]
+    fn shared_across_threads() {
+        let limiter = RateLimiter::new(20.0);
+        let ok: usize = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4).map(|_| s.spawn(|| burst(&limiter))).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+        assert!((20..=22).contains(&ok), "{ok} calls succeeded");
+    }
+}
+
+// ============================================================================================
+// Sequence tests
+// ============================================================================================
+
+mod seq_tests {
+    use pipei::PipeSeq;
+
+    fn add_one(x: i32) -> i32 {
+        x + 1
+    }
+
+    fn double(x: i32) -> i32 {
+        x * 2
+    }
+
+    fn negate(x: i32) -> i32 {
+        -x
+    }
+
+    #[test]
+    fn seq_applies_in_order() {
+        assert_eq!(3.pipe_seq([add_one, double, negate]), -8);
+        assert_eq!(3.pipe_seq([negate, double, add_one]), -5);
+    }
+
+    #[test]
+    fn seq_empty_is_identity() {
+        let fs: [fn(i32) -> i32; 0] = [];
+        assert_eq!(7.pipe_seq(fs), 7);
+    }
+
+    #[test]
+    fn sequence_fallible_stops_at_first_err() {
+        use core::cell::RefCell;
+
+        let calls = RefCell::new(Vec::new());
+        let stage = |n: usize, fail: bool| {
+            let calls = &calls;
+            move |x: i32| {
+                calls.borrow_mut().push((n, x));
+                if fail { Err(format!("stage {n} rejected {x}")) } else { Ok(x + 1) }
+            }
+        };
+        type Stage<'a> = Box<dyn FnOnce(i32) -> Result<i32, String> + 'a>;
+        let stages: [Stage; 4] =
+            [Box::new(stage(1, false)), Box::new(stage(2, true)), Box::new(stage(3, false)), Box::new(stage(4, false))];
+
+        assert_eq!(10.pipe_sequence_fallible(stages), Err("stage 2 rejected 11".to_string()));
+        assert_eq!(*calls.borrow(), [(1, 10), (2, 11)]);
+    }
+
+    #[test]
+    fn sequence_fallible_all_ok() {
+        fn checked_double(x: i32) -> Result<i32, &'static str> {
+            x.checked_mul(2).ok_or("overflow")
+        }
+        assert_eq!(1.pipe_sequence_fallible([checked_double; 3]), Ok(8));
+        assert_eq!(i32::MAX.pipe_sequence_fallible([checked_double; 3]), Err("overflow"));
+
+        type Stage = Box<dyn FnOnce(i32) -> Result<i32, &'static str>>;
+        let dynamic: Vec<Stage> = vec![Box::new(checked_double), Box::new(|x| Ok(x - 1))];
+        assert_eq!(5.pipe_sequence_fallible_dyn(dynamic), Ok(9));
+    }
+
+    #[test]
+    fn seq_dyn_applies_in_order() {
+        let offset = 10;
+        let stages: Vec<Box<dyn Fn(i32) -> i32>> =
+            vec![Box::new(add_one), Box::new(double), Box::new(move |x| x - offset), Box::new(negate)];
+        assert_eq!(3.pipe_seq_dyn(stages), 2);
+        assert_eq!(3.pipe_seq_dyn(Vec::<fn(i32) -> i32>::new()), 3);
+    }
+}
+
+// ============================================================================================
+// Deadline tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod deadline_tests {
+    use core::cell::Cell;
+    use pipei::{DeadlineExceeded, PipeDeadline};
+    use std::time::{Duration, Instant};
+
+    fn add(x: i32, y: i32) -> i32 {
+        x + y
+    }
+
+    #[test]
+    fn past_deadline_skips_f() {
+        let calls = Cell::new(0);
+        let f = |x: i32, y: i32| {
+            calls.set(calls.get() + 1);
+            x + y
+        };
+        let past = Instant::now() - Duration::from_millis(1);
+        assert_eq!(1.pipe_with_deadline(past, f)(2), Err(DeadlineExceeded));
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn future_deadline_succeeds() {
+        let deadline = Instant::now() + Duration::from_secs(3600);
+        let out = 1
+            .pipe_with_deadline(deadline, add)(2)
+            .and_then(|x| x.pipe_with_deadline(deadline, add)(3));
+        assert_eq!(out, Ok(6));
+    }
+
+    #[test]
+    fn deadline_expires_during_stage() {
+        let deadline = Instant::now() + Duration::from_millis(20);
+        let slow = |x: i32, ms: u64| {
+            std::thread::sleep(Duration::from_millis(ms));
+            x
+        };
+        assert_eq!(1.pipe_with_deadline(deadline, slow)(60), Err(DeadlineExceeded));
+    }
+}
+
+// ============================================================================================
+// Pipeline graph tests
+// ============================================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod pipeline_graph_tests {
+    use pipei::PipelineGraph;
+
+    fn parse(s: &str) -> Result<i32, String> {
+        s.parse().map_err(|_| format!("bad input: {s}"))
+    }
+
+    fn validate(r: &Result<i32, String>, max: i32) {
+        assert!(matches!(r, Ok(v) if *v <= max));
+    }
+
+    fn transform(r: Result<i32, String>, k: i32) -> i32 {
+        r.unwrap() * k
+    }
+
+    fn serialize(x: i32) -> String {
+        format!("{{\"value\":{x}}}")
+    }
+
+    #[test]
+    fn graph_traces_five_stages() {
+        let graph = PipelineGraph::new("start", "21")
+            .pipe_named("parse", parse)()
+            .tap_named("validate", validate)(100)
+            .pipe_named("transform", transform)(2)
+            .pipe_named("serialize", serialize)();
+        assert_eq!(graph.get(), r#"{"value":42}"#);
+
+        let (value, traced_names) = graph.into_traced();
+        assert_eq!(value, r#"{"value":42}"#);
+        #[cfg(debug_assertions)]
+        assert_eq!(traced_names, ["start", "parse", "validate", "transform", "serialize"]);
+        #[cfg(not(debug_assertions))]
+        assert!(traced_names.is_empty());
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[test]
+    fn graph_is_transparent_in_release() {
+        assert_eq!(size_of::<PipelineGraph<i32>>(), size_of::<i32>());
+        assert!(PipelineGraph::new("start", 1).pipe_named("x", |x: i32| x)().names().is_empty());
+    }
+}
+
+// ============================================================================================
+// Cache tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod cache_tests {
+    use core::cell::Cell;
+    use pipei::PipeCache;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn same_key_calls_f_once() {
+        let calls = Cell::new(0);
+        let slow_square = |x: u64, _salt: u8| {
+            calls.set(calls.get() + 1);
+            x * x
+        };
+        let key = |x: &u64, salt: &u8| (*x, *salt);
+
+        let mut cache = HashMap::new();
+        for _ in 0..5 {
+            assert_eq!(12.pipe_with_cache(&mut cache, key, slow_square)(1), 144);
+        }
+        assert_eq!(calls.get(), 1);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn different_keys_get_entries() {
+        let calls = Cell::new(0);
+        let f = |x: u64, _: u8| {
+            calls.set(calls.get() + 1);
+            x + 1
+        };
+        let key = |x: &u64, salt: &u8| (*x, *salt);
+
+        let mut cache = HashMap::new();
+        assert_eq!(1.pipe_with_cache(&mut cache, key, f)(0), 2);
+        assert_eq!(2.pipe_with_cache(&mut cache, key, f)(0), 3);
+        assert_eq!(1.pipe_with_cache(&mut cache, key, f)(9), 2);
+        assert_eq!(1.pipe_with_cache(&mut cache, key, f)(0), 2);
+        assert_eq!(calls.get(), 3);
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn shared_cache_across_threads() {
+        fn upper(s: String) -> String {
+            s.to_uppercase()
+        }
+
+        let cache: Arc<Mutex<HashMap<String, String>>> = Arc::default();
+        let results: Vec<String> = std::thread::scope(|s| {
+            let handles: Vec<_> = ["a", "b", "a"]
+                .into_iter()
+                .map(|w| s.spawn(|| w.to_string().pipe_with_shared_cache(&cache, |s: &String| s.clone(), upper)()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(results, ["A", "B", "A"]);
+        assert_eq!(cache.lock().unwrap().len(), 2);
+    }
+}
+
+// ============================================================================================
+// Audit log tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod audit_tests {
+    use core::hash::{Hash, Hasher};
+    use pipei::{AuditLog, PipeAudit};
+    use std::collections::hash_map::DefaultHasher;
+    use std::time::SystemTime;
+
+    fn parse(s: String, radix: u32) -> Result<u32, String> {
+        u32::from_str_radix(&s, radix).map_err(|e| e.to_string())
+    }
+
+    fn double(x: u32, max: u32) -> Result<u32, String> {
+        let y = x * 2;
+        if y <= max { Ok(y) } else { Err(format!("{y} > {max}")) }
+    }
+
+    fn hash_of<T: Hash>(v: &T) -> u64 {
+        let mut h = DefaultHasher::new();
+        v.hash(&mut h);
+        h.finish()
+    }
+
+    #[test]
+    fn three_operation_pipeline() {
+        let mut log = AuditLog::new();
+        let before = SystemTime::now();
+
+        let out = "ff"
+            .to_string()
+            .pipe_with_audit_log(&mut log, "parse", parse)(16)
+            .and_then(|x| x.pipe_with_audit_log(&mut log, "double", double)(1000))
+            .and_then(|x| x.pipe_with_audit_log(&mut log, "double", double)(1000));
+        let after = SystemTime::now();
+
+        assert_eq!(out, Err("1020 > 1000".to_string()));
+        let entries = log.entries();
+        let ops: Vec<_> = entries.iter().map(|e| (e.operation, e.success)).collect();
+        assert_eq!(ops, [("parse", true), ("double", true), ("double", false)]);
+
+        assert_eq!(entries[0].value_hash, hash_of(&"ff".to_string()));
+        assert_eq!(entries[1].value_hash, hash_of(&255u32));
+        for pair in entries.windows(2) {
+            assert!(pair[0].timestamp <= pair[1].timestamp);
+        }
+        assert!(entries.iter().all(|e| before <= e.timestamp && e.timestamp <= after));
+    }
+}
+
+// ============================================================================================
+// Reference count tests
+// ============================================================================================
+
+#[cfg(feature = "alloc")]
+mod ref_count_tests {
+    use pipei::{TapArcRefCount, TapRefCount};
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    #[test]
+    fn rc_count_after_three_clones() {
+        let rc = Rc::new(5);
+        let clones = [Rc::clone(&rc), Rc::clone(&rc), Rc::clone(&rc)];
+
+        let mut log = Vec::new();
+        let rc = rc.tap_observe_ref_count(&mut log);
+        drop(clones);
+        let rc = rc.tap_observe_ref_count(&mut log).tap_assert_ref_count(1);
+
+        assert_eq!(log, [4, 1]);
+        assert_eq!(*rc, 5);
+    }
+
+    #[test]
+    fn arc_count_across_threads() {
+        let shared = Arc::new(vec![1, 2, 3]);
+        let worker = Arc::clone(&shared);
+        let handle = std::thread::spawn(move || worker.tap_assert_ref_count(2).len());
+
+        assert_eq!(handle.join().unwrap(), 3);
+        let mut log = Vec::new();
+        shared.tap_observe_ref_count(&mut log);
+        assert_eq!(log, [1]);
+    }
+
+    #[test]
+    fn arc_count_after_three_clones() {
+        let arc = Arc::new(5);
+        let clones = [Arc::clone(&arc), Arc::clone(&arc), Arc::clone(&arc)];
+
+        let mut log = Vec::new();
+        let arc = arc.tap_observe_arc_ref_count(&mut log);
+        drop(clones);
+        arc.tap_observe_arc_ref_count(&mut log);
+
+        assert_eq!(log, [4, 1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "unexpected strong count")]
+    fn assert_ref_count_mismatch_panics() {
+        let a = Rc::new(());
+        let _b = Rc::clone(&a);
+        a.tap_assert_ref_count(1);
+    }
+}
+
+// ============================================================================================
+// Profiler tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod profiler_tests {
+    use pipei::{PipeProfile, Profiler, SimpleProfiler};
+    use std::time::Duration;
+
+    /// Panics if any stage runs longer than its budget.
+    struct BudgetProfiler {
+        budget_ns: u64,
+        started: Vec<String>,
+        finished: Vec<(String, u64)>,
+    }
+
+    impl Profiler for BudgetProfiler {
+        fn before_call(&mut self, name: &str) {
+            self.started.push(name.to_string());
+        }
+
+        fn after_call(&mut self, name: &str, duration_ns: u64) {
+            assert!(duration_ns <= self.budget_ns, "stage `{name}` over budget: {duration_ns}ns");
+            self.finished.push((name.to_string(), duration_ns));
+        }
+    }
+
+    fn add(x: u64, y: u64) -> u64 {
+        x + y
+    }
+
+    fn nap(x: u64, ms: u64) -> u64 {
+        std::thread::sleep(Duration::from_millis(ms));
+        x
+    }
+
+    #[test]
+    fn custom_profiler_sees_names() {
+        let mut p = BudgetProfiler { budget_ns: 5_000_000_000, started: vec![], finished: vec![] };
+        let dynp: &mut dyn Profiler = &mut p;
+        let x = 1.pipe_with_profiler("add", dynp, add)(2);
+        let x = x.pipe_with_profiler("nap", &mut p, nap)(5);
+
+        assert_eq!(x, 3);
+        assert_eq!(p.started, ["add", "nap"]);
+        let names: Vec<_> = p.finished.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["add", "nap"]);
+        assert!(p.finished[1].1 >= 5_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "stage `slow` over budget")]
+    fn custom_profiler_enforces_budget() {
+        let mut p = BudgetProfiler { budget_ns: 1_000_000, started: vec![], finished: vec![] };
+        1.pipe_with_profiler("slow", &mut p, nap)(20);
+    }
+
+    #[test]
+    fn simple_profiler_records() {
+        let mut p = SimpleProfiler::new();
+        let name = String::from("stage-1");
+        let x = 10
+            .pipe_with_profiler(&name, &mut p, add)(1)
+            .pipe_with_profiler("stage-2", &mut p, add)(1);
+        assert_eq!(x, 12);
+        let names: Vec<_> = p.records().iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["stage-1", "stage-2"]);
+    }
+}
+
+// ============================================================================================
+// Bimap tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod bimap_tests {
+    use core::cell::Cell;
+    use pipei::PipeBimap;
+
+    fn describe(x: i32, prefix: &str) -> String {
+        format!("{prefix}{x}")
+    }
+
+    fn err_len(e: &str, prefix: &str) -> i32 {
+        (prefix.len() + e.len()) as i32
+    }
+
+    #[test]
+    fn bimap_ok_path() {
+        let r: Result<i32, &str> = Ok(7);
+        let out: Result<String, i32> = r.pipe_bimap(describe, err_len)("#");
+        assert_eq!(out, Ok("#7".to_string()));
+    }
+
+    #[test]
+    fn bimap_err_path() {
+        let r: Result<i32, &str> = Err("bad");
+        assert_eq!(r.pipe_bimap(describe, err_len)("#"), Err(4));
+    }
+
+    #[test]
+    fn bimap_calls_one_side() {
+        let ok_calls = Cell::new(0);
+        let err_calls = Cell::new(0);
+        let ok_fn = |x: i32| {
+            ok_calls.set(ok_calls.get() + 1);
+            x
+        };
+        let err_fn = |e: &str| {
+            err_calls.set(err_calls.get() + 1);
+            e.len()
+        };
+        let _ = Ok::<i32, &str>(1).pipe_bimap(ok_fn, err_fn)();
+        let _ = Err::<i32, &str>("x").pipe_bimap(ok_fn, err_fn)();
+        assert_eq!((ok_calls.get(), err_calls.get()), (1, 1));
+    }
+
+    #[test]
+    fn bimap_with_identity_is_one_sided_map() {
+        fn keep<T>(x: T) -> T {
+            x
+        }
+        let ok: Result<i32, &str> = Ok(3);
+        let err: Result<i32, &str> = Err("e");
+
+        assert_eq!(ok.pipe_bimap(|x: i32| x * 2, keep)(), ok.map(|x| x * 2));
+        assert_eq!(err.pipe_bimap(|x: i32| x * 2, keep)(), err.map(|x| x * 2));
+        assert_eq!(err.pipe_bimap(keep, str::len)(), err.map_err(str::len));
+    }
+
+    #[test]
+    #[cfg(feature = "either")]
+    fn bimap_either_maps_each_side() {
+        use either::Either;
+
+        let left: Either<i32, &str> = Either::Left(7);
+        let right: Either<i32, &str> = Either::Right("bad");
+        assert_eq!(left.pipe_bimap(describe, err_len)("#"), Either::Left("#7".to_string()));
+        assert_eq!(right.pipe_bimap(describe, err_len)("#"), Either::Right(4));
+    }
+}
+
+// ============================================================================================
+// Collect-into tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod collect_into_tests {
+    use pipei::PipeCollection;
+    use std::collections::BTreeSet;
+
+    fn identity(x: &u32) -> u32 {
+        *x
+    }
+
+    #[test]
+    fn accumulate_three_slices() {
+        let a = [1, 2, 3];
+        let b = [4, 5];
+        let c = [6, 7, 8, 9];
+
+        let mut all = Vec::new();
+        a.iter().pipe_map_collect_into(&mut all, identity)();
+        b.iter().pipe_map_collect_into(&mut all, identity)();
+        c.iter().pipe_map_collect_into(&mut all, identity)();
+
+        assert_eq!(all.len(), a.len() + b.len() + c.len());
+        assert_eq!(all, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn collect_into_with_args() {
+        let mut words = String::new();
+        ["a", "b"].pipe_map_collect_into(&mut words, |w: &str, sep: char| format!("{w}{sep}"))(',');
+        assert_eq!(words, "a,b,");
+
+        let mut set = BTreeSet::new();
+        vec![3, 1, 3].pipe_map_collect_into(&mut set, |x: i32, k: i32| x % k)(2);
+        assert_eq!(set.into_iter().collect::<Vec<_>>(), [1]);
+    }
+}
+
+// ============================================================================================
+// Backpressure tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod backpressure_tests {
+    use pipei::PipeBackpressure;
+    use std::sync::mpsc::sync_channel;
+    use std::time::{Duration, Instant};
+
+    fn tag(x: u32, prefix: &str) -> String {
+        format!("{prefix}{x}")
+    }
+
+    #[test]
+    fn bounded_channel_blocks_producer() {
+        let (tx, rx) = sync_channel(1);
+        let consumer = std::thread::spawn(move || {
+            let mut got = Vec::new();
+            for v in rx {
+                std::thread::sleep(Duration::from_millis(20));
+                got.push(v);
+            }
+            got
+        });
+
+        let start = Instant::now();
+        let out: Vec<_> = (0..5).map(|i| i.pipe_with_sync_backpressure(&tx, tag)("#").unwrap()).collect();
+        let elapsed = start.elapsed();
+        drop(tx);
+
+        assert_eq!(out, ["#0", "#1", "#2", "#3", "#4"]);
+        assert_eq!(consumer.join().unwrap(), [0, 1, 2, 3, 4]);
+        // With room for one buffered value, the producer waits for the slow consumer.
+        assert!(elapsed >= Duration::from_millis(40), "producer never blocked: {elapsed:?}");
+    }
+
+    #[test]
+    fn disconnected_receiver_skips_f() {
+        let (tx, rx) = sync_channel::<u32>(1);
+        drop(rx);
+        let err = 7.pipe_with_sync_backpressure(&tx, tag)("#").unwrap_err();
+        assert_eq!(err.0, 7);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tokio_channel_makes_producer_wait() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let consumer = tokio::spawn(async move {
+            let mut got = Vec::new();
+            while let Some(v) = rx.recv().await {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                got.push(v);
+            }
+            got
+        });
+
+        let start = Instant::now();
+        let mut out = Vec::new();
+        for i in 0..5 {
+            out.push(i.pipe_with_backpressure(&tx, tag)("#").await.unwrap());
+        }
+        let elapsed = start.elapsed();
+        drop(tx);
+
+        assert_eq!(out, ["#0", "#1", "#2", "#3", "#4"]);
+        assert_eq!(consumer.await.unwrap(), [0, 1, 2, 3, 4]);
+        // With room for one buffered value, the producer waits for the slow consumer.
+        assert!(elapsed >= Duration::from_millis(40), "producer never waited: {elapsed:?}");
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tokio_closed_channel_skips_f() {
+        let (tx, rx) = tokio::sync::mpsc::channel::<u32>(1);
+        drop(rx);
+        let err = 7.pipe_with_backpressure(&tx, tag)("#").await.unwrap_err();
+        assert_eq!(err.0, 7);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tokio_full_channel_times_out() {
+        use tokio::sync::mpsc::error::SendTimeoutError;
+
+        let calls = std::cell::Cell::new(0);
+        let f = |x: u32, prefix: &str| {
+            calls.set(calls.get() + 1);
+            tag(x, prefix)
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let timeout = Duration::from_millis(10);
+
+        assert_eq!(1.pipe_with_backpressure_bounded(&tx, timeout, f)("#").await.as_deref(), Ok("#1"));
+        let full = 2.pipe_with_backpressure_bounded(&tx, timeout, f)("#").await;
+        assert!(matches!(full, Err(SendTimeoutError::Timeout(2))));
+        assert_eq!(calls.get(), 1);
+
+        assert_eq!(rx.recv().await, Some(1));
+        assert_eq!(3.pipe_with_backpressure_bounded(&tx, timeout, f)("#").await.as_deref(), Ok("#3"));
+    }
+}
+
+// ============================================================================================
+// While-ok tests
+// ============================================================================================
+
+#[cfg(all(feature = "1", feature = "2"))]
+mod while_ok_tests {
+    use core::cell::Cell;
+    use pipei::PipeWhileOk;
+    use std::io::{BufRead, Cursor};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct State {
+        pos: u64,
+        lines: Vec<String>,
+    }
+
+    /// Reads the next line of `src` into the state, failing at end of input.
+    fn read_line(mut s: State, src: &str) -> Result<State, String> {
+        let mut cur = Cursor::new(src);
+        cur.set_position(s.pos);
+        let mut line = String::new();
+        match cur.read_line(&mut line) {
+            Ok(0) => Err("EOF".into()),
+            Ok(_) => {
+                s.pos = cur.position();
+                s.lines.push(line.trim_end().to_string());
+                Ok(s)
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    #[test]
+    fn reads_until_eof() {
+        let state = State { pos: 0, lines: vec![] };
+        let out = state.pipe_while_ok(read_line)("alpha\nbeta\ngamma\n").unwrap();
+        assert_eq!(out.lines, ["alpha", "beta", "gamma"]);
+    }
+
+    #[test]
+    fn first_failure_is_returned() {
+        let state = State { pos: 0, lines: vec![] };
+        assert_eq!(state.pipe_while_ok(read_line)(""), Err("EOF".to_string()));
+    }
+
+    #[test]
+    fn call_count_includes_failing_call() {
+        let calls = Cell::new(0);
+        let f = |x: i32, step: i32, stop: i32| {
+            calls.set(calls.get() + 1);
+            if x + step > stop { Err(x) } else { Ok(x + step) }
+        };
+        assert_eq!(0.pipe_while_ok(f)(3, 10), Ok(9));
+        assert_eq!(calls.get(), 4);
+    }
+}
+
+// ============================================================================================
+// Transaction tests
+// ============================================================================================
+
+#[cfg(all(feature = "db", feature = "1"))]
+mod transaction_tests {
+    use core::cell::Cell;
+    use pipei::db::{MockConnection, MockTx, PipeTransaction, TxError};
+
+    fn insert_all(_: &mut MockConnection, tx: &mut MockTx, rows: &[&str]) -> Result<usize, TxError> {
+        for row in rows {
+            if row.is_empty() {
+                return Err(TxError::new("empty row"));
+            }
+            tx.execute(*row);
+        }
+        Ok(rows.len())
+    }
+
+    #[test]
+    fn success_commits() {
+        let mut conn = MockConnection::new();
+        assert_eq!((&mut conn).pipe_with_transaction(insert_all)(&["a", "b"]), Ok(2));
+        assert_eq!(conn.committed(), ["a", "b"]);
+        assert_eq!(conn.events(), ["begin", "commit"]);
+    }
+
+    #[test]
+    fn failure_rolls_back() {
+        let mut conn = MockConnection::new();
+        let out = (&mut conn).pipe_with_transaction(insert_all)(&["a", "", "c"]);
+        assert_eq!(out, Err(TxError::new("empty row")));
+        assert!(conn.committed().is_empty());
+        assert_eq!(conn.events(), ["begin", "rollback"]);
+    }
+
+    #[test]
+    fn begin_error_propagates() {
+        let calls = Cell::new(0);
+        let f = |_: &mut MockConnection, _: &mut MockTx, _: ()| {
+            calls.set(calls.get() + 1);
+            Ok(())
+        };
+        let mut conn = MockConnection::failing_begin();
+        assert_eq!((&mut conn).pipe_with_transaction(f)(()), Err(TxError::new("begin failed")));
+        assert_eq!(calls.get(), 0);
+        assert!(conn.events().is_empty());
+    }
+
+    #[test]
+    fn commit_error_propagates() {
+        let mut conn = MockConnection::failing_commit();
+        let out = (&mut conn).pipe_with_transaction(insert_all)(&["a"]);
+        assert_eq!(out, Err(TxError::new("commit failed")));
+        assert!(conn.committed().is_empty());
+        assert_eq!(conn.events(), ["begin", "rollback"]);
+    }
+}
+
+// ============================================================================================
+// Feature toggle tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod feature_toggle_tests {
+    use core::cell::Cell;
+    use pipei::{pipe_with_cfg_feature, Pipe, PipeWith};
+
+    fn enabled(s: String, n: usize) -> String {
+        s.repeat(n)
+    }
+
+    fn disabled(s: String, _n: usize) -> String {
+        s
+    }
+
+    #[test]
+    fn runtime_toggle_both_branches() {
+        assert_eq!("ab".to_string().pipe_with_feature_toggle(true, enabled, disabled)(3), "ababab");
+        assert_eq!("ab".to_string().pipe_with_feature_toggle(false, enabled, disabled)(3), "ab");
+    }
+
+    #[test]
+    fn runtime_toggle_calls_one_branch() {
+        let on = Cell::new(0);
+        let off = Cell::new(0);
+        for flag in [true, false, false] {
+            let _ = 1.pipe_with_feature_toggle(flag, |x: i32| { on.set(on.get() + 1); x }, |x: i32| { off.set(off.get() + 1); x })();
+        }
+        assert_eq!((on.get(), off.get()), (1, 2));
+    }
+
+    #[test]
+    fn compile_time_toggle() {
+        let picked = 0.pipe(pipe_with_cfg_feature!("std", |_: i32| "std", |_: i32| "no_std"))();
+        assert_eq!(picked, if cfg!(feature = "std") { "std" } else { "no_std" });
+
+        // The arms may have different types; only the selected one is compiled.
+        let scaled = 5.pipe(pipe_with_cfg_feature!("db", |x: i32| x * 100, |x: i32| x - 1))();
+        assert_eq!(scaled, if cfg!(feature = "db") { 500 } else { 4 });
+    }
+}
+
+// ============================================================================================
+// Distributed trace tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod distributed_trace_tests {
+    use pipei::trace::{self, PipeTrace, TraceContext};
+
+    fn inner(x: u32, _: ()) -> (u32, TraceContext) {
+        (x, trace::current_context().unwrap())
+    }
+
+    fn outer(x: u32, _: ()) -> (TraceContext, TraceContext) {
+        let ctx = trace::current_context().unwrap();
+        assert_eq!(trace::current_operation(), Some("outer"));
+        let (_, nested) = x.pipe_with_distributed_trace(&ctx, "inner", inner)(());
+        assert_eq!(trace::current_context(), Some(ctx));
+        (ctx, nested)
+    }
+
+    #[test]
+    fn child_spans_share_trace_id() {
+        let root = TraceContext::new(0x4bf92f3577b34da6a3ce929d0e0e4736, 0x00f067aa0ba902b7);
+        let (child, grandchild) = 7.pipe_with_distributed_trace(&root, "outer", outer)(());
+
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(grandchild.trace_id, root.trace_id);
+        assert_ne!(child.span_id, root.span_id);
+        assert_ne!(grandchild.span_id, child.span_id);
+        assert_eq!(child.parent_span_id, Some(root.span_id));
+        assert_eq!(grandchild.parent_span_id, Some(child.span_id));
+        assert_eq!(trace::current_context(), None);
+    }
+
+    #[test]
+    fn sibling_spans_differ() {
+        let root = TraceContext::new(1, 1);
+        let (_, a) = 1.pipe_with_distributed_trace(&root, "a", inner)(());
+        let (_, b) = 2.pipe_with_distributed_trace(&root, "b", inner)(());
+        assert_ne!(a.span_id, b.span_id);
+    }
+
+    #[test]
+    fn context_restored_after_panic() {
+        let root = TraceContext::new(9, 9);
+        let result = std::panic::catch_unwind(|| {
+            1.pipe_with_distributed_trace(&root, "boom", |_: i32| -> i32 { panic!("stage failed") })()
+        });
+        assert!(result.is_err());
+        assert_eq!(trace::current_context(), None);
+    }
+
+    #[test]
+    fn traceparent_round_trip() {
+        let header = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let ctx = TraceContext::from_traceparent(header).unwrap();
+        assert_eq!(ctx, TraceContext::new(0x4bf92f3577b34da6a3ce929d0e0e4736, 0x00f067aa0ba902b7));
+        assert_eq!(ctx.to_string(), header);
+        assert_eq!(TraceContext::from_traceparent("00-abc-def-01"), None);
+    }
+}
+
+// ============================================================================================
+// Boxed closure tests
+// ============================================================================================
+
+#[cfg(all(feature = "alloc", feature = "1", feature = "2"))]
+mod boxed_fn_tests {
+    use pipei::PipeBoxed;
+
+    fn gt(threshold: i32, x: i32) -> bool {
+        x > threshold
+    }
+
+    fn divisible(k: i32, x: i32) -> bool {
+        x % k == 0
+    }
+
+    fn count(pred: impl Fn(i32) -> bool, xs: &[i32]) -> usize {
+        xs.iter().filter(|&&x| pred(x)).count()
+    }
+
+    #[test]
+    fn heterogeneous_predicates() {
+        let preds: Vec<Box<dyn Fn(i32) -> bool>> =
+            vec![10.pipe_to_boxed_fn(gt), 3.pipe_to_boxed_fn(divisible), 0.pipe_to_boxed_fn(|lo: i32, x: i32| x == lo)];
+
+        let results: Vec<Vec<bool>> = [0, 12, 7].iter().map(|&x| preds.iter().map(|p| p(x)).collect()).collect();
+        assert_eq!(results, [[false, true, true], [true, true, false], [false, false, false]]);
+    }
+
+    #[test]
+    fn boxed_fn_as_impl_fn() {
+        let even = 2.pipe_to_boxed_fn(divisible);
+        assert_eq!(count(&even, &[1, 2, 3, 4]), 2);
+        assert_eq!(count(even, &[6]), 1);
+    }
+
+    #[test]
+    fn boxed_tap_returns_clone() {
+        let tagged = String::from("id").tap_to_boxed_fn(|s: &String, n: usize, m: usize| assert!(s.len() <= n + m));
+        assert_eq!(tagged(1, 1), "id");
+        assert_eq!(tagged(2, 0), "id");
+    }
+
+    #[test]
+    fn boxed_send_across_threads() {
+        let pred = 5.pipe_to_boxed_fn_send(gt);
+        let shared = std::sync::Arc::new(pred);
+        let handles: Vec<_> = [4, 6]
+            .into_iter()
+            .map(|x| {
+                let p = std::sync::Arc::clone(&shared);
+                std::thread::spawn(move || p(x))
+            })
+            .collect();
+        let out: Vec<bool> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(out, [false, true]);
+    }
+}
+
+// ============================================================================================
+// Request context tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "1"))]
+mod request_context_tests {
+    use pipei::request_ctx::{self, PipeRequestContext, RequestContext};
+
+    const OUTER: RequestContext = RequestContext { request_id: 1, user_id: Some(10), trace_id: 100 };
+    const INNER: RequestContext = RequestContext { request_id: 2, user_id: None, trace_id: 100 };
+
+    fn deep(x: u32, depth: u32) -> (u32, u128) {
+        if depth == 0 { (x, request_ctx::current().request_id) } else { deep(x + 1, depth - 1) }
+    }
+
+    #[test]
+    fn context_set_during_f() {
+        assert_eq!(5.pipe_with_request_context(OUTER, deep)(3), (8, 1));
+        assert_eq!(request_ctx::try_current(), None);
+    }
+
+    #[test]
+    fn nested_context_restored() {
+        let f = |x: u32, _: ()| {
+            let inner = x.pipe_with_request_context(INNER, |_: u32| request_ctx::current())();
+            (inner, request_ctx::current())
+        };
+        let (inner, outer) = 0.pipe_with_request_context(OUTER, f)(());
+        assert_eq!((inner, outer), (INNER, OUTER));
+        assert_eq!(request_ctx::try_current(), None);
+    }
+
+    #[test]
+    fn context_restored_after_panic() {
+        let result = std::panic::catch_unwind(|| 0.pipe_with_request_context(OUTER, |_: i32| -> i32 { panic!("handler failed") })());
+        assert!(result.is_err());
+        assert_eq!(request_ctx::try_current(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "no request context")]
+    fn current_without_context_panics() {
+        request_ctx::current();
+    }
+}
+
+// ============================================================================================
+// Type alias tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod type_alias_tests {
+    use pipei::type_aliases::{CurryOwn, TapCurryImm, TapCurryMut};
+    use pipei::{Pipe, Tap};
+
+    fn add(x: i32, y: i32) -> i32 { x + y }
+    fn check(x: &i32, expected: i32) { assert_eq!(*x, expected); }
+    fn bump(x: &mut i32) { *x += 1; }
+
+    struct Stages<F, G, H>
+    where
+        F: FnOnce(i32, i32) -> i32,
+        G: FnOnce(&i32, i32),
+        H: FnOnce(&mut i32),
+    {
+        add: CurryOwn<F, i32, (i32,), i32>,
+        check: TapCurryImm<G, i32, (i32,)>,
+        bump: TapCurryMut<H, i32, ()>,
+    }
+
+    impl<F, G, H> Stages<F, G, H>
+    where
+        F: FnOnce(i32, i32) -> i32,
+        G: FnOnce(&i32, i32),
+        H: FnOnce(&mut i32),
+    {
+        fn new(x: i32, add: F, check: G, bump: H) -> Self {
+            Stages { add: x.pipe(add), check: x.tap(check), bump: x.tap(bump) }
+        }
+    }
+
+    type BinOp = fn(i32, i32) -> i32;
+
+    struct Adder {
+        add: CurryOwn<BinOp, i32, (i32,), i32>,
+    }
+
+    #[test]
+    fn alias_names_pipe_closure_field() {
+        let adder = Adder { add: 2.pipe(add as BinOp) };
+        assert_eq!((adder.add)(3), 5);
+    }
+
+    #[test]
+    fn alias_names_generic_fields() {
+        let stages = Stages::new(7, add, check, bump);
+        assert_eq!((stages.add)(1), 8);
+        assert_eq!((stages.check)(7), 7);
+        assert_eq!((stages.bump)(), 8);
+    }
+}
+
+// ============================================================================================
+// Undo tests
+// ============================================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod undo_tests {
+    use pipei::{PipeUndo, UndoStack};
+
+    fn add(x: i32, y: i32) -> i32 { x + y }
+    fn double(x: i32) -> i32 { x * 2 }
+
+    #[test]
+    fn undo_reverts_to_earlier_stage() {
+        let mut history = UndoStack::new();
+        let stage1 = 1.pipe_with_undo(&mut history, add)(4);
+        let stage2 = stage1.pipe_with_undo(&mut history, double)();
+        let stage3 = stage2.pipe_with_undo(&mut history, add)(-3);
+        assert_eq!((stage1, stage2, stage3), (5, 10, 7));
+        assert_eq!(history.len(), 3);
+
+        assert_eq!(history.undo(), Some(stage2));
+        assert_eq!(history.undo(), Some(stage1));
+        assert_eq!(history.len(), 1);
+    }
+
+    #[test]
+    fn undo_on_empty_stack() {
+        let mut history = UndoStack::<String>::default();
+        assert!(history.is_empty());
+        assert_eq!(history.undo(), None);
+
+        let s = String::from("a").pipe_with_undo(&mut history, |s: String, t: &str| s + t)("b");
+        assert_eq!(s, "ab");
+        history.clear();
+        assert_eq!(history.undo(), None);
+    }
+}
+
+// ============================================================================================
+// Diff tap tests
+// ============================================================================================
+
+mod diff_tap_tests {
+    use pipei::TapValue;
+
+    #[test]
+    fn diff_called_between_consecutive_values() {
+        let mut prev = None;
+        let mut pairs = Vec::new();
+        let out: Vec<i32> = [1, 4, 9]
+            .into_iter()
+            .map(|x| x.tap_with_diff(&mut prev, |old: &i32, new: &i32| pairs.push((*old, *new))))
+            .collect();
+        assert_eq!(out, [1, 4, 9]);
+        assert_eq!(pairs, [(1, 4), (4, 9)]);
+        assert_eq!(prev, Some(9));
+    }
+
+    #[test]
+    fn diff_mut_edits_new_value_before_storing() {
+        let mut prev = None;
+        let mut calls = 0;
+        let mut carry = |old: &String, new: &mut String| {
+            calls += 1;
+            new.insert_str(0, old);
+        };
+        let a = String::from("a").tap_with_diff_mut(&mut prev, &mut carry);
+        let b = String::from("b").tap_with_diff_mut(&mut prev, &mut carry);
+        let c = String::from("c").tap_with_diff_mut(&mut prev, &mut carry);
+        assert_eq!((a.as_str(), b.as_str(), c.as_str()), ("a", "ab", "abc"));
+        assert_eq!(calls, 2);
+        assert_eq!(prev.as_deref(), Some("abc"));
+    }
+}
+
+// ============================================================================================
+// Migration tests
+// ============================================================================================
+
+#[cfg(feature = "alloc")]
+mod migration_tests {
+    use pipei::{Migration, MigrationError, Migrator, PipeMigrate};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Config {
+        version: u32,
+        data: String,
+    }
+
+    struct Rename(&'static str, &'static str);
+
+    impl Migration<Config, Config> for Rename {
+        fn migrate(&self, c: Config) -> Config {
+            Config { version: c.version + 1, data: c.data.replace(self.0, self.1) }
+        }
+    }
+
+    fn migrator() -> Migrator<Config> {
+        Migrator::new()
+            .add_migration(|c: Config| Config { version: c.version + 1, data: c.data + ";v1" })
+            .add_migration(Rename("v1", "one"))
+            .add_migration(|c: Config| Config { version: c.version + 1, data: c.data.to_uppercase() })
+    }
+
+    #[test]
+    fn migrations_applied_in_order() {
+        let config = Config { version: 0, data: "base".into() };
+        let migrated = config.pipe_migrate(&migrator(), 0, 3).unwrap();
+        assert_eq!(migrated, Config { version: 3, data: "BASE;ONE".into() });
+    }
+
+    #[test]
+    fn partial_migration_starts_midway() {
+        let config = Config { version: 1, data: "x;v1".into() };
+        let migrated = config.pipe_migrate(&migrator(), 1, 2).unwrap();
+        assert_eq!(migrated, Config { version: 2, data: "x;one".into() });
+    }
+
+    #[test]
+    fn out_of_range_versions_fail() {
+        let m = migrator();
+        assert_eq!(m.latest_version(), 3);
+        let config = Config { version: 0, data: String::new() };
+        let err: MigrationError = config.clone().pipe_migrate(&m, 0, 4).unwrap_err();
+        assert_eq!((err.from_version(), err.to_version()), (0, 4));
+        assert!(config.pipe_migrate(&m, 2, 1).is_err());
+    }
+}
+
+// ============================================================================================
+// Recover tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod recover_tests {
+    use pipei::PipeRecover;
+
+    #[derive(Debug, PartialEq)]
+    enum OpError {
+        Transient(u32),
+        Permanent(&'static str),
+    }
+
+    fn retry_transient(e: OpError, max_retries: u32) -> Result<u32, OpError> {
+        match e {
+            OpError::Transient(attempt) if attempt < max_retries => Ok(attempt + 1),
+            e => Err(e),
+        }
+    }
+
+    fn default_on_permanent(e: OpError) -> Result<u32, OpError> {
+        match e {
+            OpError::Permanent(_) => Ok(0),
+            e => Err(e),
+        }
+    }
+
+    #[test]
+    fn transient_error_recovers() {
+        assert_eq!(Err(OpError::Transient(1)).pipe_recover(retry_transient)(3), Ok(2));
+    }
+
+    #[test]
+    fn permanent_error_propagates() {
+        let r = Err(OpError::Permanent("disk")).pipe_recover(retry_transient)(3);
+        assert_eq!(r, Err(OpError::Permanent("disk")));
+    }
+
+    #[test]
+    fn ok_passes_through() {
+        let r: Result<u32, OpError> = Ok(9);
+        assert_eq!(r.pipe_recover(|_: OpError, _: u32| -> Result<u32, OpError> { unreachable!() })(3), Ok(9));
+    }
+
+    #[test]
+    fn chained_recoveries() {
+        let permanent = Err(OpError::Permanent("gone"))
+            .pipe_recover(retry_transient)(3)
+            .pipe_recover(default_on_permanent)();
+        assert_eq!(permanent, Ok(0));
+
+        let exhausted = Err(OpError::Transient(5))
+            .pipe_recover(retry_transient)(3)
+            .pipe_recover(default_on_permanent)();
+        assert_eq!(exhausted, Err(OpError::Transient(5)));
+    }
+
+    #[test]
+    fn recovery_may_change_error_type() {
+        let r: Result<u32, &str> = Err("bad");
+        assert_eq!(r.pipe_recover(|e: &str, code: i32| Err::<u32, _>((e.len() as i32) + code))(10), Err(13));
+    }
+}
+
+// ============================================================================================
+// Export tests
+// ============================================================================================
+
+#[cfg(all(feature = "alloc", feature = "1"))]
+mod export_tests {
+    use pipei::{Metric, MetricsExporter, Pipe, TapExport, VecMetricsExporter};
+
+    #[derive(Debug)]
+    struct Latency {
+        ms: u32,
+    }
+
+    #[test]
+    fn vec_exporter_records_name_and_debug_value() {
+        let mut exporter = VecMetricsExporter::new();
+        let latency = Latency { ms: 12 }.tap_with_export(&mut exporter, "latency");
+        let label = "ok".tap_with_export(&mut exporter, "status");
+        assert_eq!((latency.ms, label), (12, "ok"));
+        let exports: Vec<(&str, &str)> = exporter.exports().iter().map(|(n, v)| (n.as_str(), v.as_str())).collect();
+        assert_eq!(exports, [("latency", "Latency { ms: 12 }"), ("status", "\"ok\"")]);
+    }
+
+    #[test]
+    fn exporter_can_downcast_known_types() {
+        #[derive(Default)]
+        struct Sum(u64);
+        impl MetricsExporter for Sum {
+            fn export(&mut self, _name: &str, value: &dyn Metric) {
+                if let Some(n) = value.as_any().downcast_ref::<u64>() {
+                    self.0 += n;
+                }
+            }
+        }
+
+        let mut sum = Sum::default();
+        let exporter: &mut dyn MetricsExporter = &mut sum;
+        let x = 3u64.tap_with_export(exporter, "a").pipe(|x: u64, y: u64| x + y)(4).tap_with_export(exporter, "b");
+        "ignored".tap_with_export(exporter, "c");
+        assert_eq!((x, sum.0), (7, 10));
+    }
+}
+
+// ============================================================================================
+// Version gate tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod version_gate_tests {
+    use pipei::{PipeVersionGate, Version, VersionTooOld};
+
+    const MIN: Version = (2, 0, 0);
+
+    fn new_format(x: u32, prefix: &str) -> String {
+        format!("{prefix}{x}")
+    }
+
+    #[test]
+    fn exact_version_calls_f() {
+        assert_eq!(1.pipe_with_version_gate(MIN, (2, 0, 0), new_format)("#"), Ok("#1".to_string()));
+    }
+
+    #[test]
+    fn newer_version_calls_f() {
+        assert!(1.pipe_with_version_gate(MIN, (2, 0, 1), new_format)("#").is_ok());
+        assert!(1.pipe_with_version_gate(MIN, (10, 0, 0), new_format)("#").is_ok());
+    }
+
+    #[test]
+    fn older_version_reports_both_versions() {
+        let err = 1.pipe_with_version_gate(MIN, (1, 99, 99), new_format)("#").unwrap_err();
+        assert_eq!(err, VersionTooOld { min_version: (2, 0, 0), current_version: (1, 99, 99) });
+        assert_eq!(err.to_string(), "version 1.99.99 is older than the required 2.0.0");
+    }
+
+    #[test]
+    fn chains_with_question_mark() {
+        fn run(current: Version) -> Result<usize, VersionTooOld> {
+            let s = 5.pipe_with_version_gate(MIN, current, new_format)("v")?;
+            let len = s.pipe_with_version_gate((2, 1, 0), current, |s: String| s.len())()?;
+            Ok(len)
+        }
+        assert_eq!(run((2, 1, 0)), Ok(2));
+        assert_eq!(run((2, 0, 5)).unwrap_err().min_version, (2, 1, 0));
+        assert_eq!(run((1, 0, 0)).unwrap_err().min_version, (2, 0, 0));
+    }
+}
+
+// ============================================================================================
+// Environment tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod env_tests {
+    use pipei::{EnvError, PipeFromEnv};
+
+    fn double(x: u16) -> u32 { x as u32 * 2 }
+
+    #[test]
+    fn parses_several_types() {
+        std::env::set_var("PIPEI_TEST_U16", "21");
+        std::env::set_var("PIPEI_TEST_BOOL", "false");
+        std::env::set_var("PIPEI_TEST_STRING", "hello");
+
+        assert_eq!(u16::pipe_from_env("PIPEI_TEST_U16", double)(), Ok(42));
+        assert_eq!(bool::pipe_from_env("PIPEI_TEST_BOOL", |b: bool, n: i32| if b { n } else { -n })(3), Ok(-3));
+        assert_eq!(String::pipe_from_env("PIPEI_TEST_STRING", |s: String, t: &str| s + t)("!").as_deref(), Ok("hello!"));
+    }
+
+    #[test]
+    fn missing_var_is_reported() {
+        let err = u16::pipe_from_env("PIPEI_TEST_DEFINITELY_UNSET", double)().unwrap_err();
+        assert_eq!(err, EnvError::Missing("PIPEI_TEST_DEFINITELY_UNSET".into()));
+        assert_eq!(err.var_name(), "PIPEI_TEST_DEFINITELY_UNSET");
+    }
+
+    #[test]
+    fn unparsable_var_is_reported() {
+        std::env::set_var("PIPEI_TEST_BAD_U16", "70000");
+        match u16::pipe_from_env("PIPEI_TEST_BAD_U16", double)() {
+            Err(EnvError::ParseError(name, _)) => assert_eq!(name, "PIPEI_TEST_BAD_U16"),
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tap_env_inspects_and_returns_value() {
+        std::env::set_var("PIPEI_TEST_TAP", "7");
+        let mut seen = None;
+        let value = u16::tap_env("PIPEI_TEST_TAP", |v: &u16| seen = Some(*v))();
+        assert_eq!((value, seen), (Ok(7), Some(7)));
+    }
+
+    #[test]
+    fn var_read_when_called() {
+        let stage = u16::pipe_from_env("PIPEI_TEST_LATE", double);
+        std::env::set_var("PIPEI_TEST_LATE", "5");
+        assert_eq!(stage(), Ok(10));
+    }
+}
+
+// ============================================================================================
+// From iterator tests
+// ============================================================================================
+
+mod from_iter_tests {
+    use pipei::PipeFromIter;
+    use std::collections::{BTreeMap, HashSet};
+
+    #[test]
+    fn collects_into_vec() {
+        let v: Vec<i32> = (1..=4).map(|x| x * x).pipe_from_iter();
+        assert_eq!(v, [1, 4, 9, 16]);
+    }
+
+    #[test]
+    fn collects_into_hash_set() {
+        let set: HashSet<&str> = ["a", "b", "a", "c"].pipe_from_iter();
+        assert_eq!(set.len(), 3);
+        assert!(set.contains("a") && set.contains("b") && set.contains("c"));
+    }
+
+    #[test]
+    fn collects_into_btree_map() {
+        let map: BTreeMap<&str, usize> = ["one", "three", "two"].map(|w| (w, w.len())).pipe_from_iter();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.into_iter().collect::<Vec<_>>(), [("one", 3), ("three", 5), ("two", 3)]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn sorted_collects_in_order() {
+        let v: Vec<(u8, char)> = vec![(2, 'b'), (1, 'z'), (2, 'a')].pipe_from_iter_sorted();
+        assert_eq!(v, [(1, 'z'), (2, 'a'), (2, 'b')]);
+    }
+}
+
+// ============================================================================================
+// Invariant tests
+// ============================================================================================
+
+mod invariant_tests {
+    use pipei::TapValue;
+
+    #[test]
+    fn chained_invariants_pass() {
+        let v = 50.tap_with_invariant("in_range", |v| (0..=100).contains(v)).tap_with_invariant("not_max", |v| *v != 100);
+        assert_eq!(v, 50);
+    }
+
+    #[test]
+    #[should_panic(expected = "invariant 'not_max' violated")]
+    fn second_invariant_names_itself() {
+        100.tap_with_invariant("in_range", |v| (0..=100).contains(v)).tap_with_invariant("not_max", |v| *v != 100);
+    }
+
+    #[test]
+    fn invariant_on_owned_value() {
+        let s = String::from("abc").tap_with_invariant("ascii", |s| s.is_ascii());
+        assert_eq!(s, "abc");
+    }
+}
+
+// ============================================================================================
+// Correlation id tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod correlation_tests {
+    use pipei::correlation::{self, CorrelationId, PipeCorrelation};
+    use pipei::Pipe;
+
+    const OUTER: CorrelationId = CorrelationId(1);
+    const INNER: CorrelationId = CorrelationId(2);
+
+    fn tagged(x: u32, depth: u32) -> (u32, Option<CorrelationId>) {
+        if depth == 0 { (x, correlation::current_id()) } else { tagged(x + 1, depth - 1) }
+    }
+
+    #[test]
+    fn id_set_during_f_and_restored() {
+        assert_eq!(0.pipe_with_correlation_id(OUTER, tagged)(2), (2, Some(OUTER)));
+        assert_eq!(correlation::current_id(), None);
+    }
+
+    #[test]
+    fn nested_ids_inherit_and_restore() {
+        let f = |x: u32| {
+            let inherited = x.pipe(tagged)(0).1;
+            let inner = x.pipe_with_correlation_id(INNER, tagged)(0).1;
+            (inherited, inner, correlation::current_id())
+        };
+        assert_eq!(0.pipe_with_correlation_id(OUTER, f)(), (Some(OUTER), Some(INNER), Some(OUTER)));
+        assert_eq!(correlation::current_id(), None);
+    }
+
+    #[test]
+    fn id_restored_after_panic() {
+        let result = std::panic::catch_unwind(|| 0.pipe_with_correlation_id(OUTER, |_: i32| -> i32 { panic!("stage failed") })());
+        assert!(result.is_err());
+        assert_eq!(correlation::current_id(), None);
+    }
+
+    #[test]
+    fn generated_ids_are_distinct_uuids() {
+        let (a, b) = (CorrelationId::generate(), CorrelationId::generate());
+        assert_ne!(a, b);
+        let text = a.to_string();
+        assert_eq!(text.len(), 36);
+        assert_eq!(text.as_bytes()[14], b'4');
+        assert!(matches!(text.as_bytes()[19], b'8' | b'9' | b'a' | b'b'));
+    }
+}
+
+// ============================================================================================
+// Execution context tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod execution_context_tests {
+    use pipei::execution_ctx::{ExecutionContext, PipeExecutionContext};
+
+    fn correlation_id() -> Option<u128> {
+        ExecutionContext::with_current(|ctx| ctx.correlation_id)
+    }
+
+    #[test]
+    fn context_accessible_inside_f() {
+        let mut ctx = ExecutionContext { user_id: Some(3), ..ExecutionContext::new(10) };
+        let seen = 5.pipe_with_execution_context(&mut ctx, |x: i32, y: i32| {
+            (x + y, ExecutionContext::with_current(|c| (c.correlation_id, c.user_id)))
+        })(1);
+        assert_eq!(seen, (6, Some((10, Some(3)))));
+        assert_eq!(correlation_id(), None);
+    }
+
+    #[test]
+    fn nested_context_seen_by_child() {
+        let mut outer = ExecutionContext::new(1);
+        let ids = 0.pipe_with_execution_context(&mut outer, |x: i32| {
+            let mut inner = ExecutionContext::new(2);
+            let child = x.pipe_with_execution_context(&mut inner, |_: i32| correlation_id())();
+            (correlation_id(), child)
+        })();
+        assert_eq!(ids, (Some(1), Some(2)));
+        assert_eq!(correlation_id(), None);
+    }
+
+    #[test]
+    fn tags_modified_inside_f_visible_after() {
+        let mut ctx = ExecutionContext::new(7);
+        ctx.tags.insert("region".into(), "eu".into());
+        let tag = |x: u32, key: &str| {
+            ExecutionContext::with_current_mut(|c| c.tags.insert(key.into(), x.to_string()));
+            x
+        };
+        let x = 42.pipe_with_execution_context(&mut ctx, tag)("answer");
+        assert_eq!(x, 42);
+        assert_eq!(ctx.tags.len(), 2);
+        assert_eq!((ctx.tags["region"].as_str(), ctx.tags["answer"].as_str()), ("eu", "42"));
+        assert_eq!(ctx.correlation_id, 7);
+    }
+
+    #[test]
+    fn context_restored_after_panic() {
+        let mut ctx = ExecutionContext::new(9);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            0.pipe_with_execution_context(&mut ctx, |_: i32| -> i32 {
+                ExecutionContext::with_current_mut(|c| c.tags.insert("stage".into(), "failed".into()));
+                panic!("stage failed")
+            })()
+        }));
+        assert!(result.is_err());
+        assert_eq!(correlation_id(), None);
+        assert_eq!(ctx.tags["stage"], "failed");
+    }
+
+    #[test]
+    fn current_returns_a_copy() {
+        let mut ctx = ExecutionContext { user_id: Some(4), ..ExecutionContext::new(8) };
+        let copy = 0.pipe_with_execution_context(&mut ctx, |_: i32| {
+            let mut copy = ExecutionContext::current().unwrap();
+            copy.tags.insert("local".into(), "only".into());
+            copy
+        })();
+        assert_eq!((copy.correlation_id, copy.user_id), (8, Some(4)));
+        assert!(ctx.tags.is_empty());
+        assert_eq!(ExecutionContext::current(), None);
+    }
+
+    #[test]
+    fn nested_stage_inside_with_current_panics() {
+        let mut outer = ExecutionContext::new(1);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            0.pipe_with_execution_context(&mut outer, |x: i32| {
+                ExecutionContext::with_current(|_| {
+                    let mut inner = ExecutionContext::new(2);
+                    x.pipe_with_execution_context(&mut inner, |_: i32| ())()
+                })
+            })()
+        }));
+        assert!(result.is_err());
+        assert_eq!(correlation_id(), None);
+        assert_eq!(outer.correlation_id, 1);
+    }
+}
+
+// ============================================================================================
+// Type state tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod type_state_tests {
+    use pipei::{TypeStatePipe, Unvalidated, Validated, ValidationError};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Config {
+        workers: u32,
+        name: String,
+    }
+
+    fn has_workers(c: &Config) -> Result<(), ValidationError> {
+        if c.workers > 0 { Ok(()) } else { Err(ValidationError::new("no workers").with_field("workers")) }
+    }
+
+    fn describe(c: Config, suffix: &str) -> String {
+        format!("{}x{}{suffix}", c.name, c.workers)
+    }
+
+    #[test]
+    fn validated_config_can_be_processed() {
+        let raw = TypeStatePipe::<Unvalidated, _>::new(Config { workers: 4, name: "pool".into() });
+        let valid: TypeStatePipe<Validated, Config> = raw.validate(&has_workers).unwrap();
+        assert_eq!(valid.get().workers, 4);
+        assert_eq!(valid.process(describe)("!"), "poolx4!");
+    }
+
+    #[test]
+    fn invalid_config_is_rejected() {
+        let raw = TypeStatePipe::<Unvalidated, _>::new(Config { workers: 0, name: "idle".into() });
+        let err = raw.validate(&has_workers).unwrap_err();
+        assert_eq!((err.field(), err.reason()), (Some("workers"), "no workers"));
+    }
+
+    #[test]
+    fn pipe_next_transitions_state_and_type() {
+        struct Loaded;
+        let raw = TypeStatePipe::<Loaded, _>::new("8")
+            .pipe_next(|p| TypeStatePipe::<Unvalidated, _>::new(p.into_inner().parse::<u32>().unwrap()));
+        let out = raw.validate(&(1..=16)).unwrap().process(|n: u32| n * 2)();
+        assert_eq!(out, 16);
+    }
+}
+
+// ============================================================================================
+// Snapshot tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod snapshot_tests {
+    use pipei::{Pipe, Snapshot, TapValue};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Record {
+        field: String,
+        score: u32,
+    }
+
+    fn parse(line: &str) -> Record {
+        let (field, score) = line.split_once('=').unwrap();
+        Record { field: field.into(), score: score.parse().unwrap() }
+    }
+
+    fn boost(r: Record, by: u32) -> Record {
+        Record { score: r.score + by, ..r }
+    }
+
+    #[test]
+    fn snapshots_capture_each_stage() {
+        let mut after_parse = Snapshot::new();
+        let mut after_boost = Snapshot::default();
+        let untouched = Snapshot::<Record>::new();
+
+        let result = "name=5"
+            .pipe(parse)()
+            .tap_snapshot(&mut after_parse)
+            .pipe(boost)(10)
+            .tap_snapshot(&mut after_boost)
+            .pipe(|r: Record| r.score)();
+
+        assert_eq!(result, 15);
+        assert_eq!(after_parse.get().unwrap().field, "name");
+        assert_eq!(after_parse.get().unwrap().score, 5);
+        assert_eq!(after_boost.into_inner(), Some(Record { field: "name".into(), score: 15 }));
+        assert_eq!(untouched.get(), None);
+    }
+
+    #[test]
+    fn later_snapshot_replaces_earlier() {
+        let mut snap = Snapshot::new();
+        for x in [1, 2, 3] {
+            x.tap_snapshot(&mut snap);
+        }
+        assert_eq!(snap.get(), Some(&3));
+    }
+}
+
+// ============================================================================================
+// Conditional transform tests
+// ============================================================================================
+
+mod conditional_tests {
+    use pipei::{IndexOutOfBounds, PipeConditional};
+
+    type Transform = fn(i32) -> i32;
+
+    const TRANSFORMS: [Transform; 3] = [|x| x - 100, |x| x, |x| x + 100];
+
+    fn bucket(x: &i32) -> usize {
+        match *x {
+            x if x < 0 => 0,
+            0..=9 => 1,
+            10..=99 => 2,
+            _ => 3,
+        }
+    }
+
+    #[test]
+    fn each_path_taken() {
+        assert_eq!((-1).pipe_conditional(bucket, &TRANSFORMS), -101);
+        assert_eq!(5.pipe_conditional(bucket, &TRANSFORMS), 5);
+        assert_eq!(50.pipe_conditional(bucket, &TRANSFORMS), 150);
+    }
+
+    #[test]
+    fn out_of_bounds_falls_back_to_identity() {
+        assert_eq!(500.pipe_conditional(bucket, &TRANSFORMS), 500);
+        assert_eq!(1.pipe_conditional(|_: &i32| 0, &[] as &[Transform]), 1);
+    }
+
+    #[test]
+    fn result_version_reports_index() {
+        assert_eq!(50.pipe_conditional_result(bucket, &TRANSFORMS), Ok(150));
+        assert_eq!(500.pipe_conditional_result(bucket, &TRANSFORMS), Err(IndexOutOfBounds { index: 3, len: 3 }));
+    }
+
+    #[test]
+    fn boxed_closures_as_transforms() {
+        type Boxed = Box<dyn Fn(i32) -> i32>;
+        let offset = 7;
+        let transforms: Vec<Boxed> = vec![Box::new(move |x| x + offset), Box::new(|x| x * x)];
+        assert_eq!(3.pipe_conditional(|x: &i32| (*x % 2) as usize, &transforms), 9);
+        assert_eq!(4.pipe_conditional(|x: &i32| (*x % 2) as usize, &transforms), 11);
+    }
+}
+
+// ============================================================================================
+// State machine tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod state_machine_tests {
+    use pipei::StateMachine;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Light {
+        Red,
+        Green,
+        Yellow,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Event {
+        Go,
+        Slow,
+        Stop,
+    }
+
+    fn step(state: Light, cycles: u32, event: Event) -> (Light, u32) {
+        match (state, event) {
+            (Light::Red, Event::Go) => (Light::Green, cycles),
+            (Light::Green, Event::Slow) => (Light::Yellow, cycles),
+            (Light::Yellow, Event::Stop) => (Light::Red, cycles + 1),
+            (state, _) => (state, cycles),
+        }
+    }
+
+    #[test]
+    fn traffic_light_cycle() {
+        let mut seen = Vec::new();
+        let observe = |s: &Light, _: &u32, seen: &mut Vec<Light>| seen.push(*s);
+        let light = StateMachine::new(Light::Red, 0)
+            .pipe_transition(Event::Go, step)()
+            .tap_observe(observe)(&mut seen)
+            .pipe_transition(Event::Slow, step)()
+            .tap_observe(observe)(&mut seen)
+            .pipe_transition(Event::Stop, step)()
+            .tap_observe(observe)(&mut seen);
+        assert_eq!(seen, [Light::Green, Light::Yellow, Light::Red]);
+        assert_eq!(light.into_parts(), (Light::Red, 1));
+    }
+
+    #[test]
+    fn invalid_event_keeps_state() {
+        let light = StateMachine::new(Light::Red, 0).pipe_transition(Event::Stop, step)();
+        assert_eq!((*light.current_state(), *light.current_value()), (Light::Red, 0));
+    }
+
+    #[test]
+    fn transition_with_extra_arguments() {
+        let scale = |s: Light, v: u32, _: Event, by: u32| (s, v * by);
+        let m = StateMachine::new(Light::Green, 3).pipe_transition(Event::Go, scale)(4);
+        assert_eq!(*m.current_value(), 12);
+    }
+}
+
+// ============================================================================================
+// Lazy tests
+// ============================================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod lazy_tests {
+    use pipei::Lazy;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn counted(calls: &Rc<Cell<u32>>, value: u32) -> Lazy<u32> {
+        let calls = Rc::clone(calls);
+        Lazy::new(move || {
+            calls.set(calls.get() + 1);
+            value
+        })
+    }
+
+    fn add(x: &u32, y: u32) -> u32 { x + y }
+
+    #[test]
+    fn computed_once_across_pipe_force_calls() {
+        let calls = Rc::new(Cell::new(0));
+        let lazy = counted(&calls, 10);
+        assert_eq!(lazy.pipe_force(add)(1), 11);
+        assert_eq!(lazy.pipe_force(add)(2), 12);
+        assert_eq!(*lazy + 3, 13);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn unforced_value_not_computed() {
+        let calls = Rc::new(Cell::new(0));
+        let lazy = counted(&calls, 5);
+        let stage = lazy.pipe_force(add);
+        let inspected = lazy.tap_inspect_lazy(|_: &u32| panic!("not forced yet"))();
+        assert!(!inspected.is_forced());
+        assert_eq!((calls.get(), lazy.get()), (0, None));
+        assert_eq!(stage(1), 6);
+        assert_eq!((calls.get(), lazy.get()), (1, Some(&5)));
+    }
+
+    #[test]
+    fn forced_constructor_and_into_inner() {
+        let lazy = Lazy::forced(String::from("ready"));
+        assert!(lazy.is_forced());
+        assert_eq!(format!("{lazy:?}"), "Lazy(\"ready\")");
+        assert_eq!(lazy.into_inner(), "ready");
+        assert_eq!(format!("{:?}", Lazy::new(|| 1)), "Lazy(<unforced>)");
+    }
+}
+
+// ============================================================================================
+// Type name tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod type_name_tests {
+    use pipei::PipeTypeName;
+
+    struct Payload(u32);
+
+    fn tag<T>(ty: &'static str, _value: T) -> &'static str { ty }
+
+    #[test]
+    fn passes_type_name_and_value() {
+        let out = Payload(3).pipe_inspect_type_name(|ty: &'static str, p: Payload, k: u32| (ty, p.0 * k))(2);
+        assert!(out.0.ends_with("Payload"));
+        assert_eq!(out.1, 6);
+    }
+
+    #[test]
+    fn names_primitive_and_generic_types() {
+        assert_eq!(1i64.pipe_inspect_type_name(tag)(), "i64");
+        assert!(Some("x").pipe_inspect_type_name(tag)().contains("Option"));
+    }
+}
+
+// ============================================================================================
+// Resource guard tests
+// ============================================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod resource_guard_tests {
+    use pipei::PipeResourceGuard;
+    use std::cell::RefCell;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    struct Connection {
+        id: u32,
+    }
+
+    fn checked_div(x: i32, y: i32) -> i32 {
+        if y == 0 { panic!("division by zero") } else { x / y }
+    }
+
+    #[test]
+    fn success_returns_resource_without_cleanup() {
+        let closed = RefCell::new(Vec::new());
+        let (conn, q) = 10.pipe_with_resource_guard(Connection { id: 1 }, |c: Connection| closed.borrow_mut().push(c.id), checked_div)(2);
+        assert_eq!((conn.id, q), (1, 5));
+        assert!(closed.borrow().is_empty());
+    }
+
+    #[test]
+    fn panic_runs_cleanup() {
+        let closed = RefCell::new(Vec::new());
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            10.pipe_with_resource_guard(Connection { id: 2 }, |c: Connection| closed.borrow_mut().push(c.id), checked_div)(0)
+        }));
+        assert!(result.is_err());
+        assert_eq!(*closed.borrow(), [2]);
+    }
+
+    #[test]
+    fn resource_transferred_to_caller() {
+        let closed = RefCell::new(Vec::new());
+        let cleanup = |c: Connection| closed.borrow_mut().push(c.id);
+        let (conn, _) = 1.pipe_with_resource_guard(Connection { id: 3 }, cleanup, checked_div)(1);
+        let (conn, _) = 1.pipe_with_resource_guard(conn, cleanup, checked_div)(1);
+        assert_eq!(conn.id, 3);
+        assert!(closed.borrow().is_empty());
+    }
+
+    #[test]
+    fn dropped_closure_skips_cleanup() {
+        let closed = RefCell::new(Vec::new());
+        let stage = 10.pipe_with_resource_guard(Connection { id: 4 }, |c: Connection| closed.borrow_mut().push(c.id), checked_div);
+        drop(stage);
+        assert!(closed.borrow().is_empty());
+    }
+}
+
+// ============================================================================================
+// Tenant tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod tenant_tests {
+    use pipei::tenant::{self, Permission, Permissions, PipeTenant, TenantContext};
+
+    fn acme() -> TenantContext {
+        let mut ctx = TenantContext::new(1);
+        ctx.permissions = Permission::Read | Permission::Write;
+        ctx.quotas.insert("rows".into(), 100);
+        ctx
+    }
+
+    fn insert_rows(n: u64, table: &str) -> Result<String, String> {
+        tenant::with_current(|t| {
+            if !t.permissions.contains(Permission::Write) {
+                return Err(format!("tenant {} may not write", t.tenant_id));
+            }
+            match t.quota("rows") {
+                Some(limit) if n > limit => Err(format!("{n} rows exceeds quota {limit}")),
+                _ => Ok(format!("{}:{table}:{n}", t.tenant_id)),
+            }
+        })
+        .expect("no tenant")
+    }
+
+    #[test]
+    fn tenant_visible_inside_f() {
+        let ctx = acme();
+        assert_eq!(5.pipe_with_tenant(&ctx, insert_rows)("users"), Ok("1:users:5".to_string()));
+        assert_eq!(500.pipe_with_tenant(&ctx, insert_rows)("users"), Err("500 rows exceeds quota 100".to_string()));
+        assert_eq!(tenant::current_id(), None);
+    }
+
+    #[test]
+    fn calls_are_isolated() {
+        let writer = acme();
+        let reader = TenantContext { permissions: Permission::Read.into(), ..TenantContext::new(2) };
+        assert!(1.pipe_with_tenant(&writer, insert_rows)("t").is_ok());
+        assert_eq!(1.pipe_with_tenant(&reader, insert_rows)("t"), Err("tenant 2 may not write".to_string()));
+        assert!(1.pipe_with_tenant(&writer, insert_rows)("t").is_ok());
+    }
+
+    #[test]
+    fn nested_tenant_restored() {
+        let (outer, inner) = (acme(), TenantContext::new(9));
+        let ids = 0.pipe_with_tenant(&outer, |_: i32| {
+            let nested = 0.pipe_with_tenant(&inner, |_: i32| tenant::current_id())();
+            (nested, tenant::current_id())
+        })();
+        assert_eq!(ids, (Some(9), Some(1)));
+        assert_eq!(tenant::current_id(), None);
+    }
+
+    #[test]
+    fn current_returns_a_copy() {
+        let ctx = acme();
+        let copy = 0.pipe_with_tenant(&ctx, |_: i32| tenant::current())();
+        assert_eq!(copy, Some(ctx));
+        assert_eq!(tenant::current(), None);
+    }
+
+    #[test]
+    fn nested_stage_inside_with_current() {
+        let (outer, inner) = (acme(), TenantContext::new(9));
+        let ids = 0.pipe_with_tenant(&outer, |_: i32| {
+            tenant::with_current(|t| (t.tenant_id, 0.pipe_with_tenant(&inner, |_: i32| tenant::current_id())()))
+        })();
+        assert_eq!(ids, Some((1, Some(9))));
+    }
+
+    #[test]
+    fn permission_sets() {
+        let mut p = Permissions::NONE;
+        assert!(!p.contains(Permission::Read));
+        p |= Permission::Admin;
+        assert!(p.contains(Permission::Admin) && !p.contains(Permission::Delete));
+        assert_eq!(Permission::Read | Permission::Write, Permissions::from(Permission::Write) | Permission::Read);
+    }
+}
+
+// ============================================================================================
+// ABI version tests
+// ============================================================================================
+
+mod abi_version_tests {
+    use pipei::{AbiImpl, AbiVersion, PipeAbiVersion};
+
+        // Versions are scaled by 10, so that `AbiVersion(25)` stands for version 2.5.
+    const IMPLS: [AbiImpl<u32, String>; 3] = [
+        (AbiVersion(10), |x| format!("v1:{x}")),
+        (AbiVersion(20), |x| format!("v2:{x}")),
+        (AbiVersion(30), |x| format!("v3:{x}")),
+    ];
+
+    #[test]
+    fn picks_highest_compatible_version() {
+        assert_eq!(7.pipe_with_abi_version(AbiVersion(25), &IMPLS).as_deref(), Some("v2:7"));
+        assert_eq!(7.pipe_with_abi_version(AbiVersion(20), &IMPLS).as_deref(), Some("v2:7"));
+        assert_eq!(7.pipe_with_abi_version(AbiVersion(10), &IMPLS).as_deref(), Some("v1:7"));
+    }
+
+    #[test]
+    fn newer_request_uses_latest_impl() {
+        assert_eq!(7.pipe_with_abi_version(AbiVersion(100), &IMPLS).as_deref(), Some("v3:7"));
+    }
+
+    #[test]
+    fn older_request_has_no_impl() {
+        assert_eq!(7.pipe_with_abi_version(AbiVersion(0), &IMPLS), None);
+        assert_eq!(7.pipe_with_abi_version(AbiVersion(5), &[] as &[AbiImpl<u32, String>]), None);
+    }
+}
+
+// ============================================================================
+// Thread pool tests
+// ============================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod pool_tests {
+    use pipei::{PipePool, ThreadPool};
+
+    fn work(x: u64, rounds: u64) -> u64 {
+        (0..rounds).fold(x, |acc, i| acc.wrapping_mul(31).wrapping_add(i))
+    }
+
+    fn fail(x: u64) -> u64 {
+        if x == 3 { panic!("stage {x} failed") }
+        x
+    }
+
+    #[test]
+    fn pooled_results_match_sequential() {
+        let pool = ThreadPool::new(4);
+        assert_eq!(pool.threads(), 4);
+
+        let handles: Vec<_> = (0..10).map(|i| i.pipe_with_pool(&pool, work)(1000)).collect();
+        let pooled: Vec<_> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        let sequential: Vec<_> = (0..10).map(|i| work(i, 1000)).collect();
+        assert_eq!(pooled, sequential);
+    }
+
+    #[test]
+    fn panic_is_returned_by_join() {
+        let pool = ThreadPool::new(2);
+        let handles: Vec<_> = (0..5).map(|i| i.pipe_with_pool(&pool, fail)()).collect();
+        let results: Vec<_> = handles.into_iter().map(|h| h.join()).collect();
+
+        let payload = results[3].as_ref().unwrap_err();
+        assert_eq!(payload.downcast_ref::<String>().map(String::as_str), Some("stage 3 failed"));
+        // The worker that ran the panicking stage keeps serving the queue.
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 4);
+        assert_eq!(7.pipe_with_pool(&pool, fail)().join().unwrap(), 7);
+    }
+
+    #[test]
+    fn blocking_resumes_panic_on_caller() {
+        let pool = ThreadPool::new(1);
+        assert_eq!(2.pipe_with_pool_blocking(&pool, work)(0), 2);
+
+        let caught = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| 3.pipe_with_pool_blocking(&pool, fail)()));
+        assert!(caught.is_err());
+    }
+
+    #[test]
+    fn drop_waits_for_queued_jobs() {
+        let pool = ThreadPool::new(1);
+        let handles: Vec<_> = (0..3).map(|i| i.pipe_with_pool(&pool, work)(10)).collect();
+        drop(pool);
+        assert!(handles.into_iter().all(|h| h.join().is_ok()));
+    }
+}
+
+// ============================================================================
+// Metric span tests
+// ============================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod metric_span_tests {
+    use pipei::{MetricSpan, TapMetricSpan};
+    use std::time::Duration;
+
+    fn grandchild(_: &u32, _: &mut Vec<MetricSpan>) {
+        std::thread::sleep(Duration::from_millis(2));
+    }
+
+    fn child(x: &u32, children: &mut Vec<MetricSpan>, tag: &str) {
+        (*x).tap_with_metric_span(tag, children, grandchild)();
+    }
+
+    fn parent(x: &u32, children: &mut Vec<MetricSpan>) {
+        (*x).tap_with_metric_span("child1", children, child)("grandchild");
+        (*x).tap_with_metric_span("child2", children, |_: &u32, _: &mut Vec<MetricSpan>| {})();
+    }
+
+    #[test]
+    fn records_nested_tree() {
+        let mut log = Vec::new();
+        let out = 5.tap_with_metric_span("parent", &mut log, parent)();
+        assert_eq!(out, 5);
+
+        assert_eq!(log.len(), 1);
+        let root = &log[0];
+        assert_eq!(root.name, "parent");
+        let names: Vec<_> = root.children.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["child1", "child2"]);
+        assert_eq!(root.children[0].children[0].name, "grandchild");
+        assert!(root.children[1].children.is_empty());
+
+        let child_total: Duration = root.children.iter().map(|c| c.duration).sum();
+        assert!(root.duration >= child_total);
+        assert!(root.children[0].duration >= root.children[0].children[0].duration);
+        assert!(root.children[0].start >= root.start);
+    }
+
+    #[test]
+    fn to_json_serializes_tree() {
+        let mut log = Vec::new();
+        5.tap_with_metric_span("a \"b\"", &mut log, |x: &u32, c: &mut Vec<MetricSpan>| {
+            (*x).tap_with_metric_span("leaf", c, |_: &u32, _: &mut Vec<MetricSpan>| {})();
+        })();
+
+        let json = log[0].to_json();
+        assert!(json.starts_with(r#"{"name":"a \"b\"","duration_ns":"#), "{json}");
+        assert!(json.contains(r#""children":[{"name":"leaf","duration_ns":"#), "{json}");
+        assert!(json.ends_with(r#""children":[]}]}"#), "{json}");
+    }
+}
+
+// ============================================================================
+// Trait assertion tests
+// ============================================================================
+
+mod assert_implements_tests {
+    use core::fmt::Debug;
+    use pipei::PipeAssertImplements;
+
+    trait Shape {
+        fn area(&self) -> u32;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Square(u32);
+
+    impl Shape for Square {
+        fn area(&self) -> u32 {
+            self.0 * self.0
+        }
+    }
+
+    #[test]
+    fn passes_value_through() {
+        let sq = Square(3)
+            .pipe_assert_type_implements::<dyn Shape>()
+            .pipe_assert_type_implements::<dyn Debug>()
+            .pipe_assert_type_implements::<dyn Send + Sync>();
+        assert_eq!(sq, Square(3));
+        assert_eq!(sq.area(), 9);
+    }
+}
+
+// ============================================================================
+// Ownership-explicit pipe tests
+// ============================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod ownership_tests {
+    use pipei::{Pipe, PipeOwnership};
+
+    #[derive(Debug, PartialEq)]
+    struct Word(String);
+
+    fn consume(w: Word, suffix: &str) -> Word {
+        Word(w.0 + suffix)
+    }
+
+    fn measure(w: &Word, extra: usize) -> usize {
+        w.0.len() + extra
+    }
+
+    fn shout(w: &mut Word) -> usize {
+        w.0.push('!');
+        w.0.len()
+    }
+
+    #[test]
+    fn matches_pipe() {
+        let s = || Word("hi".into());
+        assert_eq!(s().pipe_move(consume)("!"), s().pipe(consume)("!"));
+        assert_eq!(s().pipe_borrow(measure)(1), s().pipe(measure)(1));
+        assert_eq!(s().pipe_borrow_mut(shout)(), s().pipe(shout)());
+    }
+
+    #[test]
+    fn borrowing_closures_keep_the_value() {
+        let len = Word("abc".into()).pipe_borrow(measure);
+        assert_eq!([len(0), len(10)], [3, 13]);
+
+        let mut grow = Word("a".into()).pipe_borrow_mut(shout);
+        assert_eq!([grow(), grow(), grow()], [2, 3, 4]);
+    }
+}
+
+// ============================================================================
+// Configured pipeline tests
+// ============================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod config_tests {
+    use pipei::PipeConfig;
+    use std::cell::Cell;
+
+    struct Config {
+        separator: char,
+        max_len: usize,
+        reads: Cell<u32>,
+    }
+
+    fn split(s: &str, cfg: &Config) -> Vec<String> {
+        cfg.reads.set(cfg.reads.get() + 1);
+        s.split(cfg.separator).map(str::to_owned).collect()
+    }
+
+    fn truncate(words: Vec<String>, cfg: &Config) -> Vec<String> {
+        cfg.reads.set(cfg.reads.get() + 1);
+        words.into_iter().map(|w| w.chars().take(cfg.max_len).collect()).collect()
+    }
+
+    fn join(words: Vec<String>, cfg: &Config, prefix: &str) -> String {
+        cfg.reads.set(cfg.reads.get() + 1);
+        format!("{prefix}{}", words.join(&cfg.separator.to_string()))
+    }
+
+    #[test]
+    fn config_reaches_every_step() {
+        let cfg = Config { separator: ',', max_len: 3, reads: Cell::new(0) };
+        let (out, cfg) = PipeConfig::new("alpha,beta,gamma", cfg)
+            .pipe_configured(split)()
+            .pipe_configured(truncate)()
+            .pipe_configured(join)("> ")
+            .into_parts();
+
+        assert_eq!(out, "> alp,bet,gam");
+        assert_eq!(cfg.reads.get(), 3);
+    }
+
+    #[test]
+    fn accessors() {
+        let p = PipeConfig::new(1, "cfg");
+        assert_eq!((*p.value(), *p.config()), (1, "cfg"));
+        assert_eq!(p.pipe_configured(|x: i32, c: &&str| x + c.len() as i32)().into_value(), 4);
+    }
+}
+
+// ============================================================================
+// Transform log tests
+// ============================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod transform_log_tests {
+    use pipei::{PipeLogged, TransformLog};
+
+    fn push(mut v: Vec<i32>, x: i32) -> Vec<i32> {
+        v.push(x);
+        v
+    }
+
+    fn scale(v: Vec<i32>, k: i32) -> Vec<i32> {
+        v.into_iter().map(|x| x * k).collect()
+    }
+
+    fn reverse(mut v: Vec<i32>) -> Vec<i32> {
+        v.reverse();
+        v
+    }
+
+    #[test]
+    fn records_and_replays_five_stages() {
+        let mut log = TransformLog::new();
+        let out = vec![1]
+            .pipe_logged("push 2", &mut log, push)(2)
+            .pipe_logged("scale", &mut log, scale)(10)
+            .pipe_logged("reverse", &mut log, reverse)()
+            .pipe_logged("push 5", &mut log, push)(5)
+            .pipe_logged("scale again", &mut log, scale)(2);
+        assert_eq!(out, [40, 20, 10]);
+
+        assert_eq!(log.len(), 5);
+        let names: Vec<_> = log.entries().map(|(name, _)| name).collect();
+        assert_eq!(names, ["push 2", "scale", "reverse", "push 5", "scale again"]);
+        assert_eq!(log.entries().nth(2).unwrap().1, &[10, 20]);
+
+        assert_eq!(log.replay(vec![1]), out);
+        assert_eq!(log.replay(vec![]), [40, 10]);
+    }
+
+    #[test]
+    fn diff_at_shows_each_change() {
+        let mut log = TransformLog::new();
+        let _ = vec![3].pipe_logged("push", &mut log, push)(4).pipe_logged("reverse", &mut log, reverse)();
+
+        assert_eq!(log.diff_at(0), Some((&vec![3], &vec![3, 4])));
+        assert_eq!(log.diff_at(1), Some((&vec![3, 4], &vec![4, 3])));
+        assert_eq!(log.diff_at(2), None);
+        assert!(TransformLog::<i32>::new().is_empty());
+    }
+
+    #[test]
+    fn diff_at_with_two_chains_in_one_log() {
+        let mut log = TransformLog::new();
+        let a = vec![1].pipe_logged("a", &mut log, push)(2);
+        let b = vec![10].pipe_logged("b", &mut log, scale)(4);
+        assert_eq!((a, b), (vec![1, 2], vec![40]));
+
+        assert_eq!(log.diff_at(0), Some((&vec![1], &vec![1, 2])));
+        assert_eq!(log.diff_at(1), Some((&vec![10], &vec![40])));
+    }
+}
+
+// ============================================================================
+// Budget tests
+// ============================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod budget_tests {
+    use pipei::{Budget, BudgetExceeded, PipeBudget};
+
+    fn add(x: u32, y: u32) -> u32 {
+        x + y
+    }
+
+    #[test]
+    fn sufficient_budget_calls_f_and_decrements() {
+        let mut budget = Budget::new(10);
+        assert_eq!(1.pipe_with_budget(&mut budget, 4, add)(2), Ok(3));
+        assert_eq!(budget.remaining(), 6);
+        assert_eq!(1.pipe_with_budget(&mut budget, 6, add)(2), Ok(3));
+        assert_eq!(budget.remaining(), 0);
+    }
+
+    #[test]
+    fn insufficient_budget_skips_f() {
+        let mut budget = Budget::new(2);
+        let mut called = false;
+        let out = 1.pipe_with_budget(&mut budget, 3, |x: u32| {
+            called = true;
+            x
+        })();
+        assert_eq!(out, Err(BudgetExceeded { cost: 3, remaining: 2 }));
+        assert!(!called);
+        assert_eq!(budget.remaining(), 2);
+    }
+
+    #[test]
+    fn chain_fails_on_third_stage() {
+        let mut budget = Budget::new(10);
+        let out = 0
+            .pipe_with_budget(&mut budget, 4, add)(1)
+            .and_then(|x| x.pipe_with_budget(&mut budget, 4, add)(1))
+            .and_then(|x| x.pipe_with_budget(&mut budget, 4, add)(1));
+        assert_eq!(out, Err(BudgetExceeded { cost: 4, remaining: 2 }));
+
+        budget.reset();
+        assert_eq!((budget.remaining(), budget.total()), (10, 10));
+    }
+}
+
+// ============================================================================
+// Schema export tests
+// ============================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod schema_tests {
+    use pipei::{ExportedSchema, Pipe, TapSchema};
+
+    #[derive(Debug)]
+    struct User {
+        name: String,
+    }
+
+    fn parse(raw: &str) -> Result<User, String> {
+        if raw.is_empty() { Err("empty".into()) } else { Ok(User { name: raw.into() }) }
+    }
+
+    fn greet(user: User, greeting: &str) -> String {
+        format!("{greeting}, {}", user.name)
+    }
+
+    #[test]
+    fn records_four_stages() {
+        let mut schema = ExportedSchema::new();
+        let out = "ada"
+            .tap_export_schema(&mut schema, "raw input")
+            .pipe(parse)()
+            .tap_export_schema(&mut schema, "validation")
+            .unwrap()
+            .tap_export_schema(&mut schema, "user")
+            .pipe(greet)("hello")
+            .tap_export_schema(&mut schema, "greeting");
+        assert_eq!(out, "hello, ada");
+
+        let stages: Vec<_> = schema.stages().iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(stages, ["raw input", "validation", "user", "greeting"]);
+
+        let md = schema.to_markdown();
+        let rows: Vec<_> = md.lines().collect();
+        assert_eq!(rows.len(), 6);
+        assert_eq!(rows[0], "| Stage | Type |");
+        assert_eq!(rows[2], "| raw input | `&str` |");
+        assert!(rows[3].starts_with("| validation |") && rows[3].contains("Result"), "{}", rows[3]);
+        assert!(rows[4].contains("User"), "{}", rows[4]);
+        assert!(rows[5].contains("String"), "{}", rows[5]);
+    }
+}
+
+// ============================================================================
+// Capability tests
+// ============================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod capability_tests {
+    use pipei::rbac::{Capabilities, PipeCapability, Role, Unauthorized};
+    use pipei::PipeAuthorize;
+
+    const VIEWER: Role<'static> = Role::new("viewer", &["read"]);
+    const EDITOR: Role<'static> = Role::new("editor", &["read", "write"]);
+    const JANITOR: Role<'static> = Role::new("janitor", &["delete"]);
+
+    fn append(doc: String, text: &str) -> String {
+        doc + text
+    }
+
+    #[test]
+    fn granted_capability_calls_f() {
+        let caps = Capabilities::from_roles(&[EDITOR]);
+        assert_eq!("a".to_string().pipe_with_capability(&caps, "write", append)("b"), Ok("ab".to_string()));
+    }
+
+    #[test]
+    fn missing_capability_skips_f() {
+        let caps = Capabilities::from_roles(&[VIEWER]);
+        let mut called = false;
+        let out = 1.pipe_with_capability(&caps, "write", |x: i32| {
+            called = true;
+            x
+        })();
+        assert_eq!(out, Err(Unauthorized("write".into())));
+        assert_eq!(out.unwrap_err().to_string(), "missing capability 'write'");
+        assert!(!called);
+    }
+
+    #[test]
+    fn all_capabilities_requires_each() {
+        let caps = Capabilities::from_roles(&[VIEWER, JANITOR]);
+        assert!(caps.contains("read") && caps.contains("delete") && !caps.contains("write"));
+
+        assert_eq!("a".to_string().pipe_with_all_capabilities(&caps, &["read", "delete"], append)("b").as_deref(), Ok("ab"));
+        assert_eq!(
+            "a".to_string().pipe_with_all_capabilities(&caps, &["read", "write", "admin"], append)("b"),
+            Err(Unauthorized("write".into()))
+        );
+        assert_eq!("a".to_string().pipe_with_all_capabilities(&caps, &[], append)("b").as_deref(), Ok("ab"));
+    }
+
+    #[test]
+    fn works_as_capability_backend() {
+        let mut caps: Capabilities = ["read"].into_iter().collect();
+        assert!(1.pipe_with_authorization(&caps, "write", |x: i32| x)().is_err());
+        caps.grant("write");
+        assert_eq!(1.pipe_with_authorization(&caps, "write", |x: i32| x)(), Ok(1));
+    }
+}
+
+// ============================================================================
+// Convergence tests
+// ============================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod converge_tests {
+    use pipei::PipeConverge;
+
+    /// A last-writer-wins register: the write with the highest timestamp wins,
+    /// with ties broken by the written value.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Lww {
+        ts: u64,
+        value: &'static str,
+    }
+
+    fn lww_merge(a: Lww, b: Lww) -> Lww {
+        if (b.ts, b.value) > (a.ts, a.value) { b } else { a }
+    }
+
+    fn write_at(_: Lww, ts: u64, value: &'static str) -> Lww {
+        Lww { ts, value }
+    }
+
+    fn write_later(reg: Lww, ts: u64, value: &'static str) -> Lww {
+        Lww { ts: reg.ts + ts, value }
+    }
+
+    #[test]
+    fn merge_is_order_independent() {
+        let start = Lww { ts: 1, value: "init" };
+        let ab = start.clone().pipe_eventually(lww_merge, write_at, write_later)(5, "x");
+        let ba = start.clone().pipe_eventually(lww_merge, write_later, write_at)(5, "x");
+        assert_eq!(ab, Lww { ts: 6, value: "x" });
+        assert_eq!(ab, ba);
+    }
+
+    #[test]
+    fn ties_are_deterministic() {
+        let start = Lww { ts: 0, value: "" };
+        let a = start.clone().pipe_eventually(lww_merge, |_: Lww| Lww { ts: 3, value: "a" }, |_: Lww| Lww { ts: 3, value: "b" })();
+        let b = start.pipe_eventually(lww_merge, |_: Lww| Lww { ts: 3, value: "b" }, |_: Lww| Lww { ts: 3, value: "a" })();
+        assert_eq!(a, b);
+        assert_eq!(a.value, "b");
+    }
+}
+
+// ============================================================================
+// Health check tests
+// ============================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod health_tests {
+    use pipei::{HealthStatus, PipeHealthCheck, ServiceUnhealthy};
+
+    fn fetch(id: u32, fail: bool) -> Result<u32, String> {
+        if fail { Err(format!("timeout fetching {id}")) } else { Ok(id * 10) }
+    }
+
+    #[test]
+    fn healthy_calls_f() {
+        let mut health = HealthStatus::Healthy;
+        assert_eq!(1.pipe_with_health_check(&mut health, fetch)(false), Ok(Ok(10)));
+        assert!(health.is_healthy());
+    }
+
+    #[test]
+    fn unhealthy_skips_f() {
+        let mut health = HealthStatus::Unhealthy("down".into());
+        let mut called = false;
+        let out = 1.pipe_with_health_check(&mut health, |x: u32| -> Result<u32, String> {
+            called = true;
+            Ok(x)
+        })();
+        assert_eq!(out, Err(ServiceUnhealthy));
+        assert!(!called);
+        assert_eq!(health, HealthStatus::Unhealthy("down".into()));
+    }
+
+    #[test]
+    fn degraded_still_calls_f() {
+        let mut health = HealthStatus::Degraded("high latency".into());
+        assert!(health.is_available() && !health.is_healthy());
+        assert_eq!(2.pipe_with_health_check(&mut health, fetch)(false), Ok(Ok(20)));
+        assert_eq!(health, HealthStatus::Healthy);
+
+        let mut health = HealthStatus::Degraded("high latency".into());
+        assert!(2.pipe_with_health_check(&mut health, fetch)(true).unwrap().is_err());
+        assert_eq!(health, HealthStatus::Unhealthy("timeout fetching 2".into()));
+    }
+
+    #[test]
+    fn sequence_updates_status() {
+        let mut health = HealthStatus::default();
+        let mut seen = Vec::new();
+        for (id, fail) in [(1, false), (2, false), (3, true), (4, false)] {
+            let out = id.pipe_with_health_check(&mut health, fetch)(fail);
+            seen.push((out.is_ok(), health.clone()));
+        }
+        assert_eq!(
+            seen,
+            [
+                (true, HealthStatus::Healthy),
+                (true, HealthStatus::Healthy),
+                (true, HealthStatus::Unhealthy("timeout fetching 3".into())),
+                (false, HealthStatus::Unhealthy("timeout fetching 3".into())),
+            ]
+        );
+
+        health = HealthStatus::Healthy;
+        assert_eq!(5.pipe_with_health_check(&mut health, fetch)(false), Ok(Ok(50)));
+    }
+}
+
+// ============================================================================
+// Change detection tests
+// ============================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod dirty_tests {
+    use pipei::Dirty;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Config {
+        threads: u32,
+    }
+
+    fn persist(config: &Config, disk: &mut Vec<u32>) {
+        disk.push(config.threads);
+    }
+
+    #[test]
+    fn modify_then_mark_clean() {
+        let mut config = Dirty::new(Config { threads: 4 });
+        assert!(!config.is_dirty());
+
+        config.get_mut().threads = 8;
+        assert!(config.is_dirty());
+        assert_eq!(config.original().threads, 4);
+
+        config.mark_clean();
+        assert!(!config.is_dirty());
+        assert_eq!(config.original().threads, 8);
+    }
+
+    #[test]
+    fn reverting_is_clean() {
+        let mut config = Dirty::new(Config { threads: 4 });
+        config.set(Config { threads: 2 });
+        config.set(Config { threads: 4 });
+        assert!(!config.is_dirty());
+    }
+
+    #[test]
+    fn taps_only_when_dirty() {
+        let mut disk = Vec::new();
+        let mut config = Dirty::new(Config { threads: 1 }).tap_check_dirty(persist)(&mut disk);
+        assert!(disk.is_empty());
+
+        config.set(Config { threads: 3 });
+        let mut config = config.tap_check_dirty(persist)(&mut disk);
+        assert_eq!(disk, [3]);
+
+        config.mark_clean();
+        let config = config.tap_check_dirty(persist)(&mut disk);
+        assert_eq!(disk, [3]);
+        assert_eq!(config.into_inner(), Config { threads: 3 });
+    }
+
+    #[test]
+    fn pipes_only_when_dirty() {
+        let threads = |c: Config, extra: u32| c.threads + extra;
+        let mut config = Dirty::new(Config { threads: 1 });
+        assert_eq!(config.clone().pipe_if_dirty(threads)(1), None);
+        config.get_mut().threads = 5;
+        assert_eq!(config.pipe_if_dirty(threads)(1), Some(6));
+    }
+}
+
+// ============================================================================
+// Saga tests
+// ============================================================================
+
+#[cfg(all(feature = "alloc", feature = "0", feature = "1"))]
+mod saga_tests {
+    use pipei::{PipeSaga, Saga, SagaFailed};
+
+    type Journal = Vec<&'static str>;
+
+    fn book_flight(mut j: Journal) -> Result<Journal, String> {
+        j.push("book flight");
+        Ok(j)
+    }
+    fn cancel_flight(mut j: Journal) -> Journal {
+        j.push("cancel flight");
+        j
+    }
+    fn book_hotel(mut j: Journal) -> Result<Journal, String> {
+        j.push("book hotel");
+        Err("no rooms".into())
+    }
+    fn cancel_hotel(mut j: Journal) -> Journal {
+        j.push("cancel hotel");
+        j
+    }
+    fn charge_card(mut j: Journal) -> Result<Journal, String> {
+        j.push("charge card");
+        Ok(j)
+    }
+    fn refund_card(mut j: Journal) -> Journal {
+        j.push("refund card");
+        j
+    }
+
+    fn start(mut j: Journal, who: &'static str) -> Journal {
+        j.push(who);
+        j
+    }
+
+    #[test]
+    fn failure_compensates_in_reverse() {
+        let saga = Saga::new()
+            .add_step(book_flight, cancel_flight)
+            .add_step(book_hotel, cancel_hotel)
+            .add_step(charge_card, refund_card);
+        assert_eq!(saga.len(), 3);
+
+        let failed = Journal::new().pipe_with_saga(&saga, start)("ada").unwrap_err();
+        assert_eq!(failed.step, 1);
+        assert_eq!(failed.error, "no rooms");
+        // Compensation starts from the value the failing step received.
+        assert_eq!(failed.value, ["ada", "book flight", "cancel hotel", "cancel flight"]);
+        assert!(!failed.value.contains(&"charge card"));
+    }
+
+    #[test]
+    fn success_runs_every_step() {
+        let saga = Saga::new().add_step(book_flight, cancel_flight).add_step(charge_card, refund_card);
+        assert_eq!(Journal::new().pipe_with_saga(&saga, start)("bob"), Ok(vec!["bob", "book flight", "charge card"]));
+    }
+
+    #[test]
+    fn first_step_failure() {
+        let saga: Saga<Journal, String> = Saga::new().add_step(book_hotel, cancel_hotel).add_step(book_flight, cancel_flight);
+        match saga.run(Journal::new()) {
+            Err(SagaFailed { step: 0, value, .. }) => assert_eq!(value, ["cancel hotel"]),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert_eq!(Saga::<u8, ()>::default().run(7), Ok(7));
+    }
+}
+
+// ============================================================================
+// Borrowing pipe tests
+// ============================================================================
+
+#[cfg(feature = "std")]
+mod pipe_ref_tests {
+    use pipei::PipeRef;
+
+    fn double(x: &u32) -> u32 {
+        x * 2
+    }
+
+    fn triple(x: &u32) -> u32 {
+        x * 3
+    }
+
+    fn count_a(s: &str) -> usize {
+        s.chars().filter(|&x| x == 'a').count()
+    }
+
+    #[test]
+    fn value_stays_usable() {
+        let val = 7;
+        let doubled = val.pipe_ref(double);
+        let tripled = val.pipe_ref(triple);
+        assert_eq!((doubled, tripled, val), (14, 21, 7));
+    }
+
+    #[test]
+    fn unsized_receiver() {
+        let s = String::from("banana");
+        assert_eq!(s.as_str().pipe_ref(count_a), 3);
+        assert!(s.pipe_ref(|s: &String| s.starts_with('b')));
+        assert_eq!(s, "banana");
+    }
+}
+
+// ============================================================================
+// Option variant tests
+// ============================================================================
+
+#[cfg(all(feature = "0", feature = "1"))]
+mod option_variant_tests {
+    use pipei::{PipeOption, TapOption};
+
+    fn record(x: &u32, seen: &mut Vec<u32>) {
+        seen.push(*x);
+    }
+
+    fn double(x: &mut u32) {
+        *x *= 2;
+    }
+
+    fn note_empty(log: &mut Vec<&'static str>, msg: &'static str) {
+        log.push(msg);
+    }
+
+    #[test]
+    fn tap_some_runs_on_some_only() {
+        let mut seen = Vec::new();
+        assert_eq!(Some(3).tap_some(record)(&mut seen), Some(3));
+        assert_eq!(None.tap_some(record)(&mut seen), None);
+        assert_eq!(seen, [3]);
+    }
+
+    #[test]
+    fn tap_some_mutates_in_place() {
+        assert_eq!(Some(4).tap_some(double)(), Some(8));
+        assert_eq!(None::<u32>.tap_some(double)(), None);
+    }
+
+    #[test]
+    fn tap_none_runs_on_none_only() {
+        let mut log = Vec::new();
+        assert_eq!(Some(1).tap_none(note_empty)(&mut log, "a"), Some(1));
+        assert_eq!(None::<u32>.tap_none(note_empty)(&mut log, "b"), None);
+        assert_eq!(log, ["b"]);
+    }
+
+    #[test]
+    fn pipe_some_maps_some_only() {
+        let describe = |x: u32, unit: &str| format!("{x}{unit}");
+        assert_eq!(Some(5).pipe_some(describe)("kg"), Some("5kg".to_string()));
+        assert_eq!(None.pipe_some(describe)("kg"), None);
+    }
+
+    #[test]
+    fn pipe_none_fills_none_only() {
+        let mut calls = 0;
+        let mut default = |base: u32| {
+            calls += 1;
+            base * 10
+        };
+        assert_eq!(None.pipe_none(&mut default)(4), Some(40));
+        assert_eq!(Some(1).pipe_none(&mut default)(4), Some(1));
+        assert_eq!(calls, 1);
+        assert_eq!(None::<&str>.pipe_none(|| "empty")(), Some("empty"));
+    }
+}
+
+// ============================================================================
+// Result variant tests
+// ============================================================================
+
+#[cfg(all(feature = "0", feature = "2"))]
+mod result_variant_tests {
+    use pipei::{PipeResult, TapResult};
+
+    fn log_ok(x: &u32, out: &mut Vec<String>, tag: &str) {
+        out.push(format!("{tag} ok {x}"));
+    }
+
+    fn log_err(e: &String, out: &mut Vec<String>, tag: &str) {
+        out.push(format!("{tag} err {e}"));
+    }
+
+    fn clamp(x: &mut u32) {
+        *x = (*x).min(10);
+    }
+
+    #[test]
+    fn taps_matching_variant_only() {
+        let mut out = Vec::new();
+        let ok: Result<u32, String> = Ok(1);
+        let err: Result<u32, String> = Err("bad".into());
+
+        assert_eq!(ok.clone().tap_ok(log_ok)(&mut out, "a").tap_err(log_err)(&mut out, "a"), ok);
+        assert_eq!(err.clone().tap_ok(log_ok)(&mut out, "b").tap_err(log_err)(&mut out, "b"), err);
+        assert_eq!(out, ["a ok 1", "b err bad"]);
+    }
+
+    #[test]
+    fn mutable_taps() {
+        assert_eq!(Ok::<u32, ()>(42).tap_ok(clamp)(), Ok(10));
+        assert_eq!(Err::<u32, u8>(1).tap_ok(clamp)(), Err(1));
+        assert_eq!(Err::<(), u8>(1).tap_err(|e: &mut u8| *e += 1)(), Err(2));
+    }
+
+    #[test]
+    fn pipe_ok_maps_ok_only() {
+        let scale = |x: u32, k: u32, label: &str| format!("{label}{}", x * k);
+        assert_eq!(Ok::<u32, u8>(3).pipe_ok(scale)(2, "="), Ok("=6".to_string()));
+        assert_eq!(Err::<u32, u8>(9).pipe_ok(scale)(2, "="), Err(9));
+    }
+
+    #[test]
+    fn pipe_err_maps_err_only() {
+        let wrap = |e: u8, op: &str| format!("{op} failed with {e}");
+        assert_eq!(Err::<u32, u8>(4).pipe_err(wrap)("read"), Err("read failed with 4".to_string()));
+        assert_eq!(Ok::<u32, u8>(1).pipe_err(wrap)("read"), Ok(1));
+    }
+
+    #[test]
+    fn pipe_ok_propagates_panics() {
+        let out = std::panic::catch_unwind(|| Ok::<u32, ()>(1).pipe_ok(|_: u32| -> u32 { panic!("boom") })());
+        assert!(out.is_err());
+    }
+}
+
+// ============================================================================================
+// Try pipe tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod try_pipe_tests {
+    use pipei::PipeTry;
+    use std::num::ParseIntError;
+
+    fn parse(s: &str, radix: u32) -> Result<u32, ParseIntError> {
+        u32::from_str_radix(s, radix)
+    }
+
+    fn checked_double(x: u32) -> Result<u32, String> {
+        x.checked_mul(2).ok_or_else(|| format!("{x} overflows"))
+    }
+
+    fn parse_and_double(s: &str) -> Result<u32, String> {
+        let x = s.try_pipe(parse)(10).map_err(|e| e.to_string())?;
+        x.try_pipe(checked_double)()
+    }
+
+    #[test]
+    fn ok_is_propagated() {
+        assert_eq!(parse_and_double("21"), Ok(42));
+    }
+
+    #[test]
+    fn err_short_circuits_with_question_mark() {
+        assert!(parse_and_double("x").is_err());
+        assert_eq!(parse_and_double("4294967295"), Err("4294967295 overflows".to_string()));
+    }
+
+    #[test]
+    fn closure_stage() {
+        let r = 5.try_pipe(|x: i32, y: i32| if y == 0 { Err("div by zero") } else { Ok(x / y) })(0);
+        assert_eq!(r, Err("div by zero"));
+    }
+}
+
+// ============================================================================================
+// Pipe if tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod pipe_if_tests {
+    use pipei::PipeWith;
+    use std::cell::Cell;
+
+    fn clamp(x: i32, max: i32) -> i32 {
+        x.min(max)
+    }
+
+    #[test]
+    fn taken_branch_calls_f() {
+        let out: i32 = 15.pipe_if(|x| *x > 10, clamp)(10);
+        assert_eq!(out, 10);
+    }
+
+    #[test]
+    fn untaken_branch_returns_value() {
+        let out: i32 = 7.pipe_if(|x| *x > 10, clamp)(10);
+        assert_eq!(out, 7);
+    }
+
+    #[test]
+    fn f_not_called_when_predicate_fails() {
+        let calls = Cell::new(0);
+        let v = vec![1, 2].pipe_if(|v| v.is_empty(), |v: Vec<i32>| {
+            calls.set(calls.get() + 1);
+            v
+        })();
+        assert_eq!((v, calls.get()), (vec![1, 2], 0));
+    }
+
+    #[test]
+    fn predicate_may_mutate_captured_state() {
+        let mut checked = Vec::new();
+        let out = 3.pipe_if(|x| { checked.push(*x); true }, |x: i32, k: i32| x * k)(4);
+        assert_eq!((out, checked), (12, vec![3]));
+    }
+
+    #[test]
+    fn panic_in_f_propagates() {
+        let out = std::panic::catch_unwind(|| 1.pipe_if(|_| true, |_: i32| -> i32 { panic!("boom") })());
+        assert!(out.is_err());
+    }
+}
+
+// ============================================================================================
+// Tap if tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod tap_if_tests {
+    use pipei::TapWith;
+    use std::cell::Cell;
+
+    #[test]
+    fn effect_runs_when_predicate_holds() {
+        let seen = Cell::new(0);
+        let x = 5.tap_if(|x| *x > 0, |x: &i32| seen.set(*x))();
+        assert_eq!((x, seen.get()), (5, 5));
+    }
+
+    #[test]
+    fn effect_skipped_when_predicate_fails() {
+        let calls = Cell::new(0);
+        let x = (-5).tap_if(|x| *x > 0, |_: &i32| calls.set(calls.get() + 1))();
+        assert_eq!((x, calls.get()), (-5, 0));
+    }
+
+    #[test]
+    fn mutable_effect_with_args() {
+        let v = vec![1].tap_if(|v| !v.is_empty(), |v: &mut Vec<i32>, x: i32| v.push(x))(2);
+        assert_eq!(v, [1, 2]);
+        let v = Vec::new().tap_if(|v| !v.is_empty(), |v: &mut Vec<i32>, x: i32| v.push(x))(2);
+        assert!(v.is_empty());
+    }
+}
+
+// ============================================================================================
+// Async tests
+// ============================================================================================
+
+#[cfg(all(feature = "async", feature = "std", feature = "0", feature = "1"))]
+mod async_tests {
+    use core::future::Future;
+    use core::pin::pin;
+    use core::task::{Context, Poll, Waker};
+    use pipei::{PipeAsync, TapAsync};
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = pin!(fut);
+        loop {
+            if let Poll::Ready(r) = fut.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+                return r;
+            }
+        }
+    }
+
+    fn assert_send<T: Send>(t: T) -> T {
+        t
+    }
+
+    /// Returns `Pending` once before completing, to exercise re-polling.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+        fn poll(mut self: core::pin::Pin<&mut Self>, _: &mut Context<'_>) -> Poll<()> {
+            if self.0 { Poll::Ready(()) } else { self.0 = true; Poll::Pending }
+        }
+    }
+
+    async fn add(x: u32, y: u32) -> u32 {
+        YieldOnce(false).await;
+        x + y
+    }
+
+    async fn record(x: &u32, out: &mut Vec<u32>) {
+        YieldOnce(false).await;
+        out.push(*x);
+    }
+
+    #[test]
+    fn pipe_async_resolves_to_result() {
+        assert_eq!(block_on(2.pipe_async(add)(3)), 5);
+    }
+
+    #[test]
+    fn pipe_async_is_lazy() {
+        let fut = 2.pipe_async(|x: u32| async move { panic!("polled {x}") })();
+        drop(fut);
+    }
+
+    #[test]
+    fn tap_async_returns_value() {
+        let mut out = Vec::new();
+        let x = block_on(4.tap_async(record)(&mut out));
+        assert_eq!((x, out), (4, vec![4]));
+    }
+
+    #[test]
+    fn tap_async_mutable() {
+        let v = block_on(vec![1].tap_async(async |v: &mut Vec<i32>, x: i32| v.push(x))(2));
+        assert_eq!(v, [1, 2]);
+    }
+
+    #[test]
+    fn futures_are_send_for_send_inputs() {
+        let fut = assert_send(1.pipe_async(add)(1));
+        assert_eq!(block_on(fut), 2);
+        let fut = assert_send(String::from("a").tap_async(async |s: &String| s.len())());
+        assert_eq!(block_on(fut), "a");
+    }
+}
+
+// ============================================================================================
+// Also tests
+// ============================================================================================
+
+#[cfg(feature = "std")]
+mod also_tests {
+    use pipei::Also;
+    use std::cell::Cell;
+
+    #[test]
+    fn mutates_and_returns_value() {
+        let v = vec![1].also(|v: &mut Vec<i32>| v.push(2)).also(|v: &mut Vec<i32>| v.push(3));
+        assert_eq!(v, [1, 2, 3]);
+    }
+
+    #[test]
+    fn inspects_by_shared_reference() {
+        let seen = Cell::new(0);
+        let x = 7.also(|x: &i32| seen.set(*x));
+        assert_eq!((x, seen.get()), (7, 7));
+    }
+
+    #[test]
+    fn accepts_fn_once() {
+        let out = Cell::new(None);
+        let tag = String::from("done");
+        let x = 1.also(|_: &i32| out.set(Some(tag)));
+        assert_eq!((x, out.take().as_deref()), (1, Some("done")));
+    }
+}
+
+// ============================================================================================
+// Run tests
+// ============================================================================================
+
+#[cfg(feature = "std")]
+mod run_tests {
+    use pipei::Run;
+    use std::cell::RefCell;
+
+    #[test]
+    fn run_consumes_value() {
+        let sink = RefCell::new(Vec::new());
+        String::from("saved").run(|s: String| sink.borrow_mut().push(s));
+        assert_eq!(*sink.borrow(), ["saved"]);
+    }
+
+    #[test]
+    fn run_discards_result() {
+        let sink = RefCell::new(0);
+        let () = 5.run(|x: i32| sink.replace(x * 2));
+        assert_eq!(*sink.borrow(), 10);
+    }
+
+    #[test]
+    fn run_returning_keeps_result() {
+        let sink = vec![1, 2, 3];
+        assert_eq!(sink.run_returning(|v: Vec<i32>| v.into_iter().sum::<i32>()), 6);
+    }
+
+    #[test]
+    fn fn_once_closure_may_consume_captures() {
+        let suffix = String::from("!");
+        assert_eq!(String::from("hi").run_returning(move |s: String| s + &suffix), "hi!");
+    }
+}
+
+// ============================================================================================
+// Convert tests
+// ============================================================================================
+
+#[cfg(all(feature = "std", feature = "0", feature = "1"))]
+mod convert_tests {
+    use pipei::PipeConvert;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    fn widen(x: i64, shift: u32) -> i64 {
+        x << shift
+    }
+
+    fn len(s: Rc<str>) -> usize {
+        s.len()
+    }
+
+    #[test]
+    fn numeric_widening() {
+        let x: i32 = -3;
+        assert_eq!(x.into_pipe::<i64, _, _, _>(widen)(40), -3 << 40);
+        assert_eq!(200u8.into_pipe::<u32, _, _, _>(|x: u32, y: u32| x * y)(1000), 200_000);
+    }
+
+    #[test]
+    fn string_into_shared_str() {
+        assert_eq!(String::from("abc").into_pipe::<Rc<str>, _, _, _>(len)(), 3);
+    }
+
+    #[test]
+    fn string_into_bytes() {
+        let first = String::from("hi").into_pipe::<Vec<u8>, _, _, _>(|b: Vec<u8>, i: usize| b[i])(0);
+        assert_eq!(first, b'h');
+    }
+
+    #[test]
+    fn conversion_happens_before_currying() {
+        struct Counted<'a>(&'a Cell<u32>);
+        impl From<Counted<'_>> for u64 {
+            fn from(c: Counted<'_>) -> u64 {
+                c.0.set(c.0.get() + 1);
+                7
+            }
+        }
+
+        let conversions = Cell::new(0);
+        let f = Counted(&conversions).into_pipe::<u64, _, _, _>(|x: u64, y: u64| x + y);
+        assert_eq!(conversions.get(), 1);
+        assert_eq!(f(1), 8);
+        assert_eq!(conversions.get(), 1);
+    }
+
+    #[test]
+    fn pipe_into_converts_result() {
+        let x: i64 = 3.pipe_into::<i64, _, _, _>(|x: i32, y: i32| x * y)(-2);
+        assert_eq!(x, -6);
+        let s = 'a'.pipe_into::<String, _, _, _>(|c: char| c.to_ascii_uppercase())();
+        assert_eq!(s, "A");
+    }
+
+    #[test]
+    fn into_pipe_then_pipe_into() {
+        let bytes = String::from("ok").into_pipe::<Vec<u8>, _, _, _>(|b: Vec<u8>| b.len() as u8)();
+        assert_eq!(bytes.pipe_into::<u64, _, _, _>(|n: u8, k: u8| n * k)(3), 6);
+    }
+}
+
+// ============================================================================================
+// Parallel traverse tests
+// ============================================================================================
+
+#[cfg(all(feature = "rayon", feature = "1", feature = "2"))]
+mod traverse_tests {
+    use pipei::PipeTraverse;
+
+    fn affine(x: &i32, k: i32, b: i32) -> i64 {
+        i64::from(*x) * i64::from(k) + i64::from(b)
+    }
+
+    fn values() -> [i32; 100] {
+        core::array::from_fn(|i| i as i32 - 50)
+    }
+
+    #[test]
+    fn traverse_matches_sequential_map() {
+        let values = values();
+        let sequential: Vec<i64> = values.iter().map(|x| affine(x, 3, -1)).collect();
+        assert_eq!(values.pipe_traverse(affine)(3, -1), sequential);
+    }
+
+    #[test]
+    fn traverse_clones_shared_args() {
+        fn tag(x: &i32, prefix: String) -> String {
+            format!("{prefix}{x}")
+        }
+
+        let tagged = vec![1, 2, 3].pipe_traverse(tag)(String::from("#"));
+        assert_eq!(tagged, ["#1", "#2", "#3"]);
+        assert!(Vec::<i32>::new().pipe_traverse(tag)(String::new()).is_empty());
+    }
+
+    #[test]
+    fn traverse_mut_matches_sequential_update() {
+        fn shift(x: &mut i32, by: i32) {
+            *x += by;
+        }
+
+        let mut parallel = values();
+        let mut sequential = values();
+        parallel.pipe_traverse_mut(shift)(7);
+        sequential.iter_mut().for_each(|x| shift(x, 7));
+        assert_eq!(parallel, sequential);
+    }
+}
+
+// ============================================================================================
+// Field tap derive tests
+// ============================================================================================
+
+#[cfg(all(feature = "macros", feature = "0", feature = "1"))]
+mod tap_fields_tests {
+    use core::cell::RefCell;
+    use pipei::{TapFields, TapWith};
+
+    #[derive(Debug, Clone, PartialEq, TapFields)]
+    struct Reading {
+        sensor: &'static str,
+        celsius: f64,
+        samples: Vec<u32>,
+    }
+
+    #[derive(TapFields)]
+    struct Tagged<T: Clone> {
+        value: T,
+        #[tap_skip]
+        #[allow(dead_code)]
+        tag: u8,
+    }
+
+    fn reading() -> Reading {
+        Reading { sensor: "t1", celsius: 21.5, samples: vec![3, 4] }
+    }
+
+    #[test]
+    fn each_field_tap_sees_its_field() {
+        let seen = RefCell::new(Vec::new());
+        let out = reading()
+            .tap_field_sensor(|s: &&str| seen.borrow_mut().push(s.to_string()))()
+            .tap_field_celsius(|c: &f64, unit: &str| seen.borrow_mut().push(format!("{c}{unit}")))("C")
+            .tap_field_samples(|v: &Vec<u32>| seen.borrow_mut().push(v.len().to_string()))();
+        assert_eq!(*seen.borrow(), ["t1", "21.5C", "2"]);
+        assert_eq!(out, reading());
+    }
+
+    #[test]
+    fn field_tap_matches_tap_proj() {
+        fn celsius(r: &Reading) -> &f64 {
+            &r.celsius
+        }
+
+        let via_proj = RefCell::new(0.0);
+        let via_field = RefCell::new(0.0);
+        reading().tap_proj(celsius, |c: &f64| *via_proj.borrow_mut() = *c)();
+        reading().tap_field_celsius(|c: &f64| *via_field.borrow_mut() = *c)();
+        assert_eq!(via_proj, via_field);
+    }
+
+    #[test]
+    fn mut_field_taps_modify_only_their_field() {
+        let out = reading()
+            .tap_field_celsius_mut(|c: &mut f64, delta: f64| *c += delta)(0.5)
+            .tap_field_samples_mut(|v: &mut Vec<u32>| v.push(5))()
+            .tap_field_sensor_mut(|s: &mut &str| *s = "t2")();
+        assert_eq!(out, Reading { sensor: "t2", celsius: 22.0, samples: vec![3, 4, 5] });
+    }
+
+    #[test]
+    fn generic_struct_with_skipped_field() {
+        let t = Tagged { value: String::from("a"), tag: 1 }.tap_field_value_mut(|v: &mut String| v.push('b'))();
+        assert_eq!(t.value, "ab");
+    }
+}
+
+// ============================================================================================
+// Pipe derive tests
+// ============================================================================================
+
+#[cfg(feature = "macros")]
+mod pipe_derive_tests {
+    use pipei::{pipe_derive, Curry, PipeOwnership};
+
+    struct DbConn {
+        rows: Vec<(u64, &'static str)>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Row(&'static str);
+
+    #[derive(Debug, PartialEq)]
+    enum DbError {
+        NotFound(u64),
+    }
+
+    const UPPERCASE: u32 = 1;
+
+    #[pipe_derive(arity = 2, borrow = "ref")]
+    fn process(conn: &DbConn, id: u64, flags: u32) -> Result<Row, DbError> {
+        let (_, name) = conn.rows.iter().find(|(row_id, _)| *row_id == id).ok_or(DbError::NotFound(id))?;
+        Ok(Row(if flags & UPPERCASE != 0 { "ALICE" } else { name }))
+    }
+
+    #[pipe_derive(arity = 1)]
+    fn push(mut v: Vec<u32>, x: u32) -> Vec<u32> {
+        v.push(x);
+        v
+    }
+
+    #[pipe_derive(arity = 0, borrow = "mut")]
+    fn bump(n: &mut u32) -> u32 {
+        *n += 1;
+        *n
+    }
+
+    fn conn() -> DbConn {
+        DbConn { rows: vec![(1, "alice"), (2, "bob")] }
+    }
+
+    #[test]
+    fn derived_stage_curries_without_pipe() {
+        let lookup = ProcessPipe.curry(conn());
+        assert_eq!(lookup(1, 0), Ok(Row("alice")));
+        assert_eq!(lookup(1, UPPERCASE), Ok(Row("ALICE")));
+        assert_eq!(lookup(3, 0), Err(DbError::NotFound(3)));
+    }
+
+    #[test]
+    fn derived_stage_plugs_into_pipe_stages() {
+        assert_eq!(conn().pipe_borrow(ProcessPipe)(2, 0), Ok(Row("bob")));
+        assert_eq!(PushPipe.curry(vec![1])(2), [1, 2]);
+
+        let mut next = BumpPipe.curry(0);
+        assert_eq!((next(), next()), (1, 2));
+    }
+
+    #[test]
+    fn annotated_function_is_kept() {
+        assert_eq!(process(&conn(), 2, 0), Ok(Row("bob")));
+    }
+}
+
+// ============================================================================================
+// Sampling tests
+// ============================================================================================
+
+#[cfg(all(feature = "rand", feature = "0", feature = "1"))]
+mod sampling_tests {
+    use core::cell::Cell;
+    use pipei::PipeSampling;
+    use rand::rngs::SmallRng;
+    use rand::SeedableRng;
+
+    fn double(x: u32) -> u32 {
+        x * 2
+    }
+
+    #[test]
+    fn half_rate_samples_about_half() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        let hits = (0..1000).filter(|&i| i.pipe_with_sampling(0.5, &mut rng, double)().is_some()).count();
+        // Binomial(1000, 0.5): mean 500, standard deviation ~15.8.
+        assert!((453..=547).contains(&hits), "{hits} samples");
+    }
+
+    #[test]
+    fn zero_rate_never_runs() {
+        let calls = Cell::new(0);
+        let f = |x: u32, y: u32| {
+            calls.set(calls.get() + 1);
+            x + y
+        };
+        let mut rng = SmallRng::seed_from_u64(7);
+        assert!((0..100).all(|i| i.pipe_with_sampling(0.0, &mut rng, f)(1).is_none()));
+        assert_eq!(calls.get(), 0);
+    }
+
+    #[test]
+    fn full_rate_always_runs() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        assert!((0..100).all(|i| i.pipe_with_sampling(1.0, &mut rng, double)() == Some(i * 2)));
+    }
+
+    #[test]
+    fn sampling_or_falls_back_to_default() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        assert_eq!(3.pipe_with_sampling_or(0.0, &mut rng, double, 0)(), 0);
+        assert_eq!(3.pipe_with_sampling_or(1.0, &mut rng, double, 0)(), 6);
+    }
+}
+
+// ============================================================================================
+// Serialize tests
+// ============================================================================================
+
+#[cfg(all(feature = "serde", feature = "std", feature = "0", feature = "1"))]
+mod serialize_tests {
+    use super::*;
+    use pipei::{DeserializerPipe, PipeDeserialize, PipeSerialize};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Order {
+        id: u32,
+        items: Vec<String>,
+    }
+
+    fn order() -> Order {
+        Order { id: 7, items: vec!["tea".into(), "cake".into()] }
+    }
+
+    #[test]
+    fn serialize_to_bytes_and_string() {
+        let expected = r#"{"id":7,"items":["tea","cake"]}"#;
+        assert_eq!(order().pipe_serialize().unwrap(), expected.as_bytes());
+        assert_eq!(order().pipe_serialize_to_string().unwrap(), expected);
+    }
+
+    #[test]
+    fn tap_serialize_writes_valid_json_and_returns_value() {
+        let mut buf: Vec<u8> = Vec::new();
+        let out = order().tap_serialize(&mut buf).unwrap();
+        assert_eq!(out, order());
+
+        let json: serde_json::Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(json["id"], 7);
+        assert_eq!(json["items"][1], "cake");
+    }
+
+    #[test]
+    fn tap_serialize_continues_the_pipeline() {
+        fn count(o: Order, extra: usize) -> usize {
+            o.items.len() + extra
+        }
+        let mut buf = Vec::new();
+        assert_eq!(order().tap_serialize(&mut buf).unwrap().pipe(count)(1), 3);
+        assert!(!buf.is_empty());
+    }
+
+    #[test]
+    fn non_string_map_keys_are_an_error() {
+        use std::collections::BTreeMap;
+        let map = BTreeMap::from([((1, 2), "pair")]);
+        assert!(map.pipe_serialize().is_err());
+    }
+
+    #[test]
+    fn serialize_then_deserialize_roundtrips() {
+        let bytes = order().pipe_serialize().unwrap();
+        assert_eq!(bytes.pipe_deserialize::<Order>().unwrap(), order());
+
+        let text = order().pipe_serialize_to_string().unwrap();
+        assert_eq!(text.pipe_deserialize_json::<Order>().unwrap(), order());
+    }
+
+    #[test]
+    fn deserialized_value_continues_the_pipeline() {
+        fn first_item(o: Order, fallback: &str) -> String {
+            o.items.into_iter().next().unwrap_or_else(|| fallback.into())
+        }
+        let raw = br#"{"id":1,"items":[]}"#;
+        assert_eq!(raw.pipe_deserialize::<Order>().unwrap().pipe(first_item)("none"), "none");
+    }
+
+    #[test]
+    fn deserialize_reports_mismatched_input() {
+        assert!(br#"{"id":"seven","items":[]}"#.pipe_deserialize::<Order>().is_err());
+        assert!("not json".pipe_deserialize_json::<Order>().is_err());
+    }
+
+    #[test]
+    fn deserializer_pipe_accepts_any_deserializer() {
+        let value = serde_json::to_value(order()).unwrap();
+        assert_eq!(DeserializerPipe(value).pipe_deserialize::<Order>().unwrap(), order());
+    }
+}
+
+// ============================================================================================
+// Idempotency tests
+// ============================================================================================
+
+#[cfg(all(feature = "serde", feature = "std", feature = "0", feature = "1"))]
+mod idempotency_tests {
+    use pipei::{IdempotencyResult, IdempotencyStore, PipeIdempotency};
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    #[test]
+    fn duplicate_key_replays_without_calling_f() {
+        let calls = Cell::new(0);
+        let create_order = |customer: &str, qty: u32| {
+            calls.set(calls.get() + 1);
+            (format!("order-{}", calls.get()), customer.len() as u32 * qty)
+        };
+        let key = |_: &&str, _: &u32| "req-1";
+        let mut store: HashMap<String, Vec<u8>> = HashMap::new();
+
+        let first = "ada".pipe_with_idempotency(&mut store, key, create_order)(2).unwrap();
+        assert_eq!(first, ("order-1".to_string(), 6));
+        assert_eq!(calls.get(), 1);
+
+        let second = "ada".pipe_with_idempotency(&mut store, key, create_order)(2).unwrap();
+        assert_eq!(second, first);
+        assert_eq!(calls.get(), 1);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn distinct_keys_each_run_f() {
+        fn add(x: u32, y: u32) -> u32 {
+            x + y
+        }
+        let key = |x: &u32, y: &u32| format!("{x}+{y}");
+        let mut store: HashMap<String, Vec<u8>> = HashMap::new();
+        assert_eq!(1.pipe_with_idempotency(&mut store, key, add)(2).unwrap(), 3);
+        assert_eq!(2.pipe_with_idempotency(&mut store, key, add)(2).unwrap(), 4);
+        assert_eq!(store["1+2"], b"3");
+    }
+
+    #[test]
+    fn stored_result_of_wrong_type_is_an_error() {
+        let mut store: HashMap<String, Vec<u8>> = HashMap::new();
+        store.store("k", b"\"text\"");
+        let r = 1u32.pipe_with_idempotency(&mut store, |_: &u32| "k", |x: u32| x)();
+        assert!(r.is_err());
+    }
+
+    #[test]
+    fn check_and_store_keeps_the_first_result() {
+        let mut store: HashMap<String, Vec<u8>> = HashMap::new();
+        assert_eq!(store.check_and_store("k", b"1"), IdempotencyResult::Fresh);
+        assert_eq!(store.check_and_store("k", b"2"), IdempotencyResult::Duplicate(b"1".to_vec()));
+    }
+}
+
+// ============================================================================================
+// Log tests
+// ============================================================================================
+
+/// A `log` backend that records messages per thread, so parallel tests see only their own.
+#[cfg(feature = "log")]
+mod log_capture {
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+    use std::cell::RefCell;
+    use std::sync::Once;
+
+    thread_local! {
+        static LEVEL: RefCell<LevelFilter> = const { RefCell::new(LevelFilter::Trace) };
+        static RECORDS: RefCell<Vec<(Level, String)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct Capture;
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            LEVEL.with(|l| metadata.level() <= *l.borrow())
+        }
+        fn log(&self, record: &Record) {
+            if self.enabled(record.metadata()) {
+                RECORDS.with(|r| r.borrow_mut().push((record.level(), record.args().to_string())));
+            }
+        }
+        fn flush(&self) {}
+    }
+
+    /// Installs the backend and sets this thread's level, clearing its records.
+    pub fn init(level: LevelFilter) {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            log::set_logger(&Capture).unwrap();
+            log::set_max_level(LevelFilter::Trace);
+        });
+        LEVEL.with(|l| *l.borrow_mut() = level);
+        RECORDS.with(|r| r.borrow_mut().clear());
+    }
+
+    /// Returns the records logged on this thread since `init`.
+    pub fn records() -> Vec<(Level, String)> {
+        RECORDS.with(|r| r.borrow().clone())
+    }
+}
+
+#[cfg(all(feature = "log", feature = "serde", feature = "std", feature = "0", feature = "1"))]
+mod structured_log_tests {
+    use super::log_capture;
+    use super::*;
+    use log::{Level, LevelFilter};
+    use pipei::TapStructuredLog;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn logged_json() -> Vec<(Level, Value)> {
+        log_capture::records().into_iter().map(|(level, msg)| (level, serde_json::from_str(&msg).unwrap())).collect()
+    }
+
+    #[test]
+    fn structured_log_adds_the_value_field() {
+        log_capture::init(LevelFilter::Trace);
+        let fields = HashMap::from([("stage".to_string(), json!("parse")), ("attempt".to_string(), json!(2))]);
+        let out = vec![1, 2].tap_with_structured_log(Level::Warn, fields);
+        assert_eq!(out, [1, 2]);
+        assert_eq!(logged_json(), [(Level::Warn, json!({"stage": "parse", "attempt": 2, "value": "[1, 2]"}))]);
+    }
+
+    #[test]
+    fn structured_event_adds_the_event_name() {
+        fn double(x: i32) -> i32 {
+            x * 2
+        }
+        log_capture::init(LevelFilter::Trace);
+        let fields = HashMap::from([("user".to_string(), json!("ada"))]);
+        assert_eq!(21.tap_structured_event("checkout", fields).pipe(double)(), 42);
+        assert_eq!(logged_json(), [(Level::Info, json!({"event": "checkout", "user": "ada", "value": "21"}))]);
+    }
+
+    #[test]
+    fn structured_log_skips_disabled_levels() {
+        log_capture::init(LevelFilter::Info);
+        assert_eq!("x".tap_with_structured_log(Level::Debug, HashMap::new()), "x");
+        assert!(log_capture::records().is_empty());
+    }
+}
+
+#[cfg(all(feature = "log", feature = "0", feature = "1"))]
+mod conditional_log_tests {
+    use super::log_capture;
+    use log::{Level, LevelFilter};
+    use pipei::TapConditionalLog;
+    use std::cell::Cell;
+
+    fn over_limit(x: &u32, limit: &u32) -> String {
+        format!("{x} is over {limit}")
+    }
+
+    fn run(pred_holds: bool, enabled: bool) -> (u32, Vec<(Level, String)>) {
+        log_capture::init(if enabled { LevelFilter::Warn } else { LevelFilter::Error });
+        let out = 120.tap_with_conditional_log(Level::Warn, |_| pred_holds, over_limit)(100);
+        (out, log_capture::records())
+    }
+
+    #[test]
+    fn logs_when_predicate_holds_and_level_enabled() {
+        assert_eq!(run(true, true), (120, vec![(Level::Warn, "120 is over 100".to_string())]));
+    }
+
+    #[test]
+    fn skips_when_predicate_fails() {
+        assert_eq!(run(false, true), (120, vec![]));
+    }
+
+    #[test]
+    fn skips_when_level_disabled() {
+        assert_eq!(run(true, false), (120, vec![]));
+    }
+
+    #[test]
+    fn skips_when_both_fail() {
+        assert_eq!(run(false, false), (120, vec![]));
+    }
+
+    #[test]
+    fn disabled_level_formats_nothing() {
+        log_capture::init(LevelFilter::Off);
+        let formatted = Cell::new(false);
+        let fmt = |x: &u32| {
+            formatted.set(true);
+            x.to_string()
+        };
+        assert_eq!(7.tap_with_conditional_log(Level::Error, |_| true, fmt)(), 7);
+        assert!(!formatted.get());
+    }
+}
+
+// ============================================================================================
+// Compression tests
+// ============================================================================================
+
+#[cfg(all(feature = "flate2", feature = "0", feature = "1"))]
+mod compression_tests {
+    use pipei::{Pipe, PipeGzip};
+
+    const TEXT: &[u8] = b"the quick brown fox jumps over the lazy dog";
+
+    #[test]
+    fn compress_then_decompress_roundtrips() {
+        let compressed = TEXT.pipe_compress_gzip().unwrap();
+        assert_ne!(compressed, TEXT);
+        assert_eq!(compressed.tap_decompress_gzip().unwrap(), TEXT);
+    }
+
+    #[test]
+    fn compressible_input_gets_smaller() {
+        let input = TEXT.repeat(100);
+        let compressed = input.pipe_compress_gzip().unwrap();
+        assert!(compressed.len() < input.len() / 10, "{} of {} bytes", compressed.len(), input.len());
+    }
+
+    #[test]
+    fn pipe_with_gzip_passes_compressed_bytes_to_f() {
+        fn sizes(compressed: Vec<u8>, original: usize) -> (usize, Vec<u8>) {
+            (original, compressed.tap_decompress_gzip().unwrap())
+        }
+        let input = TEXT.repeat(10);
+        let (n, restored) = input.pipe_with_gzip(sizes)(input.len()).unwrap();
+        assert_eq!(n, input.len());
+        assert_eq!(restored, input);
+    }
+
+    #[test]
+    fn compressed_bytes_continue_the_pipeline() {
+        fn tag(blob: Vec<u8>, prefix: u8) -> Vec<u8> {
+            let mut out = vec![prefix];
+            out.extend(blob);
+            out
+        }
+        let stored = TEXT.pipe_compress_gzip().unwrap().pipe(tag)(7);
+        assert_eq!(stored[0], 7);
+        assert_eq!(stored[1..].tap_decompress_gzip().unwrap(), TEXT);
+    }
+
+    #[test]
+    fn invalid_input_fails_to_decompress() {
+        assert!(TEXT.tap_decompress_gzip().is_err());
+    }
+}
+
+// ============================================================================================
+// Metrics tests
+// ============================================================================================
+
+#[cfg(all(feature = "metrics", feature = "0", feature = "1"))]
+mod metrics_tests {
+    use super::*;
+    use metrics_exporter_prometheus::PrometheusBuilder;
+    use pipei::TapMetrics;
+
+    /// Runs `f` with a local Prometheus recorder and returns the rendered metrics.
+    fn render(f: impl FnOnce()) -> String {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, f);
+        handle.render()
+    }
+
+    #[test]
+    fn counter_increments_once_per_tap() {
+        fn double(x: u32) -> u32 {
+            x * 2
+        }
+        let out = render(|| {
+            for i in 0..3 {
+                assert_eq!(i.tap_with_counter("jobs_total", &[("queue", "fast")]).pipe(double)(), i * 2);
+            }
+            "x".tap_with_counter("jobs_total", &[("queue", "slow")]);
+        });
+        assert!(out.contains("jobs_total{queue=\"fast\"} 3"), "{out}");
+        assert!(out.contains("jobs_total{queue=\"slow\"} 1"), "{out}");
+    }
+
+    #[test]
+    fn counter_accepts_runtime_names_and_no_labels() {
+        let name = String::from("dynamic_total");
+        let out = render(|| {
+            7.tap_with_counter(name, Vec::<metrics::Label>::new());
+        });
+        assert!(out.contains("dynamic_total 1"), "{out}");
+    }
+
+    #[test]
+    fn histogram_records_value_fn() {
+        let out = render(|| {
+            let batch = vec![1, 2, 3].tap_with_histogram("batch_size", |b| b.len() as f64);
+            assert_eq!(batch, [1, 2, 3]);
+            vec![1].tap_with_histogram("batch_size", |b| b.len() as f64);
+        });
+        assert!(out.contains("batch_size_sum 4"), "{out}");
+        assert!(out.contains("batch_size_count 2"), "{out}");
+    }
+}
+
+// ============================================================================================
+// OpenTelemetry tests
+// ============================================================================================
+
+#[cfg(all(feature = "opentelemetry", feature = "0", feature = "1"))]
+mod otel_tests {
+    use opentelemetry::trace::{SpanId, Status, TraceContextExt, TracerProvider};
+    use opentelemetry::Context;
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracer, SdkTracerProvider, SpanData};
+    use pipei::PipeOtelSpan;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn setup() -> (SdkTracerProvider, SdkTracer, InMemorySpanExporter) {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+        let tracer = provider.tracer("pipei-tests");
+        (provider, tracer, exporter)
+    }
+
+    fn span<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+        spans.iter().find(|s| s.name == name).unwrap()
+    }
+
+    #[test]
+    fn span_is_named_and_ok() {
+        fn add(x: i32, y: i32) -> i32 {
+            x + y
+        }
+        let (_provider, tracer, exporter) = setup();
+        assert_eq!(1.pipe_with_otel_span(&tracer, "add", add)(2), 3);
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].name, "add");
+        assert_eq!(spans[0].status, Status::Ok);
+        assert_eq!(spans[0].parent_span_id, SpanId::INVALID);
+    }
+
+    #[test]
+    fn nested_stage_is_a_child_span() {
+        let (_provider, tracer, exporter) = setup();
+        let outer = |x: u32, k: u32| x.pipe_with_otel_span(&tracer, "inner", |y: u32| y * k)() + 1;
+        assert_eq!(5.pipe_with_otel_span(&tracer, "outer", outer)(2), 11);
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let (outer, inner) = (span(&spans, "outer"), span(&spans, "inner"));
+        assert_eq!(inner.parent_span_id, outer.span_context.span_id());
+        assert_eq!(inner.span_context.trace_id(), outer.span_context.trace_id());
+    }
+
+    #[test]
+    fn stage_sees_its_span_as_current() {
+        let (_provider, tracer, exporter) = setup();
+        let ids = "req".pipe_with_otel_span(&tracer, "handle", |_: &str| {
+            let cx = Context::current();
+            let sc = cx.span().span_context().clone();
+            (sc.trace_id(), sc.span_id())
+        })();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let handle = span(&spans, "handle");
+        assert_eq!(ids, (handle.span_context.trace_id(), handle.span_context.span_id()));
+        assert!(!Context::current().has_active_span());
+    }
+
+    #[test]
+    fn panicking_stage_marks_span_as_error() {
+        let (_provider, tracer, exporter) = setup();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            0.pipe_with_otel_span(&tracer, "divide", |x: i32, y: i32| x / y)(0)
+        }));
+        assert!(result.is_err());
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert!(matches!(span(&spans, "divide").status, Status::Error { .. }));
+    }
+}
+
+// ============================================================================================
+// Async stream tests
+// ============================================================================================
+
+#[cfg(all(feature = "futures", feature = "0", feature = "1"))]
+mod async_stream_tests {
+    use futures::stream::{self, StreamExt};
+    use pipei::PipeAsyncStream;
+    use std::cell::Cell;
+    use std::pin::pin;
+    use std::time::Duration;
+
+    async fn async_double(x: i32, delay: Option<Duration>) -> i32 {
+        tokio::time::sleep(delay.unwrap_or(Duration::from_millis(1))).await;
+        x * 2
+    }
+
+    #[tokio::test]
+    async fn maps_async_function_over_stream() {
+        let out: Vec<i32> = stream::iter(vec![1, 2, 3]).pipe_async_stream(async_double)(None).collect().await;
+        assert_eq!(out, [2, 4, 6]);
+    }
+
+    #[tokio::test]
+    async fn shares_arguments_across_items() {
+        let delay = Some(Duration::from_millis(2));
+        let out: Vec<i32> = stream::iter(0..4).pipe_async_stream(async_double)(delay).collect().await;
+        assert_eq!(out, [0, 2, 4, 6]);
+    }
+
+    #[tokio::test]
+    async fn items_are_mapped_lazily() {
+        let calls = Cell::new(0);
+        let count = |x: i32| {
+            calls.set(calls.get() + 1);
+            async move { x }
+        };
+        let mut mapped = pin!(stream::iter(1..=3).pipe_async_stream(count)());
+        assert_eq!(calls.get(), 0);
+        assert_eq!(mapped.next().await, Some(1));
+        assert_eq!(calls.get(), 1);
+    }
+}
+
+// ============================================================================================
+// JSON Patch tests
+// ============================================================================================
+
+#[cfg(all(feature = "json-patch", feature = "0", feature = "1"))]
+mod json_patch_tests {
+    use super::*;
+    use pipei::TapJsonPatch;
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[derive(Debug, Clone, PartialEq, Serialize)]
+    struct Account {
+        owner: String,
+        balance: i64,
+        tags: Vec<String>,
+    }
+
+    fn deposit(mut a: Account, amount: i64) -> Account {
+        a.balance += amount;
+        a
+    }
+
+    fn tag(mut a: Account, t: &str) -> Account {
+        a.tags.push(t.into());
+        a
+    }
+
+    fn account() -> Account {
+        Account { owner: "ada".into(), balance: 10, tags: vec![] }
+    }
+
+    #[test]
+    fn records_the_patch_for_each_stage() {
+        let start = account();
+        let mut log = Vec::new();
+        let after_deposit = start.clone().pipe(deposit)(5).tap_with_json_patch(&start, &mut log).unwrap();
+        let end = after_deposit.clone().pipe(tag)("vip").tap_with_json_patch(&after_deposit, &mut log).unwrap();
+
+        assert_eq!(end.balance, 15);
+        assert_eq!(serde_json::to_value(&log[0]).unwrap(), json!([{"op": "replace", "path": "/balance", "value": 15}]));
+        assert_eq!(serde_json::to_value(&log[1]).unwrap(), json!([{"op": "add", "path": "/tags/0", "value": "vip"}]));
+    }
+
+    #[test]
+    fn replaying_the_log_reproduces_the_value() {
+        let start = account();
+        let mut log = Vec::new();
+        let a = start.clone().pipe(deposit)(-3).tap_with_json_patch(&start, &mut log).unwrap();
+        let b = a.clone().pipe(tag)("frozen").tap_with_json_patch(&a, &mut log).unwrap();
+
+        let mut doc = serde_json::to_value(&start).unwrap();
+        for patch in &log {
+            json_patch::patch(&mut doc, patch).unwrap();
+        }
+        assert_eq!(doc, serde_json::to_value(&b).unwrap());
+    }
+
+    #[test]
+    fn unchanged_value_logs_an_empty_patch() {
+        let mut log = Vec::new();
+        let same = account().tap_with_json_patch(&account(), &mut log).unwrap();
+        assert_eq!(same, account());
+        assert!(log[0].is_empty());
+    }
+}